@@ -0,0 +1,379 @@
+use std::{collections::HashSet, fmt::Debug, hash::BuildHasher};
+
+use petgraph::{
+    graph::{EdgeIndex, NodeIndex},
+    Graph, Undirected,
+};
+
+use crate::{
+    compute_tree_decomposition_of_components, find_connected_components_of_induced_subgraph,
+    SpanningTreeConstructionMethod,
+};
+
+/// The maximum size of the parts a balanced separator splits the graph into relative to the number
+/// of vertices of the graph.
+///
+/// Half Every part contains at most half of the vertices
+///
+/// TwoThirds Every part contains at most two thirds of the vertices
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeparatorBalance {
+    Half,
+    TwoThirds,
+}
+
+impl SeparatorBalance {
+    /// Returns true if a part with part_size vertices is small enough for a graph with
+    /// number_of_vertices vertices.
+    fn is_balanced(&self, part_size: usize, number_of_vertices: usize) -> bool {
+        match self {
+            SeparatorBalance::Half => 2 * part_size <= number_of_vertices,
+            SeparatorBalance::TwoThirds => 3 * part_size <= 2 * number_of_vertices,
+        }
+    }
+}
+
+/// The part of the tree decomposition a separator is taken from.
+///
+/// Bag The vertices of the bag
+///
+/// Edge The adhesion of the edge, i.e. the intersection of the bags at its endpoints
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeparatorLocation {
+    Bag(NodeIndex),
+    Edge(EdgeIndex),
+}
+
+/// A separator of the graph taken from a tree decomposition.
+///
+/// location The bag or edge of the tree decomposition the separator is taken from
+///
+/// separator The vertices of the separator
+///
+/// parts The connected components of the graph after removing the separator
+#[derive(Debug, Clone)]
+pub struct BalancedSeparator<S> {
+    pub location: SeparatorLocation,
+    pub separator: HashSet<NodeIndex, S>,
+    pub parts: Vec<Vec<NodeIndex>>,
+}
+
+/// A partition of the vertices of the graph into parts and a separator such that there is no edge
+/// between different parts.
+///
+/// parts The parts of the partition, some of which might be empty if the graph is small
+///
+/// separator The vertices that aren't contained in any part
+#[derive(Debug, Clone)]
+pub struct GraphPartition<S> {
+    pub parts: Vec<Vec<NodeIndex>>,
+    pub separator: HashSet<NodeIndex, S>,
+}
+
+/// Returns the adhesions of the tree decomposition, i.e. for each edge the intersection of the bags
+/// at its endpoints. Each adhesion separates the vertices that only occur on one side of the edge
+/// from the vertices that only occur on the other side.
+pub fn find_adhesions<O, S: Default + BuildHasher + Clone>(
+    tree_decomposition: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+) -> Vec<(EdgeIndex, HashSet<NodeIndex, S>)> {
+    tree_decomposition
+        .edge_indices()
+        .map(|edge| {
+            let (first_bag, second_bag) = tree_decomposition
+                .edge_endpoints(edge)
+                .expect("Edge should exist in the tree decomposition");
+            let adhesion = tree_decomposition[first_bag]
+                .intersection(&tree_decomposition[second_bag])
+                .cloned()
+                .collect();
+            (edge, adhesion)
+        })
+        .collect()
+}
+
+/// Finds the smallest separator among the adhesions and bags of the tree decomposition whose removal
+/// splits the graph into parts that are small enough for the balance. Ties are broken by the size of
+/// the biggest part.
+///
+/// For a tree decomposition of the graph some bag always splits the graph into parts of at most half
+/// of the vertices, so None is only returned if the tree decomposition has no bags.
+pub fn find_balanced_separator<N, E, O, S: Default + BuildHasher + Clone>(
+    graph: &Graph<N, E, Undirected>,
+    tree_decomposition: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    balance: SeparatorBalance,
+) -> Option<BalancedSeparator<S>> {
+    let vertices: HashSet<NodeIndex, S> = graph.node_indices().collect();
+    find_balanced_separator_of_vertices(graph, tree_decomposition, &vertices, balance)
+}
+
+/// Partitions the vertices of the graph into the given number of parts using nested dissection: A
+/// balanced separator is found using [find_balanced_separator], the components that remain after
+/// removing it are distributed on two sides proportional to the number of parts each side should
+/// be split into and both sides are partitioned recursively using the tree decomposition
+/// restricted to them.
+///
+/// Panics: Panics if number_of_parts is zero or if the tree decomposition isn't a tree
+/// decomposition of the graph.
+pub fn partition_graph<N, E, O, S: Default + BuildHasher + Clone>(
+    graph: &Graph<N, E, Undirected>,
+    tree_decomposition: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    number_of_parts: usize,
+    balance: SeparatorBalance,
+) -> GraphPartition<S> {
+    assert!(
+        number_of_parts > 0,
+        "number_of_parts should be at least one"
+    );
+
+    let mut partition = GraphPartition {
+        parts: Vec::with_capacity(number_of_parts),
+        separator: Default::default(),
+    };
+    let mut stack: Vec<(HashSet<NodeIndex, S>, usize)> =
+        vec![(graph.node_indices().collect(), number_of_parts)];
+
+    while let Some((vertices, number_of_parts)) = stack.pop() {
+        if number_of_parts == 1 {
+            let mut part: Vec<NodeIndex> = vertices.into_iter().collect();
+            part.sort();
+            partition.parts.push(part);
+            continue;
+        }
+        if vertices.is_empty() {
+            partition
+                .parts
+                .extend((0..number_of_parts).map(|_| Vec::new()));
+            continue;
+        }
+
+        let balanced_separator =
+            find_balanced_separator_of_vertices(graph, tree_decomposition, &vertices, balance)
+                .expect("The tree decomposition should contain the vertices of the graph");
+        partition
+            .separator
+            .extend(balanced_separator.separator.iter().cloned());
+
+        // Distribute the components on both sides, biggest component first, to the side that is
+        // less filled relative to the number of parts it should be split into
+        let first_number_of_parts = number_of_parts.div_ceil(2);
+        let second_number_of_parts = number_of_parts / 2;
+        let mut components = balanced_separator.parts;
+        components.sort_by_key(|component| std::cmp::Reverse(component.len()));
+        let mut first_side: HashSet<NodeIndex, S> = Default::default();
+        let mut second_side: HashSet<NodeIndex, S> = Default::default();
+        for component in components {
+            if first_side.len() * second_number_of_parts
+                <= second_side.len() * first_number_of_parts
+            {
+                first_side.extend(component);
+            } else {
+                second_side.extend(component);
+            }
+        }
+
+        stack.push((second_side, second_number_of_parts));
+        stack.push((first_side, first_number_of_parts));
+    }
+
+    partition
+}
+
+/// Partitions the vertices of the graph into the given number of parts using [partition_graph] on
+/// tree decompositions of the components computed by
+/// [compute_treewidth_upper_bound][crate::compute_treewidth_upper_bound].
+///
+/// Panics: Panics if number_of_parts is zero. If check_tree_decomposition_bool is true, panics if a
+/// tree decomposition is incorrect.
+pub fn compute_graph_partition<
+    N: Clone,
+    E: Clone,
+    O: Clone + Ord + Default + Debug,
+    S: Default + BuildHasher + Clone,
+>(
+    graph: &Graph<N, E, Undirected>,
+    edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    treewidth_computation_method: SpanningTreeConstructionMethod,
+    check_tree_decomposition_bool: bool,
+    clique_bound: Option<usize>,
+    number_of_parts: usize,
+    balance: SeparatorBalance,
+) -> GraphPartition<S> {
+    let tree_decomposition = compute_tree_decomposition_of_components(
+        graph,
+        edge_weight_function,
+        treewidth_computation_method,
+        check_tree_decomposition_bool,
+        clique_bound,
+    );
+
+    partition_graph(graph, &tree_decomposition, number_of_parts, balance)
+}
+
+/// Finds the balanced separator (see [find_balanced_separator]) of the subgraph induced by the
+/// vertices, using the bags and adhesions of the tree decomposition restricted to the vertices.
+fn find_balanced_separator_of_vertices<N, E, O, S: Default + BuildHasher + Clone>(
+    graph: &Graph<N, E, Undirected>,
+    tree_decomposition: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    vertices: &HashSet<NodeIndex, S>,
+    balance: SeparatorBalance,
+) -> Option<BalancedSeparator<S>> {
+    let candidates = find_adhesions(tree_decomposition)
+        .into_iter()
+        .map(|(edge, adhesion)| (SeparatorLocation::Edge(edge), adhesion))
+        .chain(
+            tree_decomposition
+                .node_indices()
+                .map(|bag| (SeparatorLocation::Bag(bag), tree_decomposition[bag].clone())),
+        );
+
+    let mut best_separator: Option<(BalancedSeparator<S>, usize)> = None;
+    for (location, candidate) in candidates {
+        let separator: HashSet<NodeIndex, S> = candidate
+            .into_iter()
+            .filter(|vertex| vertices.contains(vertex))
+            .collect();
+        if let Some((best_separator, _)) = &best_separator {
+            if separator.len() > best_separator.separator.len() {
+                continue;
+            }
+        }
+
+        let remaining_vertices: HashSet<NodeIndex, S> = vertices
+            .iter()
+            .filter(|vertex| !separator.contains(vertex))
+            .cloned()
+            .collect();
+        let parts =
+            find_connected_components_of_induced_subgraph::<N, E, S>(graph, &remaining_vertices);
+        let biggest_part_size = parts.iter().map(|part| part.len()).max().unwrap_or(0);
+        if !balance.is_balanced(biggest_part_size, vertices.len()) {
+            continue;
+        }
+
+        let is_better = match &best_separator {
+            None => true,
+            Some((best_separator, best_biggest_part_size)) => {
+                (separator.len(), biggest_part_size)
+                    < (best_separator.separator.len(), *best_biggest_part_size)
+            }
+        };
+        if is_better {
+            best_separator = Some((
+                BalancedSeparator {
+                    location,
+                    separator,
+                    parts,
+                },
+                biggest_part_size,
+            ));
+        }
+    }
+
+    best_separator.map(|(best_separator, _)| best_separator)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::RandomState;
+
+    use super::*;
+    use crate::{compute_treewidth_upper_bound, negative_intersection};
+
+    /// Returns true if no edge of the graph connects vertices of different parts
+    fn parts_are_separated<N, E>(
+        graph: &Graph<N, E, Undirected>,
+        parts: &[Vec<NodeIndex>],
+    ) -> bool {
+        let mut part_of_vertex = vec![None; graph.node_count()];
+        for (index, part) in parts.iter().enumerate() {
+            for vertex in part {
+                part_of_vertex[vertex.index()] = Some(index);
+            }
+        }
+        graph.edge_indices().all(|edge| {
+            let (first_vertex, second_vertex) = graph
+                .edge_endpoints(edge)
+                .expect("Edge should exist in the graph");
+            match (
+                part_of_vertex[first_vertex.index()],
+                part_of_vertex[second_vertex.index()],
+            ) {
+                (Some(first_part), Some(second_part)) => first_part == second_part,
+                _ => true,
+            }
+        })
+    }
+
+    #[test]
+    fn test_balanced_separators() {
+        for i in 1..3 {
+            let graph = crate::tests::setup_test_graph(i).graph;
+            for computation_method in crate::tests::COMPUTATION_METHODS {
+                let (_, tree_decomposition, _, _, _, _) =
+                    compute_treewidth_upper_bound::<_, _, _, RandomState>(
+                        &graph,
+                        negative_intersection,
+                        computation_method,
+                        false,
+                        None,
+                    );
+
+                for (edge, adhesion) in find_adhesions(&tree_decomposition) {
+                    let (first_bag, second_bag) = tree_decomposition
+                        .edge_endpoints(edge)
+                        .expect("Edge should exist in the tree decomposition");
+                    assert!(adhesion.is_subset(&tree_decomposition[first_bag]));
+                    assert!(adhesion.is_subset(&tree_decomposition[second_bag]));
+                }
+
+                for balance in [SeparatorBalance::Half, SeparatorBalance::TwoThirds] {
+                    let balanced_separator =
+                        find_balanced_separator(&graph, &tree_decomposition, balance)
+                            .expect("A bag should be a balanced separator");
+                    assert!(parts_are_separated(&graph, &balanced_separator.parts));
+                    assert_eq!(
+                        balanced_separator.separator.len()
+                            + balanced_separator
+                                .parts
+                                .iter()
+                                .map(|part| part.len())
+                                .sum::<usize>(),
+                        graph.node_count()
+                    );
+                    for part in balanced_separator.parts.iter() {
+                        assert!(balance.is_balanced(part.len(), graph.node_count()));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_graph_partitions() {
+        for (graph, _) in crate::tests::setup_test_graphs_and_partial_k_trees(46, 5, 3, 60, 30) {
+            for number_of_parts in 1..6 {
+                let partition = compute_graph_partition::<_, _, _, RandomState>(
+                    &graph,
+                    negative_intersection,
+                    SpanningTreeConstructionMethod::MSTAndUseTreeStructure,
+                    true,
+                    None,
+                    number_of_parts,
+                    SeparatorBalance::Half,
+                );
+                assert_eq!(partition.parts.len(), number_of_parts);
+                assert!(parts_are_separated(&graph, &partition.parts));
+
+                let mut vertices: Vec<NodeIndex> = partition
+                    .parts
+                    .iter()
+                    .flatten()
+                    .chain(partition.separator.iter())
+                    .cloned()
+                    .collect();
+                vertices.sort();
+                assert_eq!(vertices, graph.node_indices().collect::<Vec<_>>());
+            }
+        }
+    }
+}
@@ -0,0 +1,197 @@
+use std::{
+    collections::{HashMap, HashSet},
+    hash::BuildHasher,
+};
+
+use petgraph::{graph::NodeIndex, Graph, Undirected};
+
+/// Computes an ordering of the vertices of the graph using maximum cardinality search. In each
+/// step the vertex that has the most already visited neighbours is visited next (ties are broken
+/// by the smallest node index).
+///
+/// Returns the vertices in the order they were visited. If the graph is chordal, the reverse of
+/// this ordering is a perfect elimination ordering.
+pub fn maximum_cardinality_search<N, E>(graph: &Graph<N, E, Undirected>) -> Vec<NodeIndex> {
+    let number_of_vertices = graph.node_count();
+    // Number of visited neighbours for each vertex, None if the vertex is visited already
+    let mut weights: Vec<Option<usize>> = vec![Some(0); number_of_vertices];
+    // Buckets of unvisited vertices by their weight. Vertices might also be contained in buckets
+    // for smaller weights, these entries are skipped
+    let mut buckets: Vec<Vec<NodeIndex>> = vec![graph.node_indices().rev().collect()];
+    let mut maximum_weight = 0;
+    let mut ordering = Vec::with_capacity(number_of_vertices);
+
+    while ordering.len() < number_of_vertices {
+        let vertex = match buckets[maximum_weight].pop() {
+            Some(vertex) if weights[vertex.index()] == Some(maximum_weight) => vertex,
+            Some(_) => continue,
+            None => {
+                maximum_weight -= 1;
+                continue;
+            }
+        };
+
+        weights[vertex.index()] = None;
+        ordering.push(vertex);
+        for neighbour in graph.neighbors(vertex) {
+            if let Some(weight) = weights[neighbour.index()].as_mut() {
+                *weight += 1;
+                if buckets.len() <= *weight {
+                    buckets.push(Vec::new());
+                }
+                buckets[*weight].push(neighbour);
+                maximum_weight = maximum_weight.max(*weight);
+            }
+        }
+    }
+
+    ordering
+}
+
+/// Returns a perfect elimination ordering of the graph if the graph is chordal and None otherwise.
+///
+/// The ordering is the reverse of the ordering computed by [maximum_cardinality_search]. It is a
+/// perfect elimination ordering iff the graph is chordal, which is checked as described by Tarjan
+/// and Yannakakis: For each vertex, its neighbours that come later in the ordering except for the
+/// first one of them need to be adjacent to this first one.
+pub fn find_perfect_elimination_ordering<N, E, S: Default + BuildHasher>(
+    graph: &Graph<N, E, Undirected>,
+) -> Option<Vec<NodeIndex>> {
+    let mut ordering = maximum_cardinality_search(graph);
+    ordering.reverse();
+
+    let positions: HashMap<NodeIndex, usize, S> = ordering
+        .iter()
+        .enumerate()
+        .map(|(position, vertex)| (*vertex, position))
+        .collect();
+    for (position, vertex) in ordering.iter().enumerate() {
+        let later_neighbours: HashSet<NodeIndex, S> = graph
+            .neighbors(*vertex)
+            .filter(|neighbour| positions[neighbour] > position)
+            .collect();
+        if let Some(first_later_neighbour) = later_neighbours
+            .iter()
+            .min_by_key(|neighbour| positions[*neighbour])
+        {
+            let neighbours_of_first_later_neighbour: HashSet<NodeIndex, S> =
+                graph.neighbors(*first_later_neighbour).collect();
+            if later_neighbours.iter().any(|neighbour| {
+                neighbour != first_later_neighbour
+                    && !neighbours_of_first_later_neighbour.contains(neighbour)
+            }) {
+                return None;
+            }
+        }
+    }
+
+    Some(ordering)
+}
+
+/// Returns true if the graph is chordal, i.e. every cycle of length at least four has a chord.
+pub fn is_chordal<N, E, S: Default + BuildHasher>(graph: &Graph<N, E, Undirected>) -> bool {
+    find_perfect_elimination_ordering::<N, E, S>(graph).is_some()
+}
+
+/// Returns the maximal cliques of a chordal graph given a perfect elimination ordering of it.
+///
+/// Each maximal clique consists of a vertex and its neighbours that come later in the ordering.
+/// The clique of a vertex v is not maximal iff there is a vertex u whose first later neighbour is v
+/// and whose clique is bigger by one.
+pub(crate) fn find_maximal_cliques_of_chordal_graph<N, E, S: Default + BuildHasher>(
+    graph: &Graph<N, E, Undirected>,
+    perfect_elimination_ordering: &[NodeIndex],
+) -> Vec<Vec<NodeIndex>> {
+    let positions: HashMap<NodeIndex, usize, S> = perfect_elimination_ordering
+        .iter()
+        .enumerate()
+        .map(|(position, vertex)| (*vertex, position))
+        .collect();
+
+    let mut cliques: Vec<Vec<NodeIndex>> = Vec::with_capacity(perfect_elimination_ordering.len());
+    let mut is_maximal = vec![true; perfect_elimination_ordering.len()];
+    for (position, vertex) in perfect_elimination_ordering.iter().enumerate() {
+        let mut clique: Vec<NodeIndex> = graph
+            .neighbors(*vertex)
+            .filter(|neighbour| positions[neighbour] > position)
+            .collect();
+        if let Some(first_later_neighbour_position) =
+            clique.iter().map(|neighbour| positions[neighbour]).min()
+        {
+            if clique.len()
+                == graph
+                    .neighbors(perfect_elimination_ordering[first_later_neighbour_position])
+                    .filter(|neighbour| positions[neighbour] > first_later_neighbour_position)
+                    .count()
+                    + 1
+            {
+                is_maximal[first_later_neighbour_position] = false;
+            }
+        }
+        clique.push(*vertex);
+        cliques.push(clique);
+    }
+
+    cliques
+        .into_iter()
+        .zip(is_maximal)
+        .filter_map(|(clique, is_maximal)| is_maximal.then_some(clique))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::RandomState;
+
+    use itertools::Itertools;
+
+    use super::*;
+
+    #[test]
+    fn test_k_trees_are_chordal() {
+        for k in 1..6 {
+            let graph = crate::generate_k_tree(k, 30).expect("k should be smaller than n");
+            let perfect_elimination_ordering =
+                find_perfect_elimination_ordering::<_, _, RandomState>(&graph)
+                    .expect("k-trees should be chordal");
+            assert_eq!(
+                crate::find_induced_width_of_elimination_ordering::<_, _, RandomState>(
+                    &graph,
+                    &perfect_elimination_ordering
+                ),
+                k
+            );
+
+            let maximal_cliques = find_maximal_cliques_of_chordal_graph::<_, _, RandomState>(
+                &graph,
+                &perfect_elimination_ordering,
+            );
+            let expected_maximal_cliques: Vec<Vec<NodeIndex>> =
+                crate::find_maximal_cliques::<Vec<_>, _, RandomState>(&graph)
+                    .map(|clique| clique.into_iter().sorted().collect())
+                    .sorted()
+                    .collect();
+            assert_eq!(
+                maximal_cliques
+                    .into_iter()
+                    .map(|clique| clique.into_iter().sorted().collect::<Vec<_>>())
+                    .sorted()
+                    .collect::<Vec<_>>(),
+                expected_maximal_cliques
+            );
+        }
+    }
+
+    #[test]
+    fn test_cycles_are_not_chordal() {
+        for n in 3..8 {
+            let mut graph: Graph<i32, i32, Undirected> = Graph::new_undirected();
+            let vertices: Vec<NodeIndex> = (0..n).map(|i| graph.add_node(i)).collect();
+            for i in 0..n as usize {
+                graph.add_edge(vertices[i], vertices[(i + 1) % n as usize], 0);
+            }
+
+            assert_eq!(is_chordal::<_, _, RandomState>(&graph), n == 3);
+        }
+    }
+}
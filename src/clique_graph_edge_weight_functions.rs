@@ -2,6 +2,27 @@ use petgraph::graph::NodeIndex;
 use rand::Rng;
 use std::{collections::HashSet, hash::BuildHasher};
 
+use crate::{ObjectiveValue, VertexWeights};
+
+/// Numeric value of an edge weight. Used when candidates are compared by their distance to the best
+/// candidate, see [RestrictedCandidateList::Alpha][crate::RestrictedCandidateList].
+pub trait EdgeWeightValue {
+    fn edge_weight_value(&self) -> f64;
+}
+
+impl EdgeWeightValue for i32 {
+    fn edge_weight_value(&self) -> f64 {
+        *self as f64
+    }
+}
+
+/// Tuples are compared lexicographically, so their value is the value of the first entry.
+impl<A: EdgeWeightValue, B> EdgeWeightValue for (A, B) {
+    fn edge_weight_value(&self) -> f64 {
+        self.0.edge_weight_value()
+    }
+}
+
 /// Returns 0.
 pub fn constant<S>(_: &HashSet<NodeIndex, S>, _: &HashSet<NodeIndex, S>) -> i32 {
     0
@@ -86,3 +107,31 @@ pub fn least_difference_then_negative_intersection<S: BuildHasher + Default>(
         negative_intersection(first_vertex, second_vertex),
     )
 }
+
+/// Returns the negative of the weighted size of the intersection (see
+/// [VertexWeights::weighted_size]).
+pub fn negative_weighted_intersection<S: BuildHasher>(
+    first_vertex: &HashSet<NodeIndex, S>,
+    second_vertex: &HashSet<NodeIndex, S>,
+    vertex_weights: &VertexWeights,
+) -> ObjectiveValue {
+    ObjectiveValue(-vertex_weights.weighted_size(first_vertex.intersection(second_vertex)))
+}
+
+/// Returns the weighted size of the union (see [VertexWeights::weighted_size]).
+pub fn weighted_union<S: BuildHasher>(
+    first_vertex: &HashSet<NodeIndex, S>,
+    second_vertex: &HashSet<NodeIndex, S>,
+    vertex_weights: &VertexWeights,
+) -> ObjectiveValue {
+    ObjectiveValue(vertex_weights.weighted_size(first_vertex.union(second_vertex)))
+}
+
+/// Returns the weighted size of the symmetric difference (see [VertexWeights::weighted_size]).
+pub fn weighted_least_difference<S: BuildHasher>(
+    first_vertex: &HashSet<NodeIndex, S>,
+    second_vertex: &HashSet<NodeIndex, S>,
+    vertex_weights: &VertexWeights,
+) -> ObjectiveValue {
+    ObjectiveValue(vertex_weights.weighted_size(first_vertex.symmetric_difference(second_vertex)))
+}
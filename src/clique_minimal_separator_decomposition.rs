@@ -0,0 +1,372 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Debug,
+    hash::BuildHasher,
+};
+
+use petgraph::{graph::NodeIndex, Graph, Undirected};
+
+use crate::{
+    check_tree_decomposition, compute_treewidth_upper_bound,
+    elimination_ordering::{adjacency_map, eliminate_vertex},
+    find_induced_subgraph, find_width_of_tree_decomposition,
+    minimal_triangulation::find_minimal_elimination_ordering_with_labels,
+    SpanningTreeConstructionMethod,
+};
+
+/// An atom of the clique minimal separator decomposition together with the clique separator that
+/// was used to split it off the rest of the graph (None for the last atom).
+struct Atom<S> {
+    vertices: Vec<NodeIndex>,
+    separator: Option<HashSet<NodeIndex, S>>,
+}
+
+/// Computes the atoms of the clique minimal separator decomposition of the graph, i.e. the maximal
+/// connected subgraphs that have no clique minimal separator. The treewidth of the graph is the
+/// maximum treewidth of its atoms.
+///
+/// Uses the algorithm by Berry, Pogorelcnik and Simonet: A minimal elimination ordering computed by
+/// MCS-M (see [find_minimal_elimination_ordering][crate::find_minimal_elimination_ordering]) also
+/// yields the minimal separators of the graph. These are the neighbours at the time of elimination
+/// of the vertices whose weight, when visited by MCS-M, is not bigger than the weight of the vertex
+/// visited before. Going through the elimination ordering, each such separator that is a clique
+/// splits off the component containing the vertex (together with the separator) as an atom.
+///
+/// Components of a graph that is not connected are split off as well (the empty set is a clique
+/// separator), so every atom is connected.
+pub fn find_clique_minimal_separator_decomposition<N, E, S: Default + BuildHasher + Clone>(
+    graph: &Graph<N, E, Undirected>,
+) -> Vec<Vec<NodeIndex>> {
+    find_atoms::<N, E, S>(graph)
+        .into_iter()
+        .map(|atom| atom.vertices)
+        .collect()
+}
+
+/// Computes an upper bound for the treewidth by running [compute_treewidth_upper_bound] on each
+/// atom of the clique minimal separator decomposition (see
+/// [find_clique_minimal_separator_decomposition]) and gluing the tree decompositions of the atoms
+/// together.
+///
+/// The atoms are glued along the clique separators: The separator is contained in a bag of the
+/// tree decomposition of the atom that was split off and in a bag of the tree decomposition of the
+/// rest of the graph, so connecting these two bags keeps the properties of a tree decomposition.
+/// The weights of these edges are given by the edge weight function.
+///
+/// Returns the tree decomposition of the whole graph and its width. The graph doesn't need to be
+/// connected.
+///
+/// Panics: If check_tree_decomposition_bool is true, panics if the resulting tree decomposition is
+/// incorrect.
+pub fn compute_treewidth_upper_bound_using_clique_separators<
+    N: Clone,
+    E: Clone,
+    O: Clone + Ord + Default + Debug,
+    S: Default + BuildHasher + Clone,
+>(
+    graph: &Graph<N, E, Undirected>,
+    edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    treewidth_computation_method: SpanningTreeConstructionMethod,
+    check_tree_decomposition_bool: bool,
+    clique_bound: Option<usize>,
+) -> (Graph<HashSet<NodeIndex, S>, O, Undirected>, usize) {
+    let atoms = find_atoms::<N, E, S>(graph);
+
+    let mut tree_decomposition: Graph<HashSet<NodeIndex, S>, O, Undirected> =
+        Graph::new_undirected();
+    // For each atom the vertices of its tree decomposition in the tree decomposition of the graph
+    let mut bags_of_atoms: Vec<Vec<NodeIndex>> = Vec::with_capacity(atoms.len());
+    for atom in atoms.iter() {
+        let atom_vertices: HashSet<NodeIndex, S> = atom.vertices.iter().cloned().collect();
+        // Vertices of the subgraph are mapped to the vertices of the graph by their index
+        let subgraph: Graph<NodeIndex, E, Undirected> =
+            find_induced_subgraph(graph, &atom_vertices);
+        let (_, atom_tree_decomposition, _, _, _, _) = compute_treewidth_upper_bound(
+            &subgraph,
+            edge_weight_function,
+            treewidth_computation_method,
+            false,
+            clique_bound,
+        );
+
+        let mut node_index_map: HashMap<NodeIndex, NodeIndex, S> = Default::default();
+        for (bag_index, bag) in
+            atom_tree_decomposition
+                .node_indices()
+                .zip(atom_tree_decomposition.node_weights().map(|bag| {
+                    bag.iter()
+                        .map(|vertex| {
+                            *subgraph
+                                .node_weight(*vertex)
+                                .expect("Vertices of the subgraph should map to the graph")
+                        })
+                        .collect::<HashSet<NodeIndex, S>>()
+                }))
+        {
+            node_index_map.insert(bag_index, tree_decomposition.add_node(bag));
+        }
+        for edge in atom_tree_decomposition.edge_indices() {
+            let (first_bag, second_bag) = atom_tree_decomposition
+                .edge_endpoints(edge)
+                .expect("Edge should exist in the tree decomposition");
+            tree_decomposition.add_edge(
+                node_index_map[&first_bag],
+                node_index_map[&second_bag],
+                atom_tree_decomposition
+                    .edge_weight(edge)
+                    .expect("Edge should have a weight")
+                    .clone(),
+            );
+        }
+        bags_of_atoms.push(
+            atom_tree_decomposition
+                .node_indices()
+                .map(|bag_index| node_index_map[&bag_index])
+                .collect(),
+        );
+    }
+
+    // The tree decompositions of the atoms after an atom form a tree decomposition of the rest of
+    // the graph at the time the atom was split off
+    for (atom_number, atom) in atoms.iter().enumerate() {
+        if let Some(separator) = &atom.separator {
+            let atom_bag = find_bag_containing_separator(
+                &tree_decomposition,
+                bags_of_atoms[atom_number].iter(),
+                separator,
+            );
+            let rest_bag = find_bag_containing_separator(
+                &tree_decomposition,
+                bags_of_atoms[atom_number + 1..].iter().flatten(),
+                separator,
+            );
+            let weight = edge_weight_function(
+                tree_decomposition
+                    .node_weight(atom_bag)
+                    .expect("Bags should exist in the tree decomposition"),
+                tree_decomposition
+                    .node_weight(rest_bag)
+                    .expect("Bags should exist in the tree decomposition"),
+            );
+            tree_decomposition.add_edge(atom_bag, rest_bag, weight);
+        }
+    }
+
+    if check_tree_decomposition_bool {
+        assert!(
+            check_tree_decomposition(graph, &tree_decomposition, &None, &None),
+            "Tree decomposition is invalid. See previous print statements for reason."
+        );
+    }
+    let treewidth = find_width_of_tree_decomposition(&tree_decomposition);
+
+    (tree_decomposition, treewidth)
+}
+
+/// Computes the atoms of the clique minimal separator decomposition in the order they are split off
+/// together with the separators (see [find_clique_minimal_separator_decomposition]).
+fn find_atoms<N, E, S: Default + BuildHasher + Clone>(
+    graph: &Graph<N, E, Undirected>,
+) -> Vec<Atom<S>> {
+    let (elimination_ordering, labels) = find_minimal_elimination_ordering_with_labels(graph);
+
+    let mut adjacency: HashMap<NodeIndex, HashSet<NodeIndex, S>, S> = adjacency_map(graph);
+    let later_neighbours: Vec<HashSet<NodeIndex, S>> = elimination_ordering
+        .iter()
+        .map(|vertex| eliminate_vertex(&mut adjacency, *vertex, true))
+        .collect();
+
+    let mut remaining_vertices: HashSet<NodeIndex, S> = graph.node_indices().collect();
+    let mut atoms = Vec::new();
+    for (position, (vertex, separator)) in elimination_ordering
+        .iter()
+        .zip(later_neighbours)
+        .enumerate()
+    {
+        // The vertex visited by MCS-M before this one comes next in the elimination ordering
+        let is_generator = elimination_ordering
+            .get(position + 1)
+            .is_some_and(|previous_vertex| {
+                labels[vertex.index()] <= labels[previous_vertex.index()]
+            });
+        if !is_generator || !remaining_vertices.contains(vertex) || !is_clique(graph, &separator) {
+            continue;
+        }
+
+        let component =
+            find_component_avoiding_separator(graph, *vertex, &remaining_vertices, &separator);
+        if component.len() + separator.len() == remaining_vertices.len() {
+            continue;
+        }
+
+        for component_vertex in component.iter() {
+            remaining_vertices.remove(component_vertex);
+        }
+        let mut atom_vertices: Vec<NodeIndex> = component;
+        atom_vertices.extend(separator.iter().cloned());
+        atoms.push(Atom {
+            vertices: atom_vertices,
+            separator: Some(separator),
+        });
+    }
+    atoms.push(Atom {
+        vertices: remaining_vertices.into_iter().collect(),
+        separator: None,
+    });
+
+    atoms
+}
+
+/// Returns the first of the given bags of the tree decomposition that contains the separator.
+///
+/// Panics: Panics if none of the bags contains the separator.
+fn find_bag_containing_separator<'a, O, S: BuildHasher>(
+    tree_decomposition: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    mut bags: impl Iterator<Item = &'a NodeIndex>,
+    separator: &HashSet<NodeIndex, S>,
+) -> NodeIndex {
+    *bags
+        .find(|bag| {
+            separator.is_subset(
+                tree_decomposition
+                    .node_weight(**bag)
+                    .expect("Bags should exist in the tree decomposition"),
+            )
+        })
+        .expect("Clique separator should be contained in a bag")
+}
+
+/// Returns true if the vertices are pairwise adjacent in the graph.
+fn is_clique<N, E, S>(graph: &Graph<N, E, Undirected>, vertices: &HashSet<NodeIndex, S>) -> bool {
+    vertices.iter().all(|first_vertex| {
+        vertices.iter().all(|second_vertex| {
+            first_vertex == second_vertex || graph.contains_edge(*first_vertex, *second_vertex)
+        })
+    })
+}
+
+/// Returns the vertices of the connected component containing the start vertex of the subgraph
+/// induced by the given vertices without the separator.
+fn find_component_avoiding_separator<N, E, S: Default + BuildHasher>(
+    graph: &Graph<N, E, Undirected>,
+    start_vertex: NodeIndex,
+    vertices: &HashSet<NodeIndex, S>,
+    separator: &HashSet<NodeIndex, S>,
+) -> Vec<NodeIndex> {
+    let mut component = vec![start_vertex];
+    let mut visited: HashSet<NodeIndex, S> = Default::default();
+    visited.insert(start_vertex);
+    let mut stack = vec![start_vertex];
+    while let Some(vertex) = stack.pop() {
+        for neighbour in graph.neighbors(vertex) {
+            if vertices.contains(&neighbour)
+                && !separator.contains(&neighbour)
+                && visited.insert(neighbour)
+            {
+                component.push(neighbour);
+                stack.push(neighbour);
+            }
+        }
+    }
+    component
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::RandomState;
+
+    use itertools::Itertools;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::negative_intersection;
+
+    /// Returns two cycles of length 5 sharing an edge and a triangle attached to a vertex.
+    fn setup_graph_with_clique_separators() -> Graph<i32, i32, Undirected> {
+        let mut graph: Graph<i32, i32, Undirected> = Graph::new_undirected();
+        let vertices: Vec<NodeIndex> = (0..10).map(|i| graph.add_node(i)).collect();
+        for (first_vertex, second_vertex) in [
+            (0, 1),
+            (1, 2),
+            (2, 3),
+            (3, 4),
+            (4, 0),
+            (0, 5),
+            (5, 6),
+            (6, 7),
+            (7, 1),
+            (7, 8),
+            (8, 9),
+            (9, 7),
+        ] {
+            graph.add_edge(vertices[first_vertex], vertices[second_vertex], 0);
+        }
+        graph
+    }
+
+    #[test]
+    fn test_clique_minimal_separator_decomposition() {
+        let graph = setup_graph_with_clique_separators();
+        let atoms: Vec<Vec<usize>> =
+            find_clique_minimal_separator_decomposition::<_, _, RandomState>(&graph)
+                .into_iter()
+                .map(|atom| {
+                    atom.into_iter()
+                        .map(|vertex| vertex.index())
+                        .sorted()
+                        .collect()
+                })
+                .sorted()
+                .collect();
+
+        assert_eq!(
+            atoms,
+            vec![vec![0, 1, 2, 3, 4], vec![0, 1, 5, 6, 7], vec![7, 8, 9]]
+        );
+    }
+
+    #[test]
+    fn test_treewidth_heuristic_using_clique_separators() {
+        let graph = setup_graph_with_clique_separators();
+        for computation_method in crate::tests::COMPUTATION_METHODS {
+            let (_, computed_treewidth) =
+                compute_treewidth_upper_bound_using_clique_separators::<_, _, _, RandomState>(
+                    &graph,
+                    negative_intersection,
+                    computation_method,
+                    true,
+                    None,
+                );
+            assert_eq!(computed_treewidth, 2);
+        }
+
+        // Checks that the glued tree decompositions are valid
+        let mut rng = StdRng::seed_from_u64(37);
+        for _ in 0..10 {
+            let graph = crate::generate_partial_k_tree(3, 25, 30, &mut rng)
+                .expect("k should be smaller than n");
+            compute_treewidth_upper_bound_using_clique_separators::<_, _, _, RandomState>(
+                &graph,
+                negative_intersection,
+                SpanningTreeConstructionMethod::FillWhilstMST,
+                true,
+                None,
+            );
+        }
+
+        for i in 0..3 {
+            let test_graph = crate::tests::setup_test_graph(i);
+            for computation_method in crate::tests::COMPUTATION_METHODS {
+                let (_, computed_treewidth) =
+                    compute_treewidth_upper_bound_using_clique_separators::<_, _, _, RandomState>(
+                        &test_graph.graph,
+                        negative_intersection,
+                        computation_method,
+                        true,
+                        None,
+                    );
+                assert!(computed_treewidth >= test_graph.treewidth);
+            }
+        }
+    }
+}
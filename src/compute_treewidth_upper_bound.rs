@@ -26,6 +26,9 @@ use petgraph::{graph::NodeIndex, Graph, Undirected};
 /// edge heuristic trying to speed up filling up by using the tree structure
 ///
 /// FillWhilstMSTBagSize Fills bags while constructing a spanning tree of the clique graph trying to minimize the maximum bag size in each step
+///
+/// FillWhilstMSTBeam Generalization of FillWhilstMSTBagSize that keeps the given number of best
+/// partial spanning trees in each step (beam search) instead of only the best one
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum SpanningTreeConstructionMethod {
     MSTAndFill,
@@ -34,8 +37,79 @@ pub enum SpanningTreeConstructionMethod {
     FillWhilstMSTEdgeUpdate,
     FillWhilstMSTTree,
     FillWhilstMSTBagSize,
+    FillWhilstMSTBeam(usize),
+}
+
+/// Error returned by [compute_treewidth_upper_bound_bounded] (and the fill routines) if a bag of
+/// the tree decomposition contains more than width_bound + 1 vertices.
+///
+/// width_bound The width bound that was exceeded
+///
+/// step The step in which the width bound was exceeded. For the FillWhilstMST methods this is the
+/// number of vertices of the clique graph in the spanning tree, for MSTAndFill the number of pairs
+/// of bags that were checked and for MSTAndUseTreeStructure the number of vertices of the graph
+/// whose bags were filled up. It is 0 if a clique is already bigger than the bound
+///
+/// clique The (sorted) clique that caused the overflow. For the FillWhilstMST methods this is the
+/// clique that was added to the spanning tree last, otherwise the clique whose bag overflowed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WidthBoundExceeded {
+    pub width_bound: usize,
+    pub step: usize,
+    pub clique: Vec<NodeIndex>,
+}
+
+impl WidthBoundExceeded {
+    pub(crate) fn new<'a>(
+        width_bound: usize,
+        step: usize,
+        clique: impl IntoIterator<Item = &'a NodeIndex>,
+    ) -> Self {
+        let mut clique: Vec<NodeIndex> = clique.into_iter().cloned().collect();
+        clique.sort();
+        WidthBoundExceeded {
+            width_bound,
+            step,
+            clique,
+        }
+    }
 }
 
+impl std::fmt::Display for WidthBoundExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "width bound {} exceeded in step {} caused by clique {:?}",
+            self.width_bound, self.step, self.clique
+        )
+    }
+}
+
+impl std::error::Error for WidthBoundExceeded {}
+
+/// Objective that FillWhilstMSTBagSize minimizes instead of the size of the biggest bag.
+///
+/// VertexWeighted The vertex weighted objective (see [VertexWeightedObjective]), ties are broken by
+/// the width
+///
+/// Lexicographic The width followed by the tie breakers (see [TreeDecompositionObjective])
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum BagSizeObjective<'a> {
+    VertexWeighted(&'a VertexWeights, VertexWeightedObjective),
+    Lexicographic(&'a TreeDecompositionObjective),
+}
+
+/// Clique graph, tree decomposition, tree decomposition before filling up the bags, predecessor
+/// map, clique graph map and width as returned by [compute_treewidth_upper_bound].
+pub type CliqueGraphTreeDecomposition<O, S> = (
+    Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    Option<Graph<HashSet<NodeIndex, S>, O, Undirected>>,
+    Option<HashMap<NodeIndex, (NodeIndex, usize), S>>,
+    Option<HashMap<NodeIndex, HashSet<NodeIndex, S>, S>>,
+    usize,
+);
+
 /// Computes an upper bound for the treewidth using the clique graph operator.
 ///
 /// Does this by computing the clique graph of the given graph and then constructing a spanning
@@ -51,6 +125,13 @@ pub enum SpanningTreeConstructionMethod {
 /// size of the cliques instead. The resulting graph is the intersection graph of the set of all
 /// cliques that are maximal or have a size of clique_bound
 ///
+/// If the graph is chordal (which is checked using [maximum cardinality
+/// search][crate::maximum_cardinality_search]), a clique tree is an optimal tree decomposition. In
+/// that case the clique tree (a maximum weight spanning tree of the clique graph weighted by the
+/// sizes of the intersections) is returned directly without filling up any bags, regardless of
+/// the spanning tree construction method and the clique bound. Use
+/// [compute_treewidth_upper_bound_with_optimality] to find out whether this shortcut was used.
+///
 /// Can also check the tree decomposition for correctness after computation which will up to double
 /// the running time. If so, will panic if the tree decomposition if incorrect returning the vertices
 /// and path that is faulty.
@@ -73,21 +154,209 @@ pub fn compute_treewidth_upper_bound<
     Option<HashMap<NodeIndex, HashSet<NodeIndex, S>, S>>,
     usize,
 ) {
+    compute_treewidth_upper_bound_with_optimality(
+        graph,
+        edge_weight_function,
+        treewidth_computation_method,
+        check_tree_decomposition_bool,
+        clique_bound,
+    )
+    .0
+}
+
+/// Computes an upper bound for the treewidth like [compute_treewidth_upper_bound].
+///
+/// Returns the same as [compute_treewidth_upper_bound] and whether the width is optimal, which is
+/// the case iff the graph is chordal and the clique tree was returned directly.
+pub fn compute_treewidth_upper_bound_with_optimality<
+    N: Clone,
+    E: Clone,
+    O: Clone + Ord + Default + Debug,
+    S: Default + BuildHasher + Clone,
+>(
+    graph: &Graph<N, E, Undirected>,
+    edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    treewidth_computation_method: SpanningTreeConstructionMethod,
+    check_tree_decomposition_bool: bool,
+    clique_bound: Option<usize>,
+) -> (CliqueGraphTreeDecomposition<O, S>, bool) {
+    compute_treewidth_upper_bound_with_optional_width_bound(
+        graph,
+        edge_weight_function,
+        treewidth_computation_method,
+        check_tree_decomposition_bool,
+        clique_bound,
+        None,
+        None,
+    )
+    .expect("There is no width bound that could be exceeded")
+}
+
+/// Computes an upper bound for the treewidth like [compute_treewidth_upper_bound] but stops as soon
+/// as a bag of the tree decomposition contains more than width_bound + 1 vertices. This answers the
+/// question whether the heuristic finds a tree decomposition of width at most width_bound without
+/// running the computation to completion.
+///
+/// Returns the same as [compute_treewidth_upper_bound] or, if the width bound is exceeded, the step
+/// and the clique that caused the overflow (see [WidthBoundExceeded]).
+pub fn compute_treewidth_upper_bound_bounded<
+    N: Clone,
+    E: Clone,
+    O: Clone + Ord + Default + Debug,
+    S: Default + BuildHasher + Clone,
+>(
+    graph: &Graph<N, E, Undirected>,
+    edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    treewidth_computation_method: SpanningTreeConstructionMethod,
+    width_bound: usize,
+    check_tree_decomposition_bool: bool,
+    clique_bound: Option<usize>,
+) -> Result<CliqueGraphTreeDecomposition<O, S>, WidthBoundExceeded> {
+    compute_treewidth_upper_bound_with_optional_width_bound(
+        graph,
+        edge_weight_function,
+        treewidth_computation_method,
+        check_tree_decomposition_bool,
+        clique_bound,
+        Some(width_bound),
+        None,
+    )
+    .map(|(result, _)| result)
+}
+
+/// Computes an upper bound for the treewidth like [compute_treewidth_upper_bound] except that
+/// FillWhilstMSTBagSize minimizes the given objective, i.e. it uses the tie breakers of the
+/// objective to choose between extensions of the spanning tree that lead to the same width. The
+/// other spanning tree construction methods are not affected by the objective.
+///
+/// The objective is evaluated on the whole partial tree decomposition for every candidate extension
+/// (see [fill_bags_while_generating_mst_least_cost]). In particular the number of fill edges is
+/// recomputed from all bags each time, which makes FillWhilstMSTBagSize considerably slower than
+/// with the plain width.
+///
+/// Returns the same as [compute_treewidth_upper_bound] and the metrics of the tree decomposition
+/// (see [find_metrics_of_tree_decomposition]).
+pub fn compute_treewidth_upper_bound_with_objective<
+    N: Clone,
+    E: Clone,
+    O: Clone + Ord + Default + Debug,
+    S: Default + BuildHasher + Clone,
+>(
+    graph: &Graph<N, E, Undirected>,
+    edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    treewidth_computation_method: SpanningTreeConstructionMethod,
+    objective: &TreeDecompositionObjective,
+    check_tree_decomposition_bool: bool,
+    clique_bound: Option<usize>,
+) -> (CliqueGraphTreeDecomposition<O, S>, TreeDecompositionMetrics) {
+    let (result, _) = compute_treewidth_upper_bound_with_optional_width_bound(
+        graph,
+        edge_weight_function,
+        treewidth_computation_method,
+        check_tree_decomposition_bool,
+        clique_bound,
+        None,
+        Some(BagSizeObjective::Lexicographic(objective)),
+    )
+    .expect("There is no width bound that could be exceeded");
+    let metrics = find_metrics_of_tree_decomposition(graph, &result.1);
+
+    (result, metrics)
+}
+
+/// Computes an upper bound for the treewidth like [compute_treewidth_upper_bound]. If a width bound
+/// is given, stops as soon as it is exceeded (see [compute_treewidth_upper_bound_bounded]). If a
+/// bag size objective is given, FillWhilstMSTBagSize minimizes it instead of the width (see
+/// [fill_bags_while_generating_mst_least_cost]).
+///
+/// Returns the same as [compute_treewidth_upper_bound_with_optimality].
+pub(crate) fn compute_treewidth_upper_bound_with_optional_width_bound<
+    N: Clone,
+    E: Clone,
+    O: Clone + Ord + Default + Debug,
+    S: Default + BuildHasher + Clone,
+>(
+    graph: &Graph<N, E, Undirected>,
+    edge_weight_function: impl Fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    treewidth_computation_method: SpanningTreeConstructionMethod,
+    check_tree_decomposition_bool: bool,
+    clique_bound: Option<usize>,
+    width_bound: Option<usize>,
+    bag_size_objective: Option<BagSizeObjective>,
+) -> Result<(CliqueGraphTreeDecomposition<O, S>, bool), WidthBoundExceeded> {
+    // For chordal graphs a clique tree is an optimal tree decomposition, so no bags need to be
+    // filled up
+    if let Some(perfect_elimination_ordering) = find_perfect_elimination_ordering::<N, E, S>(graph)
+    {
+        let cliques =
+            find_maximal_cliques_of_chordal_graph::<N, E, S>(graph, &perfect_elimination_ordering);
+        check_cliques_against_width_bound(&cliques, width_bound)?;
+
+        let clique_graph: Graph<HashSet<NodeIndex, S>, O, Undirected> =
+            construct_clique_graph(cliques, &edge_weight_function);
+        let clique_tree = find_clique_tree(&clique_graph);
+
+        if check_tree_decomposition_bool {
+            assert!(
+                check_tree_decomposition(graph, &clique_tree, &None, &None),
+                "Tree decomposition is invalid. See previous print statements for reason."
+            );
+        }
+        let treewidth = find_width_of_tree_decomposition(&clique_tree);
+
+        return Ok((
+            (
+                clique_graph,
+                clique_tree.clone(),
+                Some(clique_tree),
+                None,
+                None,
+                treewidth,
+            ),
+            true,
+        ));
+    }
+
     // Find cliques in initial graph
-    let cliques: Vec<Vec<_>> = if let Some(k) = clique_bound {
-        find_maximum_cliques_bounded::<Vec<_>, _, S>(graph, k)
-            // .sorted()
-            .collect()
-    } else {
-        find_maximal_cliques::<Vec<_>, _, S>(graph)
-            // .sorted()
-            .collect()
-    };
+    let cliques = find_cliques::<N, E, S>(graph, clique_bound);
     if cliques.len() > 3 {
         // DEBUG
         // println!("Number of cliques: {}", cliques.len());
     }
 
+    compute_treewidth_upper_bound_from_cliques(
+        graph,
+        cliques,
+        edge_weight_function,
+        treewidth_computation_method,
+        check_tree_decomposition_bool,
+        width_bound,
+        bag_size_objective,
+    )
+    .map(|result| (result, false))
+}
+
+/// Computes an upper bound for the treewidth like [compute_treewidth_upper_bound] but uses the given
+/// cliques as the vertices of the clique graph instead of finding the maximal cliques of the graph.
+/// The cliques need to cover all edges of the graph. See
+/// [compute_treewidth_upper_bound_with_optional_width_bound] for the width bound and the bag size
+/// objective.
+pub(crate) fn compute_treewidth_upper_bound_from_cliques<
+    N: Clone,
+    E: Clone,
+    O: Clone + Ord + Default + Debug,
+    S: Default + BuildHasher + Clone,
+>(
+    graph: &Graph<N, E, Undirected>,
+    cliques: Vec<Vec<NodeIndex>>,
+    edge_weight_function: impl Fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    treewidth_computation_method: SpanningTreeConstructionMethod,
+    check_tree_decomposition_bool: bool,
+    width_bound: Option<usize>,
+    bag_size_objective: Option<BagSizeObjective>,
+) -> Result<CliqueGraphTreeDecomposition<O, S>, WidthBoundExceeded> {
+    check_cliques_against_width_bound(&cliques, width_bound)?;
+
     let (
         clique_graph_tree_after_filling_up,
         clique_graph_map,
@@ -97,7 +366,7 @@ pub fn compute_treewidth_upper_bound<
     ) = match treewidth_computation_method {
         SpanningTreeConstructionMethod::MSTAndFill => {
             let clique_graph: Graph<_, _, _> =
-                construct_clique_graph(cliques, edge_weight_function);
+                construct_clique_graph(cliques, &edge_weight_function);
 
             let mut clique_graph_tree: Graph<
                 std::collections::HashSet<petgraph::prelude::NodeIndex, S>,
@@ -108,7 +377,7 @@ pub fn compute_treewidth_upper_bound<
             ));
             let clique_graph_tree_before_filling = clique_graph_tree.clone();
 
-            fill_bags_along_paths(&mut clique_graph_tree);
+            fill_bags_along_paths(&mut clique_graph_tree, width_bound)?;
 
             (
                 clique_graph_tree,
@@ -120,7 +389,7 @@ pub fn compute_treewidth_upper_bound<
         }
         SpanningTreeConstructionMethod::MSTAndUseTreeStructure => {
             let (clique_graph, clique_graph_map) =
-                construct_clique_graph_with_bags(cliques, edge_weight_function);
+                construct_clique_graph_with_bags(cliques, &edge_weight_function);
             // DEBUG
             // println!("Initial clique graph: {:?}", clique_graph);
 
@@ -148,8 +417,11 @@ pub fn compute_treewidth_upper_bound<
                 |a, b| a.eq(b)
             ));
 
-            let predecessor_map =
-                fill_bags_along_paths_using_structure(&mut clique_graph_tree, &clique_graph_map);
+            let predecessor_map = fill_bags_along_paths_using_structure(
+                &mut clique_graph_tree,
+                &clique_graph_map,
+                width_bound,
+            )?;
 
             (
                 clique_graph_tree,
@@ -161,7 +433,7 @@ pub fn compute_treewidth_upper_bound<
         }
         SpanningTreeConstructionMethod::FillWhilstMST => {
             let (clique_graph, clique_graph_map) =
-                construct_clique_graph_with_bags(cliques, edge_weight_function);
+                construct_clique_graph_with_bags(cliques, &edge_weight_function);
 
             let clique_graph_tree: Graph<
                 std::collections::HashSet<petgraph::prelude::NodeIndex, S>,
@@ -169,15 +441,16 @@ pub fn compute_treewidth_upper_bound<
                 petgraph::prelude::Undirected,
             > = fill_bags_while_generating_mst::<N, E, O, S>(
                 &clique_graph,
-                edge_weight_function,
+                &edge_weight_function,
                 clique_graph_map,
-            );
+                width_bound,
+            )?;
 
             (clique_graph_tree, None, None, None, clique_graph)
         }
         SpanningTreeConstructionMethod::FillWhilstMSTEdgeUpdate => {
             let (clique_graph, clique_graph_map) =
-                construct_clique_graph_with_bags(cliques, edge_weight_function);
+                construct_clique_graph_with_bags(cliques, &edge_weight_function);
 
             let clique_graph_tree: Graph<
                 std::collections::HashSet<petgraph::prelude::NodeIndex, S>,
@@ -185,15 +458,16 @@ pub fn compute_treewidth_upper_bound<
                 petgraph::prelude::Undirected,
             > = fill_bags_while_generating_mst_update_edges::<N, E, O, S>(
                 &clique_graph,
-                edge_weight_function,
+                &edge_weight_function,
                 clique_graph_map,
-            );
+                width_bound,
+            )?;
 
             (clique_graph_tree, None, None, None, clique_graph)
         }
         SpanningTreeConstructionMethod::FillWhilstMSTTree => {
             let (clique_graph, clique_graph_map) =
-                construct_clique_graph_with_bags(cliques, edge_weight_function);
+                construct_clique_graph_with_bags(cliques, &edge_weight_function);
 
             let clique_graph_tree: Graph<
                 std::collections::HashSet<petgraph::prelude::NodeIndex, S>,
@@ -201,24 +475,69 @@ pub fn compute_treewidth_upper_bound<
                 petgraph::prelude::Undirected,
             > = fill_bags_while_generating_mst_using_tree::<N, E, O, S>(
                 &clique_graph,
-                edge_weight_function,
+                &edge_weight_function,
                 clique_graph_map,
-            );
+                width_bound,
+            )?;
 
             (clique_graph_tree, None, None, None, clique_graph)
         }
         SpanningTreeConstructionMethod::FillWhilstMSTBagSize => {
             let (clique_graph, clique_graph_map) =
-                construct_clique_graph_with_bags(cliques, edge_weight_function);
+                construct_clique_graph_with_bags(cliques, &edge_weight_function);
+
+            let clique_graph_tree: Graph<
+                std::collections::HashSet<petgraph::prelude::NodeIndex, S>,
+                O,
+                petgraph::prelude::Undirected,
+            > = match bag_size_objective {
+                Some(BagSizeObjective::VertexWeighted(vertex_weights, objective)) => {
+                    fill_bags_while_generating_mst_least_cost::<N, E, O, S, _>(
+                        &clique_graph,
+                        clique_graph_map,
+                        |tree_decomposition| {
+                            (
+                                ObjectiveValue(
+                                    vertex_weights.evaluate(objective, tree_decomposition),
+                                ),
+                                find_width_of_tree_decomposition(tree_decomposition),
+                            )
+                        },
+                        width_bound,
+                    )?
+                }
+                // The metrics are not updated incrementally but evaluated on each candidate
+                Some(BagSizeObjective::Lexicographic(objective)) => {
+                    fill_bags_while_generating_mst_least_cost::<N, E, O, S, _>(
+                        &clique_graph,
+                        clique_graph_map,
+                        |tree_decomposition| objective.evaluate(graph, tree_decomposition),
+                        width_bound,
+                    )?
+                }
+                None => fill_bags_while_generating_mst_least_bag_size::<N, E, O, S>(
+                    &clique_graph,
+                    clique_graph_map,
+                    width_bound,
+                )?,
+            };
+
+            (clique_graph_tree, None, None, None, clique_graph)
+        }
+        SpanningTreeConstructionMethod::FillWhilstMSTBeam(beam_width) => {
+            let (clique_graph, clique_graph_map) =
+                construct_clique_graph_with_bags(cliques, &edge_weight_function);
 
             let clique_graph_tree: Graph<
                 std::collections::HashSet<petgraph::prelude::NodeIndex, S>,
                 O,
                 petgraph::prelude::Undirected,
-            > = fill_bags_while_generating_mst_least_bag_size::<N, E, O, S>(
+            > = fill_bags_while_generating_mst_beam::<O, S>(
                 &clique_graph,
                 clique_graph_map,
-            );
+                beam_width,
+                width_bound,
+            )?;
 
             (clique_graph_tree, None, None, None, clique_graph)
         }
@@ -237,16 +556,148 @@ pub fn compute_treewidth_upper_bound<
     }
     let treewidth = find_width_of_tree_decomposition(&clique_graph_tree_after_filling_up);
 
-    (
+    Ok((
         clique_graph,
         clique_graph_tree_after_filling_up,
         clique_graph_tree_before_filling,
         predecessor_map,
         clique_graph_map,
         treewidth,
+    ))
+}
+
+/// Returns an error if a width bound is given and one of the cliques has more than
+/// width_bound + 1 vertices.
+fn check_cliques_against_width_bound(
+    cliques: &[Vec<NodeIndex>],
+    width_bound: Option<usize>,
+) -> Result<(), WidthBoundExceeded> {
+    if let Some(width_bound) = width_bound {
+        if let Some(clique) = cliques.iter().find(|clique| clique.len() > width_bound + 1) {
+            return Err(WidthBoundExceeded::new(width_bound, 0, clique));
+        }
+    }
+    Ok(())
+}
+
+/// Returns a maximum weight spanning tree of the clique graph where the weight of an edge is the
+/// size of the intersection of its bags. The edge weights of the tree are taken from the clique
+/// graph. For chordal graphs this is a clique tree, i.e. a tree decomposition whose bags are the
+/// maximal cliques.
+fn find_clique_tree<O: Clone, S: Default + BuildHasher + Clone>(
+    clique_graph: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+) -> Graph<HashSet<NodeIndex, S>, O, Undirected> {
+    let intersection_clique_graph = clique_graph.map(
+        |_, bag| bag.clone(),
+        |edge, _| {
+            let (first_vertex, second_vertex) = clique_graph
+                .edge_endpoints(edge)
+                .expect("Edge should exist in the clique graph");
+            negative_intersection(
+                clique_graph
+                    .node_weight(first_vertex)
+                    .expect("Vertices in clique graph should have bags as weights"),
+                clique_graph
+                    .node_weight(second_vertex)
+                    .expect("Vertices in clique graph should have bags as weights"),
+            )
+        },
+    );
+    let clique_tree: Graph<HashSet<NodeIndex, S>, i32, Undirected> =
+        petgraph::data::FromElements::from_elements(petgraph::algo::min_spanning_tree(
+            &intersection_clique_graph,
+        ));
+
+    clique_tree.map(
+        |_, bag| bag.clone(),
+        |edge, _| {
+            let (first_vertex, second_vertex) = clique_tree
+                .edge_endpoints(edge)
+                .expect("Edge should exist in the clique tree");
+            clique_graph
+                .find_edge(first_vertex, second_vertex)
+                .and_then(|edge| clique_graph.edge_weight(edge))
+                .expect("Edges of the clique tree should be edges of the clique graph")
+                .clone()
+        },
     )
 }
 
+/// Finds the maximal cliques of the graph or, if a clique bound is given, the cliques that are
+/// maximal or have a size of clique_bound (see [find_maximum_cliques_bounded]).
+pub(crate) fn find_cliques<N, E, S: Default + BuildHasher + Clone>(
+    graph: &Graph<N, E, Undirected>,
+    clique_bound: Option<usize>,
+) -> Vec<Vec<NodeIndex>> {
+    if let Some(k) = clique_bound {
+        find_maximum_cliques_bounded::<Vec<_>, _, S>(graph, k)
+            // .sorted()
+            .collect()
+    } else {
+        find_maximal_cliques::<Vec<_>, _, S>(graph)
+            // .sorted()
+            .collect()
+    }
+}
+
+/// Computes a tree decomposition of each component of the graph using
+/// [compute_treewidth_upper_bound] and combines them into one forest of bags whose bags contain the
+/// vertices of the graph.
+///
+/// Panics: If check_tree_decomposition_bool is true, panics if a tree decomposition is incorrect.
+pub(crate) fn compute_tree_decomposition_of_components<
+    N: Clone,
+    E: Clone,
+    O: Clone + Ord + Default + Debug,
+    S: Default + BuildHasher + Clone,
+>(
+    graph: &Graph<N, E, Undirected>,
+    edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    treewidth_computation_method: SpanningTreeConstructionMethod,
+    check_tree_decomposition_bool: bool,
+    clique_bound: Option<usize>,
+) -> Graph<HashSet<NodeIndex, S>, O, Undirected> {
+    let mut tree_decomposition: Graph<HashSet<NodeIndex, S>, O, Undirected> =
+        Graph::new_undirected();
+    for component in find_connected_components::<HashSet<NodeIndex, S>, _, _, S>(graph) {
+        let subgraph = find_induced_subgraph(graph, &component);
+        let (_, component_tree_decomposition, _, _, _, _) = compute_treewidth_upper_bound(
+            &subgraph,
+            edge_weight_function,
+            treewidth_computation_method,
+            check_tree_decomposition_bool,
+            clique_bound,
+        );
+
+        let bags: Vec<NodeIndex> = component_tree_decomposition
+            .node_weights()
+            .map(|bag| {
+                tree_decomposition.add_node(
+                    bag.iter()
+                        .map(|vertex| {
+                            *subgraph
+                                .node_weight(*vertex)
+                                .expect("Vertices of the subgraph should map to the graph")
+                        })
+                        .collect(),
+                )
+            })
+            .collect();
+        for edge in component_tree_decomposition.edge_indices() {
+            let (first_bag, second_bag) = component_tree_decomposition
+                .edge_endpoints(edge)
+                .expect("Edge should exist in the tree decomposition");
+            tree_decomposition.add_edge(
+                bags[first_bag.index()],
+                bags[second_bag.index()],
+                component_tree_decomposition[edge].clone(),
+            );
+        }
+    }
+
+    tree_decomposition
+}
+
 /// Computes an upper bound for the treewidth returning the maximum [compute_treewidth_upper_bound] on the
 /// components
 pub fn compute_treewidth_upper_bound_not_connected<
@@ -290,25 +741,61 @@ mod tests {
     use super::*;
     use crate::tests::*;
 
+    type FxBuildHasher = std::hash::BuildHasherDefault<rustc_hash::FxHasher>;
+
+    /// Like [compute_treewidth_upper_bound_not_connected] but always fills up the bags with the
+    /// computation method instead of returning the clique tree of chordal components, so that the
+    /// chordal test graphs exercise the computation methods as well.
+    fn compute_treewidth_upper_bound_not_connected_by_filling<O: Clone + Ord + Default + Debug>(
+        graph: &Graph<i32, i32, Undirected>,
+        edge_weight_function: fn(
+            &HashSet<NodeIndex, FxBuildHasher>,
+            &HashSet<NodeIndex, FxBuildHasher>,
+        ) -> O,
+        treewidth_computation_method: SpanningTreeConstructionMethod,
+        check_tree_decomposition_bool: bool,
+    ) -> usize {
+        let components = find_connected_components::<Vec<_>, _, _, FxBuildHasher>(graph);
+        let mut computed_treewidth: usize = 0;
+
+        for component in components {
+            let mut subgraph = graph.clone();
+            subgraph.retain_nodes(|_, v| component.contains(&v));
+
+            let cliques = find_cliques::<_, _, FxBuildHasher>(&subgraph, None);
+            computed_treewidth = computed_treewidth.max(
+                compute_treewidth_upper_bound_from_cliques(
+                    &subgraph,
+                    cliques,
+                    edge_weight_function,
+                    treewidth_computation_method,
+                    check_tree_decomposition_bool,
+                    None,
+                    None,
+                )
+                .expect("There is no width bound that could be exceeded")
+                .5,
+            );
+        }
+
+        computed_treewidth
+    }
+
     #[test]
     fn test_treewidth_heuristic_check_tree_decomposition() {
         for i in 0..3 {
             let test_graph = setup_test_graph(i);
-            let _ = compute_treewidth_upper_bound_not_connected::<_, _, RandomState, _>(
-                &test_graph.graph,
-                constant,
+            for computation_method in [
                 SpanningTreeConstructionMethod::MSTAndUseTreeStructure,
-                true,
-                None,
-            );
-
-            let _ = compute_treewidth_upper_bound_not_connected::<_, _, RandomState, _>(
-                &test_graph.graph,
-                constant,
                 SpanningTreeConstructionMethod::MSTAndFill,
-                true,
-                None,
-            );
+            ] {
+                let _ = compute_treewidth_upper_bound_not_connected_by_filling(
+                    &test_graph.graph,
+                    constant,
+                    computation_method,
+                    true,
+                );
+            }
         }
     }
 
@@ -317,15 +804,12 @@ mod tests {
         for i in 0..3 {
             for computation_method in COMPUTATION_METHODS {
                 let test_graph = setup_test_graph(i);
-                let computed_treewidth =
-                    compute_treewidth_upper_bound_not_connected::<
-                        _,
-                        _,
-                        std::hash::BuildHasherDefault<rustc_hash::FxHasher>,
-                        _,
-                    >(
-                        &test_graph.graph, constant, computation_method, false, None
-                    );
+                let computed_treewidth = compute_treewidth_upper_bound_not_connected_by_filling(
+                    &test_graph.graph,
+                    constant,
+                    computation_method,
+                    false,
+                );
                 if !(i == 1
                     && (computation_method == SpanningTreeConstructionMethod::MSTAndFill
                         || computation_method
@@ -351,17 +835,11 @@ mod tests {
         for i in vec![0, 2] {
             for computation_method in COMPUTATION_METHODS {
                 let test_graph = setup_test_graph(i);
-                let computed_treewidth = compute_treewidth_upper_bound_not_connected::<
-                    _,
-                    _,
-                    std::hash::BuildHasherDefault<rustc_hash::FxHasher>,
-                    _,
-                >(
+                let computed_treewidth = compute_treewidth_upper_bound_not_connected_by_filling(
                     &test_graph.graph,
                     negative_intersection,
                     computation_method,
                     true,
-                    None,
                 );
                 if !(i == 1
                     && (computation_method == SpanningTreeConstructionMethod::MSTAndFill
@@ -378,6 +856,202 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_treewidth_heuristic_and_check_result_beam_search() {
+        for i in 0..3 {
+            for beam_width in [1, 3] {
+                let computation_method =
+                    SpanningTreeConstructionMethod::FillWhilstMSTBeam(beam_width);
+                let test_graph = setup_test_graph(i);
+                let computed_treewidth = compute_treewidth_upper_bound_not_connected_by_filling(
+                    &test_graph.graph,
+                    negative_intersection,
+                    computation_method,
+                    true,
+                );
+                assert_eq!(
+                    computed_treewidth, test_graph.treewidth,
+                    "computation method: {:?}. Test graph {:?}",
+                    computation_method, i
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_treewidth_heuristic_bounded() {
+        for i in 1..3 {
+            let test_graph = setup_test_graph(i);
+            for computation_method in [
+                SpanningTreeConstructionMethod::MSTAndFill,
+                SpanningTreeConstructionMethod::MSTAndUseTreeStructure,
+                SpanningTreeConstructionMethod::FillWhilstMST,
+                SpanningTreeConstructionMethod::FillWhilstMSTEdgeUpdate,
+                SpanningTreeConstructionMethod::FillWhilstMSTTree,
+                SpanningTreeConstructionMethod::FillWhilstMSTBagSize,
+                SpanningTreeConstructionMethod::FillWhilstMSTBeam(3),
+            ] {
+                let (_, _, _, _, _, computed_treewidth) = compute_treewidth_upper_bound::<
+                    _,
+                    _,
+                    _,
+                    std::hash::BuildHasherDefault<rustc_hash::FxHasher>,
+                >(
+                    &test_graph.graph,
+                    negative_intersection,
+                    computation_method,
+                    false,
+                    None,
+                );
+
+                let (_, _, _, _, _, bounded_treewidth) = compute_treewidth_upper_bound_bounded::<
+                    _,
+                    _,
+                    _,
+                    std::hash::BuildHasherDefault<rustc_hash::FxHasher>,
+                >(
+                    &test_graph.graph,
+                    negative_intersection,
+                    computation_method,
+                    computed_treewidth,
+                    true,
+                    None,
+                )
+                .expect("Width bound should not be exceeded");
+                assert_eq!(bounded_treewidth, computed_treewidth);
+
+                // The treewidth is a lower bound for the width of every tree decomposition
+                let width_bound_exceeded = compute_treewidth_upper_bound_bounded::<
+                    _,
+                    _,
+                    _,
+                    std::hash::BuildHasherDefault<rustc_hash::FxHasher>,
+                >(
+                    &test_graph.graph,
+                    negative_intersection,
+                    computation_method,
+                    test_graph.treewidth - 1,
+                    false,
+                    None,
+                )
+                .expect_err("Width bound should be exceeded");
+                assert_eq!(width_bound_exceeded.width_bound, test_graph.treewidth - 1);
+                assert!(!width_bound_exceeded.clique.is_empty());
+            }
+        }
+
+        // The 3x3 grid has treewidth 3 but all of its maximal cliques are edges, so the width bound
+        // can only be exceeded once the bags are filled up
+        let mut grid: Graph<i32, i32, Undirected> = Graph::new_undirected();
+        let vertices: Vec<NodeIndex> = (0..9).map(|_| grid.add_node(0)).collect();
+        for row in 0..3 {
+            for column in 0..3 {
+                if column < 2 {
+                    grid.add_edge(
+                        vertices[3 * row + column],
+                        vertices[3 * row + column + 1],
+                        0,
+                    );
+                }
+                if row < 2 {
+                    grid.add_edge(
+                        vertices[3 * row + column],
+                        vertices[3 * row + column + 3],
+                        0,
+                    );
+                }
+            }
+        }
+        for computation_method in [
+            SpanningTreeConstructionMethod::MSTAndFill,
+            SpanningTreeConstructionMethod::MSTAndUseTreeStructure,
+            SpanningTreeConstructionMethod::FillWhilstMST,
+            SpanningTreeConstructionMethod::FillWhilstMSTEdgeUpdate,
+            SpanningTreeConstructionMethod::FillWhilstMSTTree,
+            SpanningTreeConstructionMethod::FillWhilstMSTBagSize,
+            SpanningTreeConstructionMethod::FillWhilstMSTBeam(3),
+        ] {
+            let width_bound_exceeded = compute_treewidth_upper_bound_bounded::<
+                _,
+                _,
+                _,
+                std::hash::BuildHasherDefault<rustc_hash::FxHasher>,
+            >(
+                &grid,
+                negative_intersection,
+                computation_method,
+                2,
+                false,
+                None,
+            )
+            .expect_err("Width bound should be exceeded");
+            assert_eq!(width_bound_exceeded.width_bound, 2);
+            assert!(width_bound_exceeded.step > 0);
+            assert_eq!(width_bound_exceeded.clique.len(), 2);
+        }
+    }
+
+    #[test]
+    fn test_treewidth_heuristic_bounded_fails_on_big_clique() {
+        let test_graph = setup_test_graph(2);
+        let width_bound_exceeded = compute_treewidth_upper_bound_bounded::<_, _, _, RandomState>(
+            &test_graph.graph,
+            negative_intersection,
+            SpanningTreeConstructionMethod::FillWhilstMST,
+            0,
+            false,
+            None,
+        )
+        .expect_err("Width bound should be exceeded");
+        assert_eq!(width_bound_exceeded.step, 0);
+        assert!(width_bound_exceeded.clique.len() > 1);
+    }
+
+    #[test]
+    fn test_treewidth_heuristic_returns_clique_tree_for_chordal_graphs() {
+        for k in 1..5 {
+            let graph = generate_k_tree(k, 30).expect("k should be smaller than n");
+            for computation_method in COMPUTATION_METHODS {
+                let ((clique_graph, tree_decomposition, _, _, _, treewidth), is_optimal) =
+                    compute_treewidth_upper_bound_with_optimality::<_, _, _, RandomState>(
+                        &graph,
+                        constant,
+                        computation_method,
+                        true,
+                        None,
+                    );
+
+                assert!(is_optimal);
+                assert_eq!(treewidth, k);
+                // The bags of a clique tree are exactly the maximal cliques
+                assert_eq!(tree_decomposition.node_count(), clique_graph.node_count());
+                assert!(tree_decomposition
+                    .node_weights()
+                    .eq(clique_graph.node_weights()));
+            }
+        }
+
+        // The cycle a - b - c - d - a is not chordal
+        let mut graph: Graph<i32, i32, Undirected> = Graph::new_undirected();
+        let a = graph.add_node(0);
+        let b = graph.add_node(0);
+        let c = graph.add_node(0);
+        let d = graph.add_node(0);
+        graph.extend_with_edges([(a, b), (b, c), (c, d), (d, a)]);
+        for computation_method in COMPUTATION_METHODS {
+            let ((_, _, _, _, _, treewidth), is_optimal) =
+                compute_treewidth_upper_bound_with_optimality::<_, _, _, RandomState>(
+                    &graph,
+                    constant,
+                    computation_method,
+                    true,
+                    None,
+                );
+            assert!(!is_optimal);
+            assert_eq!(treewidth, 2);
+        }
+    }
+
     #[test]
     fn negative_intersection_weight_heuristic_does_not_fail_on_first_test_graph() {
         let i = 1;
@@ -408,17 +1082,11 @@ mod tests {
         for i in 0..3 {
             for computation_method in COMPUTATION_METHODS {
                 let test_graph = setup_test_graph(i);
-                let computed_treewidth = compute_treewidth_upper_bound_not_connected::<
-                    _,
-                    _,
-                    std::hash::BuildHasherDefault<rustc_hash::FxHasher>,
-                    _,
-                >(
+                let computed_treewidth = compute_treewidth_upper_bound_not_connected_by_filling(
                     &test_graph.graph,
                     least_difference,
                     computation_method,
                     false,
-                    None,
                 );
                 assert_eq!(computed_treewidth, test_graph.treewidth);
             }
@@ -9,7 +9,7 @@ use petgraph::Graph;
 /// weight function.
 pub fn construct_clique_graph<InnerCollection, OuterIterator, O, S: Default + BuildHasher>(
     cliques: OuterIterator,
-    edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    edge_weight_function: impl Fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
 ) -> Graph<HashSet<NodeIndex, S>, O, petgraph::prelude::Undirected>
 where
     OuterIterator: IntoIterator<Item = InnerCollection>,
@@ -57,7 +57,7 @@ pub fn construct_clique_graph_with_bags<
     S: Default + BuildHasher,
 >(
     cliques: OuterIterator,
-    edge_weight_heuristic: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    edge_weight_heuristic: impl Fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
 ) -> (
     Graph<HashSet<NodeIndex, S>, O, petgraph::prelude::Undirected>,
     HashMap<NodeIndex, HashSet<NodeIndex, S>, S>,
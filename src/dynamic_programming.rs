@@ -0,0 +1,520 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    hash::BuildHasher,
+};
+
+use petgraph::{graph::NodeIndex, Graph, Undirected};
+
+use crate::{make_nice_tree_decomposition, NiceNodeType, NiceTreeDecomposition};
+
+/// A dynamic program over a [nice tree decomposition][crate::make_nice_tree_decomposition] that is
+/// run bottom-up by [solve_dynamic_program]. Each handler gets the tables of the children of a node
+/// and the bag of the node and returns the table of the node.
+///
+/// The constraints of an edge can either be checked in the introduce handler (for the neighbours of
+/// the introduced vertex in the bag) or, if the nice tree decomposition has introduce edge nodes, in
+/// the introduce edge handler, which leaves the table unchanged by default.
+pub trait DynamicProgram<S> {
+    type Table;
+
+    /// Returns the table of a leaf, whose bag is empty.
+    fn leaf(&self) -> Self::Table;
+
+    /// Returns the table of a node whose bag is the bag of its child plus the vertex.
+    fn introduce(
+        &self,
+        child_table: Self::Table,
+        bag: &HashSet<NodeIndex, S>,
+        vertex: NodeIndex,
+    ) -> Self::Table;
+
+    /// Returns the table of a node that introduces the edge between two vertices of its bag.
+    fn introduce_edge(
+        &self,
+        child_table: Self::Table,
+        _bag: &HashSet<NodeIndex, S>,
+        _first_vertex: NodeIndex,
+        _second_vertex: NodeIndex,
+    ) -> Self::Table {
+        child_table
+    }
+
+    /// Returns the table of a node whose bag is the bag of its child without the vertex.
+    fn forget(
+        &self,
+        child_table: Self::Table,
+        bag: &HashSet<NodeIndex, S>,
+        vertex: NodeIndex,
+    ) -> Self::Table;
+
+    /// Returns the table of a node whose two children have the same bag as the node.
+    fn join(
+        &self,
+        first_child_table: Self::Table,
+        second_child_table: Self::Table,
+        bag: &HashSet<NodeIndex, S>,
+    ) -> Self::Table;
+}
+
+/// Runs the dynamic program bottom-up on the nice tree decomposition and returns the table of the
+/// root.
+///
+/// Panics: Panics if a node doesn't have the number of children required by its type.
+pub fn solve_dynamic_program<S, P: DynamicProgram<S>>(
+    nice_tree_decomposition: &NiceTreeDecomposition<S>,
+    dynamic_program: &P,
+) -> P::Table {
+    let tree = &nice_tree_decomposition.tree;
+
+    // Nodes in pre-order, so children are handled before their parents in reverse
+    let mut pre_order = Vec::with_capacity(tree.node_count());
+    let mut stack = vec![nice_tree_decomposition.root];
+    while let Some(node) = stack.pop() {
+        pre_order.push(node);
+        stack.extend(tree.neighbors(node));
+    }
+
+    let mut tables: HashMap<NodeIndex, P::Table> = HashMap::new();
+    for node in pre_order.into_iter().rev() {
+        let nice_bag = tree
+            .node_weight(node)
+            .expect("Nodes should exist in the nice tree decomposition");
+        let mut child_tables = tree.neighbors(node).map(|child| {
+            tables
+                .remove(&child)
+                .expect("Children should be handled before their parents")
+        });
+        let mut next_child_table = || {
+            child_tables
+                .next()
+                .expect("Node should have the number of children required by its type")
+        };
+
+        let table = match nice_bag.node_type {
+            NiceNodeType::Leaf => dynamic_program.leaf(),
+            NiceNodeType::Introduce(vertex) => {
+                dynamic_program.introduce(next_child_table(), &nice_bag.bag, vertex)
+            }
+            NiceNodeType::IntroduceEdge(first_vertex, second_vertex) => dynamic_program
+                .introduce_edge(
+                    next_child_table(),
+                    &nice_bag.bag,
+                    first_vertex,
+                    second_vertex,
+                ),
+            NiceNodeType::Forget(vertex) => {
+                dynamic_program.forget(next_child_table(), &nice_bag.bag, vertex)
+            }
+            NiceNodeType::Join => {
+                let first_child_table = next_child_table();
+                let second_child_table = next_child_table();
+                dynamic_program.join(first_child_table, second_child_table, &nice_bag.bag)
+            }
+        };
+        tables.insert(node, table);
+    }
+
+    tables
+        .remove(&nice_tree_decomposition.root)
+        .expect("Root should have a table")
+}
+
+/// Dynamic program for the maximum weight of an independent set of the graph. The weights of the
+/// vertices are given by their index.
+///
+/// The table maps each independent subset of the bag to the maximum weight of an independent set
+/// of the graph induced by the forgotten vertices and the bag whose intersection with the bag is
+/// this subset, not counting the weights of the vertices in the bag.
+pub struct MaximumWeightIndependentSet<'a, N, E> {
+    pub graph: &'a Graph<N, E, Undirected>,
+    pub weights: &'a [usize],
+}
+
+impl<N, E, S> DynamicProgram<S> for MaximumWeightIndependentSet<'_, N, E> {
+    type Table = HashMap<BTreeSet<NodeIndex>, usize>;
+
+    fn leaf(&self) -> Self::Table {
+        HashMap::from([(BTreeSet::new(), 0)])
+    }
+
+    fn introduce(
+        &self,
+        child_table: Self::Table,
+        _bag: &HashSet<NodeIndex, S>,
+        vertex: NodeIndex,
+    ) -> Self::Table {
+        let mut table = child_table.clone();
+        for (subset, weight) in child_table {
+            if subset
+                .iter()
+                .all(|other_vertex| !self.graph.contains_edge(vertex, *other_vertex))
+            {
+                let mut subset = subset;
+                subset.insert(vertex);
+                table.insert(subset, weight);
+            }
+        }
+        table
+    }
+
+    fn forget(
+        &self,
+        child_table: Self::Table,
+        _bag: &HashSet<NodeIndex, S>,
+        vertex: NodeIndex,
+    ) -> Self::Table {
+        let mut table: Self::Table = HashMap::new();
+        for (mut subset, mut weight) in child_table {
+            if subset.remove(&vertex) {
+                weight += self.weights[vertex.index()];
+            }
+            let best_weight = table.entry(subset).or_insert(weight);
+            *best_weight = (*best_weight).max(weight);
+        }
+        table
+    }
+
+    fn join(
+        &self,
+        first_child_table: Self::Table,
+        second_child_table: Self::Table,
+        _bag: &HashSet<NodeIndex, S>,
+    ) -> Self::Table {
+        first_child_table
+            .into_iter()
+            .filter_map(|(subset, first_weight)| {
+                let second_weight = second_child_table.get(&subset)?;
+                Some((subset, first_weight + second_weight))
+            })
+            .collect()
+    }
+}
+
+/// Dynamic program for the minimum size of a vertex cover of the graph.
+///
+/// The table maps each subset of the bag that covers the edges inside the bag to the minimum number
+/// of forgotten vertices in a vertex cover of the graph induced by the forgotten vertices and the
+/// bag whose intersection with the bag is this subset.
+pub struct MinimumVertexCover<'a, N, E> {
+    pub graph: &'a Graph<N, E, Undirected>,
+}
+
+impl<N, E, S> DynamicProgram<S> for MinimumVertexCover<'_, N, E> {
+    type Table = HashMap<BTreeSet<NodeIndex>, usize>;
+
+    fn leaf(&self) -> Self::Table {
+        HashMap::from([(BTreeSet::new(), 0)])
+    }
+
+    fn introduce(
+        &self,
+        child_table: Self::Table,
+        bag: &HashSet<NodeIndex, S>,
+        vertex: NodeIndex,
+    ) -> Self::Table {
+        let mut table: Self::Table = HashMap::new();
+        for (subset, size) in child_table {
+            // The vertex can only be left out if all its neighbours in the bag are in the cover
+            if bag.iter().all(|other_vertex| {
+                *other_vertex == vertex
+                    || subset.contains(other_vertex)
+                    || !self.graph.contains_edge(vertex, *other_vertex)
+            }) {
+                table.insert(subset.clone(), size);
+            }
+            let mut subset = subset;
+            subset.insert(vertex);
+            table.insert(subset, size);
+        }
+        table
+    }
+
+    fn forget(
+        &self,
+        child_table: Self::Table,
+        _bag: &HashSet<NodeIndex, S>,
+        vertex: NodeIndex,
+    ) -> Self::Table {
+        let mut table: Self::Table = HashMap::new();
+        for (mut subset, mut size) in child_table {
+            if subset.remove(&vertex) {
+                size += 1;
+            }
+            let best_size = table.entry(subset).or_insert(size);
+            *best_size = (*best_size).min(size);
+        }
+        table
+    }
+
+    fn join(
+        &self,
+        first_child_table: Self::Table,
+        second_child_table: Self::Table,
+        _bag: &HashSet<NodeIndex, S>,
+    ) -> Self::Table {
+        first_child_table
+            .into_iter()
+            .filter_map(|(subset, first_size)| {
+                let second_size = second_child_table.get(&subset)?;
+                Some((subset, first_size + second_size))
+            })
+            .collect()
+    }
+}
+
+/// Dynamic program deciding whether the graph can be colored with the given number of colors.
+///
+/// The table contains the colorings of the bag (mapping each vertex to its color) that can be
+/// extended to a proper coloring of the graph induced by the forgotten vertices and the bag.
+pub struct KColorability<'a, N, E> {
+    pub graph: &'a Graph<N, E, Undirected>,
+    pub number_of_colors: usize,
+}
+
+impl<N, E, S> DynamicProgram<S> for KColorability<'_, N, E> {
+    type Table = HashSet<BTreeMap<NodeIndex, usize>>;
+
+    fn leaf(&self) -> Self::Table {
+        HashSet::from([BTreeMap::new()])
+    }
+
+    fn introduce(
+        &self,
+        child_table: Self::Table,
+        _bag: &HashSet<NodeIndex, S>,
+        vertex: NodeIndex,
+    ) -> Self::Table {
+        let mut table: Self::Table = HashSet::new();
+        for coloring in child_table {
+            for color in 0..self.number_of_colors {
+                if coloring.iter().all(|(other_vertex, other_color)| {
+                    *other_color != color || !self.graph.contains_edge(vertex, *other_vertex)
+                }) {
+                    let mut coloring = coloring.clone();
+                    coloring.insert(vertex, color);
+                    table.insert(coloring);
+                }
+            }
+        }
+        table
+    }
+
+    fn forget(
+        &self,
+        child_table: Self::Table,
+        _bag: &HashSet<NodeIndex, S>,
+        vertex: NodeIndex,
+    ) -> Self::Table {
+        child_table
+            .into_iter()
+            .map(|mut coloring| {
+                coloring.remove(&vertex);
+                coloring
+            })
+            .collect()
+    }
+
+    fn join(
+        &self,
+        first_child_table: Self::Table,
+        second_child_table: Self::Table,
+        _bag: &HashSet<NodeIndex, S>,
+    ) -> Self::Table {
+        first_child_table
+            .intersection(&second_child_table)
+            .cloned()
+            .collect()
+    }
+}
+
+/// Returns the maximum weight of an independent set of the graph using the tree decomposition (see
+/// [MaximumWeightIndependentSet]). The weights of the vertices are given by their index.
+///
+/// Panics: Panics if the tree decomposition is empty or not a tree decomposition of the graph.
+pub fn find_maximum_weight_independent_set<N, E, O, S: Default + BuildHasher + Clone>(
+    graph: &Graph<N, E, Undirected>,
+    tree_decomposition: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    weights: &[usize],
+) -> usize {
+    let nice_tree_decomposition =
+        make_nice_tree_decomposition(graph, tree_decomposition, None, false);
+    solve_dynamic_program(
+        &nice_tree_decomposition,
+        &MaximumWeightIndependentSet { graph, weights },
+    )
+    .into_values()
+    .max()
+    .expect("Root table should contain the empty set")
+}
+
+/// Returns the minimum size of a vertex cover of the graph using the tree decomposition (see
+/// [MinimumVertexCover]).
+///
+/// Panics: Panics if the tree decomposition is empty or not a tree decomposition of the graph.
+pub fn find_minimum_vertex_cover<N, E, O, S: Default + BuildHasher + Clone>(
+    graph: &Graph<N, E, Undirected>,
+    tree_decomposition: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+) -> usize {
+    let nice_tree_decomposition =
+        make_nice_tree_decomposition(graph, tree_decomposition, None, false);
+    solve_dynamic_program(&nice_tree_decomposition, &MinimumVertexCover { graph })
+        .into_values()
+        .min()
+        .expect("Root table should contain the empty set")
+}
+
+/// Returns true if the graph can be colored with the given number of colors using the tree
+/// decomposition (see [KColorability]).
+///
+/// Panics: Panics if the tree decomposition is empty or not a tree decomposition of the graph.
+pub fn is_k_colorable<N, E, O, S: Default + BuildHasher + Clone>(
+    graph: &Graph<N, E, Undirected>,
+    tree_decomposition: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    number_of_colors: usize,
+) -> bool {
+    let nice_tree_decomposition =
+        make_nice_tree_decomposition(graph, tree_decomposition, None, false);
+    !solve_dynamic_program(
+        &nice_tree_decomposition,
+        &KColorability {
+            graph,
+            number_of_colors,
+        },
+    )
+    .is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::RandomState;
+
+    use itertools::Itertools;
+    use petgraph::visit::EdgeRef;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+    use crate::{
+        compute_treewidth_upper_bound_using_elimination_ordering, EliminationOrderingHeuristic,
+    };
+
+    fn is_independent_set(graph: &Graph<i32, i32, Undirected>, vertices: &[NodeIndex]) -> bool {
+        vertices
+            .iter()
+            .tuple_combinations()
+            .all(|(first_vertex, second_vertex)| {
+                !graph.contains_edge(*first_vertex, *second_vertex)
+            })
+    }
+
+    fn brute_force_maximum_weight_independent_set(
+        graph: &Graph<i32, i32, Undirected>,
+        weights: &[usize],
+    ) -> usize {
+        graph
+            .node_indices()
+            .powerset()
+            .filter(|vertices| is_independent_set(graph, vertices))
+            .map(|vertices| vertices.iter().map(|vertex| weights[vertex.index()]).sum())
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn brute_force_minimum_vertex_cover(graph: &Graph<i32, i32, Undirected>) -> usize {
+        graph
+            .node_indices()
+            .powerset()
+            .filter(|vertices| {
+                graph.edge_references().all(|edge| {
+                    vertices.contains(&edge.source()) || vertices.contains(&edge.target())
+                })
+            })
+            .map(|vertices| vertices.len())
+            .min()
+            .unwrap_or(0)
+    }
+
+    fn brute_force_is_k_colorable(graph: &Graph<i32, i32, Undirected>, k: usize) -> bool {
+        (0..graph.node_count())
+            .map(|_| 0..k)
+            .multi_cartesian_product()
+            .any(|coloring| {
+                graph
+                    .edge_references()
+                    .all(|edge| coloring[edge.source().index()] != coloring[edge.target().index()])
+            })
+    }
+
+    /// The graphs need to be small enough for the brute force solutions.
+    fn test_graphs() -> Vec<Graph<i32, i32, Undirected>> {
+        let mut graphs: Vec<Graph<i32, i32, Undirected>> =
+            crate::tests::setup_test_graphs_and_partial_k_trees(43, 3, 3, 10, 40)
+                .into_iter()
+                .map(|(graph, _)| graph)
+                .collect();
+        // Test graph 0 is not connected
+        graphs.push(crate::tests::setup_test_graph(0).graph);
+        graphs
+    }
+
+    #[test]
+    fn test_dynamic_programs_against_brute_force() {
+        let mut rng = StdRng::seed_from_u64(43);
+        for graph in test_graphs() {
+            let (tree_decomposition, _): (
+                Graph<HashSet<NodeIndex, RandomState>, i32, Undirected>,
+                _,
+            ) = compute_treewidth_upper_bound_using_elimination_ordering(
+                &graph,
+                EliminationOrderingHeuristic::MinFill,
+                true,
+            );
+
+            let weights: Vec<usize> = (0..graph.node_count())
+                .map(|_| rng.gen_range(1..10))
+                .collect();
+            assert_eq!(
+                find_maximum_weight_independent_set(&graph, &tree_decomposition, &weights),
+                brute_force_maximum_weight_independent_set(&graph, &weights)
+            );
+            assert_eq!(
+                find_minimum_vertex_cover(&graph, &tree_decomposition),
+                brute_force_minimum_vertex_cover(&graph)
+            );
+            for k in 1..4 {
+                assert_eq!(
+                    is_k_colorable(&graph, &tree_decomposition, k),
+                    brute_force_is_k_colorable(&graph, k)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_dynamic_programs_on_forest_of_bags() {
+        // Two disjoint cycles a - b - c - d - a whose tree decompositions are not connected
+        let mut graph: Graph<i32, i32, Undirected> = Graph::new_undirected();
+        for _ in 0..2 {
+            let cycle: Vec<NodeIndex> = (0..4).map(|_| graph.add_node(0)).collect();
+            for i in 0..4 {
+                graph.add_edge(cycle[i], cycle[(i + 1) % 4], 0);
+            }
+        }
+
+        let tree_decomposition =
+            crate::compute_tree_decomposition_of_components::<_, _, _, RandomState>(
+                &graph,
+                crate::negative_intersection,
+                crate::SpanningTreeConstructionMethod::MSTAndFill,
+                true,
+                None,
+            );
+        assert_eq!(petgraph::algo::connected_components(&tree_decomposition), 2);
+
+        assert_eq!(find_minimum_vertex_cover(&graph, &tree_decomposition), 4);
+        assert_eq!(
+            find_maximum_weight_independent_set(&graph, &tree_decomposition, &[1; 8]),
+            4
+        );
+        assert!(is_k_colorable(&graph, &tree_decomposition, 2));
+    }
+}
@@ -0,0 +1,478 @@
+use std::{
+    collections::{HashMap, HashSet},
+    hash::BuildHasher,
+};
+
+use petgraph::{graph::NodeIndex, Graph, Undirected};
+
+use crate::{check_tree_decomposition, find_width_of_tree_decomposition};
+
+/// Different greedy heuristics for computing an [elimination ordering][https://en.wikipedia.org/wiki/Chordal_graph#Perfect_elimination_and_efficient_recognition]
+/// of a graph. These are the classic baselines the clique graph heuristic can be compared against.
+///
+/// MinDegree Repeatedly eliminates a vertex of minimum degree, turning its neighbourhood into a
+/// clique
+///
+/// MinFill Repeatedly eliminates a vertex whose elimination adds the least number of fill edges,
+/// turning its neighbourhood into a clique
+///
+/// MinWidth Repeatedly removes a vertex of minimum degree without adding fill edges (the ordering
+/// is afterwards still turned into a tree decomposition using the elimination game)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EliminationOrderingHeuristic {
+    MinDegree,
+    MinFill,
+    MinWidth,
+}
+
+/// Computes an upper bound for the treewidth using a greedy elimination ordering heuristic.
+///
+/// Does this by computing an elimination ordering according to the given heuristic and then
+/// constructing a tree decomposition from it using the elimination game. The resulting tree
+/// decomposition has the same type as the ones computed by
+/// [compute_treewidth_upper_bound][crate::compute_treewidth_upper_bound], so results can be
+/// compared side by side.
+///
+/// Returns a tuple with the tree decomposition and the treewidth upper bound. The elimination
+/// ordering itself can be computed using [find_elimination_ordering].
+///
+/// Can also check the tree decomposition for correctness after computation. If so, will panic if
+/// the tree decomposition is incorrect.
+pub fn compute_treewidth_upper_bound_using_elimination_ordering<
+    N: Clone,
+    E: Clone,
+    O: Default,
+    S: Default + BuildHasher + Clone,
+>(
+    graph: &Graph<N, E, Undirected>,
+    elimination_ordering_heuristic: EliminationOrderingHeuristic,
+    check_tree_decomposition_bool: bool,
+) -> (Graph<HashSet<NodeIndex, S>, O, Undirected>, usize) {
+    let elimination_ordering =
+        find_elimination_ordering::<N, E, S>(graph, elimination_ordering_heuristic);
+    let tree_decomposition: Graph<HashSet<NodeIndex, S>, O, Undirected> =
+        tree_decomposition_from_elimination_ordering(graph, &elimination_ordering);
+
+    if check_tree_decomposition_bool {
+        assert!(
+            check_tree_decomposition(graph, &tree_decomposition, &None, &None),
+            "Tree decomposition is invalid. See previous print statements for reason."
+        );
+    }
+    let treewidth = find_width_of_tree_decomposition(&tree_decomposition);
+
+    (tree_decomposition, treewidth)
+}
+
+/// Computes an elimination ordering of the given graph using the given greedy heuristic.
+///
+/// Ties are broken by choosing the vertex with the smallest NodeIndex, so the result is
+/// deterministic.
+pub fn find_elimination_ordering<N, E, S: Default + BuildHasher + Clone>(
+    graph: &Graph<N, E, Undirected>,
+    elimination_ordering_heuristic: EliminationOrderingHeuristic,
+) -> Vec<NodeIndex> {
+    let mut adjacency: HashMap<NodeIndex, HashSet<NodeIndex, S>, S> = adjacency_map(graph);
+    let mut elimination_ordering = Vec::with_capacity(graph.node_count());
+
+    while !adjacency.is_empty() {
+        let vertex_to_eliminate = match elimination_ordering_heuristic {
+            EliminationOrderingHeuristic::MinDegree | EliminationOrderingHeuristic::MinWidth => {
+                *adjacency
+                    .iter()
+                    .min_by_key(|(vertex, neighbours)| (neighbours.len(), **vertex))
+                    .expect("Adjacency shouldn't be empty by loop invariant")
+                    .0
+            }
+            EliminationOrderingHeuristic::MinFill => *adjacency
+                .keys()
+                .min_by_key(|vertex| (number_of_fill_edges(&adjacency, **vertex), **vertex))
+                .expect("Adjacency shouldn't be empty by loop invariant"),
+        };
+
+        eliminate_vertex(
+            &mut adjacency,
+            vertex_to_eliminate,
+            elimination_ordering_heuristic != EliminationOrderingHeuristic::MinWidth,
+        );
+        elimination_ordering.push(vertex_to_eliminate);
+    }
+
+    elimination_ordering
+}
+
+/// Constructs a tree decomposition from the given elimination ordering using the elimination game.
+///
+/// For each vertex v (in the order of elimination) a bag containing v and its neighbours at the
+/// time of elimination is created. Afterwards the neighbourhood of v is turned into a clique and v
+/// is removed. The bag of v is attached to the bag of the neighbour that is eliminated next. If
+/// the graph is not connected, the resulting trees are joined by edges between bags with empty
+/// intersection.
+///
+/// Panics: Panics if the elimination ordering is not a permutation of the vertices of the graph
+pub fn tree_decomposition_from_elimination_ordering<N, E, O: Default, S>(
+    graph: &Graph<N, E, Undirected>,
+    elimination_ordering: &[NodeIndex],
+) -> Graph<HashSet<NodeIndex, S>, O, Undirected>
+where
+    S: Default + BuildHasher + Clone,
+{
+    assert_eq!(
+        elimination_ordering.len(),
+        graph.node_count(),
+        "Elimination ordering should contain every vertex exactly once"
+    );
+    let mut position_in_ordering: HashMap<NodeIndex, usize, S> = Default::default();
+    for (position, vertex) in elimination_ordering.iter().enumerate() {
+        assert!(
+            position_in_ordering.insert(*vertex, position).is_none(),
+            "Elimination ordering should contain every vertex exactly once"
+        );
+    }
+
+    let mut adjacency: HashMap<NodeIndex, HashSet<NodeIndex, S>, S> = adjacency_map(graph);
+    let mut result_graph: Graph<HashSet<NodeIndex, S>, O, Undirected> = Graph::new_undirected();
+    // Maps the vertices of the original graph to the bag created when they were eliminated
+    let mut bag_of_vertex: HashMap<NodeIndex, NodeIndex, S> = Default::default();
+    // Bags (and the vertex that is eliminated next among their neighbours) in order of creation
+    let mut bags_and_parent_vertices: Vec<(NodeIndex, Option<NodeIndex>)> = Vec::new();
+
+    for vertex in elimination_ordering {
+        let neighbours = eliminate_vertex(&mut adjacency, *vertex, true);
+        let parent_vertex = neighbours
+            .iter()
+            .min_by_key(|neighbour| position_in_ordering.get(*neighbour))
+            .cloned();
+
+        let mut bag = neighbours;
+        bag.insert(*vertex);
+        let bag_index = result_graph.add_node(bag);
+        bag_of_vertex.insert(*vertex, bag_index);
+        bags_and_parent_vertices.push((bag_index, parent_vertex));
+    }
+
+    let mut previous_root: Option<NodeIndex> = None;
+    for (bag_index, parent_vertex) in bags_and_parent_vertices {
+        if let Some(parent_vertex) = parent_vertex {
+            let parent_bag = bag_of_vertex
+                .get(&parent_vertex)
+                .expect("Every vertex should have a bag after the elimination game");
+            result_graph.add_edge(bag_index, *parent_bag, O::default());
+        } else {
+            // Bag is the root of one of the trees. Join it to the previous tree to obtain a tree.
+            if let Some(previous_root) = previous_root {
+                result_graph.add_edge(previous_root, bag_index, O::default());
+            }
+            previous_root = Some(bag_index);
+        }
+    }
+
+    result_graph
+}
+
+/// Computes an elimination ordering from the given tree decomposition whose induced width does not
+/// exceed the width of the tree decomposition.
+///
+/// Does this by rooting the tree decomposition and traversing it in post-order. Whenever a bag is
+/// visited, the vertices of the bag that are not contained in the bag of its parent are eliminated
+/// (these vertices don't appear in any bag that hasn't been visited yet). At the root all remaining
+/// vertices are eliminated. Vertices eliminated at the same bag are ordered by their NodeIndex. If
+/// the tree decomposition is a forest, each of its trees is rooted and traversed in turn.
+pub fn elimination_ordering_from_tree_decomposition<E, S: Default + BuildHasher + Clone>(
+    tree_decomposition: &Graph<HashSet<NodeIndex, S>, E, Undirected>,
+) -> Vec<NodeIndex> {
+    let mut elimination_ordering = Vec::new();
+    let mut eliminated_vertices: HashSet<NodeIndex, S> = Default::default();
+    let mut visited_bags: HashSet<NodeIndex, S> = Default::default();
+
+    for root in tree_decomposition.node_indices() {
+        if visited_bags.contains(&root) {
+            continue;
+        }
+
+        // Each entry consists of a bag, its parent and whether its children have been visited
+        // already
+        let mut stack: Vec<(NodeIndex, Option<NodeIndex>, bool)> = vec![(root, None, false)];
+        while let Some((current_bag, parent_bag, children_visited)) = stack.pop() {
+            if !children_visited {
+                visited_bags.insert(current_bag);
+                stack.push((current_bag, parent_bag, true));
+                for child_bag in tree_decomposition.neighbors(current_bag) {
+                    if Some(child_bag) != parent_bag {
+                        stack.push((child_bag, Some(current_bag), false));
+                    }
+                }
+            } else {
+                let bag = tree_decomposition
+                    .node_weight(current_bag)
+                    .expect("Vertices of the tree decomposition should have bags as weights");
+                let mut vertices_to_eliminate: Vec<NodeIndex> = bag
+                    .iter()
+                    .filter(|vertex| !eliminated_vertices.contains(*vertex))
+                    .filter(|vertex| match parent_bag {
+                        Some(parent_bag) => !tree_decomposition
+                            .node_weight(parent_bag)
+                            .expect(
+                                "Vertices of the tree decomposition should have bags as weights",
+                            )
+                            .contains(*vertex),
+                        None => true,
+                    })
+                    .cloned()
+                    .collect();
+                vertices_to_eliminate.sort();
+
+                eliminated_vertices.extend(vertices_to_eliminate.iter().cloned());
+                elimination_ordering.extend(vertices_to_eliminate);
+            }
+        }
+    }
+
+    elimination_ordering
+}
+
+/// Returns the induced width of the given elimination ordering, that is the maximum number of
+/// neighbours a vertex has at the time of its elimination when playing the elimination game.
+///
+/// Panics: Panics if the elimination ordering contains vertices that are not in the graph or
+/// contains vertices more than once
+pub fn find_induced_width_of_elimination_ordering<N, E, S: Default + BuildHasher + Clone>(
+    graph: &Graph<N, E, Undirected>,
+    elimination_ordering: &[NodeIndex],
+) -> usize {
+    let mut adjacency: HashMap<NodeIndex, HashSet<NodeIndex, S>, S> = adjacency_map(graph);
+
+    elimination_ordering
+        .iter()
+        .map(|vertex| eliminate_vertex(&mut adjacency, *vertex, true).len())
+        .max()
+        .unwrap_or(0)
+}
+
+/// Returns the adjacency of the given graph as a map from each vertex to its set of neighbours.
+/// Self loops are ignored.
+pub(crate) fn adjacency_map<N, E, S: Default + BuildHasher>(
+    graph: &Graph<N, E, Undirected>,
+) -> HashMap<NodeIndex, HashSet<NodeIndex, S>, S> {
+    let mut adjacency: HashMap<NodeIndex, HashSet<NodeIndex, S>, S> = Default::default();
+    for vertex in graph.node_indices() {
+        adjacency.insert(
+            vertex,
+            graph
+                .neighbors(vertex)
+                .filter(|neighbour| *neighbour != vertex)
+                .collect(),
+        );
+    }
+    adjacency
+}
+
+/// Removes the vertex from the adjacency and returns its neighbours. If fill is true, the
+/// neighbourhood of the vertex is turned into a clique beforehand.
+pub(crate) fn eliminate_vertex<S: Default + BuildHasher + Clone>(
+    adjacency: &mut HashMap<NodeIndex, HashSet<NodeIndex, S>, S>,
+    vertex: NodeIndex,
+    fill: bool,
+) -> HashSet<NodeIndex, S> {
+    let neighbours = adjacency
+        .remove(&vertex)
+        .expect("Vertex to eliminate should be in the adjacency");
+
+    for neighbour in neighbours.iter() {
+        let neighbours_of_neighbour = adjacency
+            .get_mut(neighbour)
+            .expect("Neighbours should be in the adjacency");
+        neighbours_of_neighbour.remove(&vertex);
+        if fill {
+            neighbours_of_neighbour.extend(neighbours.iter().filter(|other| *other != neighbour));
+        }
+    }
+
+    neighbours
+}
+
+/// Returns the number of edges that would have to be added to turn the neighbourhood of the vertex
+/// into a clique.
+pub(crate) fn number_of_fill_edges<S: BuildHasher>(
+    adjacency: &HashMap<NodeIndex, HashSet<NodeIndex, S>, S>,
+    vertex: NodeIndex,
+) -> usize {
+    let neighbours = adjacency
+        .get(&vertex)
+        .expect("Vertex should be in the adjacency");
+    let mut number_of_fill_edges = 0;
+
+    for neighbour in neighbours.iter() {
+        let neighbours_of_neighbour = adjacency
+            .get(neighbour)
+            .expect("Neighbours should be in the adjacency");
+        number_of_fill_edges += neighbours
+            .iter()
+            .filter(|other| *other > neighbour && !neighbours_of_neighbour.contains(*other))
+            .count();
+    }
+
+    number_of_fill_edges
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::RandomState;
+
+    use super::*;
+
+    pub const ELIMINATION_ORDERING_HEURISTICS: [EliminationOrderingHeuristic; 3] = [
+        EliminationOrderingHeuristic::MinDegree,
+        EliminationOrderingHeuristic::MinFill,
+        EliminationOrderingHeuristic::MinWidth,
+    ];
+
+    #[test]
+    fn test_elimination_ordering_heuristics_on_test_graphs() {
+        for i in 0..3 {
+            let test_graph = crate::tests::setup_test_graph(i);
+            for elimination_ordering_heuristic in ELIMINATION_ORDERING_HEURISTICS {
+                let (_, treewidth) =
+                    compute_treewidth_upper_bound_using_elimination_ordering::<
+                        _,
+                        _,
+                        i32,
+                        RandomState,
+                    >(&test_graph.graph, elimination_ordering_heuristic, true);
+                let elimination_ordering = find_elimination_ordering::<_, _, RandomState>(
+                    &test_graph.graph,
+                    elimination_ordering_heuristic,
+                );
+
+                let mut sorted_elimination_ordering = elimination_ordering.clone();
+                sorted_elimination_ordering.sort();
+                assert!(sorted_elimination_ordering
+                    .into_iter()
+                    .eq(test_graph.graph.node_indices()));
+                assert_eq!(
+                    treewidth, test_graph.treewidth,
+                    "Test graph {} failed with heuristic {:?}",
+                    i, elimination_ordering_heuristic
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_conversion_between_tree_decomposition_and_elimination_ordering() {
+        // Test graph 0 is not connected
+        for i in 1..3 {
+            let test_graph = crate::tests::setup_test_graph(i);
+            for computation_method in crate::tests::COMPUTATION_METHODS {
+                let (_, tree_decomposition, _, _, _, treewidth) =
+                    crate::compute_treewidth_upper_bound::<_, _, _, RandomState>(
+                        &test_graph.graph,
+                        crate::negative_intersection,
+                        computation_method,
+                        false,
+                        None,
+                    );
+
+                let elimination_ordering =
+                    elimination_ordering_from_tree_decomposition(&tree_decomposition);
+                let induced_width = find_induced_width_of_elimination_ordering::<_, _, RandomState>(
+                    &test_graph.graph,
+                    &elimination_ordering,
+                );
+                assert!(
+                    induced_width <= treewidth,
+                    "Test graph {} failed with computation method {:?}",
+                    i,
+                    computation_method
+                );
+
+                let converted_tree_decomposition: Graph<HashSet<NodeIndex, RandomState>, i32, _> =
+                    tree_decomposition_from_elimination_ordering(
+                        &test_graph.graph,
+                        &elimination_ordering,
+                    );
+                assert!(check_tree_decomposition(
+                    &test_graph.graph,
+                    &converted_tree_decomposition,
+                    &None,
+                    &None
+                ));
+                assert_eq!(
+                    find_width_of_tree_decomposition(&converted_tree_decomposition),
+                    induced_width
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_elimination_ordering_from_tree_decomposition_with_several_components() {
+        // Test graph 0 is not connected, so its tree decomposition is a forest with one tree per
+        // component
+        let test_graph = crate::tests::setup_test_graph(0);
+        let tree_decomposition =
+            crate::compute_tree_decomposition_of_components::<_, _, _, RandomState>(
+                &test_graph.graph,
+                crate::negative_intersection,
+                crate::SpanningTreeConstructionMethod::MSTAndUseTreeStructure,
+                false,
+                None,
+            );
+        assert!(
+            crate::find_connected_components::<Vec<_>, _, _, RandomState>(&tree_decomposition)
+                .count()
+                > 1
+        );
+
+        let elimination_ordering =
+            elimination_ordering_from_tree_decomposition(&tree_decomposition);
+        let mut sorted_elimination_ordering = elimination_ordering.clone();
+        sorted_elimination_ordering.sort();
+        assert!(sorted_elimination_ordering
+            .into_iter()
+            .eq(test_graph.graph.node_indices()));
+
+        let induced_width = find_induced_width_of_elimination_ordering::<_, _, RandomState>(
+            &test_graph.graph,
+            &elimination_ordering,
+        );
+        assert!(induced_width <= find_width_of_tree_decomposition(&tree_decomposition));
+
+        let converted_tree_decomposition: Graph<HashSet<NodeIndex, RandomState>, i32, _> =
+            tree_decomposition_from_elimination_ordering(&test_graph.graph, &elimination_ordering);
+        assert!(check_tree_decomposition(
+            &test_graph.graph,
+            &converted_tree_decomposition,
+            &None,
+            &None
+        ));
+    }
+
+    #[test]
+    fn test_elimination_ordering_heuristics_on_k_tree() {
+        use rand::Rng;
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..10 {
+            let k: usize = rng.gen_range(1..20);
+            let n: usize = rng.gen_range(k + 1..80);
+            let k_tree = crate::generate_k_tree(k, n).expect("k should be smaller than n");
+
+            for elimination_ordering_heuristic in ELIMINATION_ORDERING_HEURISTICS {
+                let (tree_decomposition, treewidth) =
+                    compute_treewidth_upper_bound_using_elimination_ordering::<
+                        _,
+                        _,
+                        i32,
+                        RandomState,
+                    >(&k_tree, elimination_ordering_heuristic, true);
+                assert_eq!(tree_decomposition.node_count(), n);
+                assert_eq!(
+                    treewidth, k,
+                    "k_tree with n: {} and k: {} failed with heuristic {:?}",
+                    n, k, elimination_ordering_heuristic
+                );
+            }
+        }
+    }
+}
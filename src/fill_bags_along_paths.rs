@@ -7,6 +7,8 @@ use std::{
     hash::BuildHasher,
 };
 
+use crate::WidthBoundExceeded;
+
 /// Struct for keeping track of node_index (node identifier in the graph) and the level of the node
 /// in the rooted tree.
 #[derive(PartialEq, Eq, Debug)]
@@ -38,11 +40,25 @@ impl PartialOrd for Predecessor {
 
 /// Given a tree graph with bags (HashSets) as Vertices, checks all 2-combinations of bags for non-empty-intersection
 /// and inserts the intersecting nodes in all bags that are along the (unique) path of the two bags in the tree.
+///
+/// If a width bound is given, stops as soon as a bag contains more than width_bound + 1 vertices.
+/// The returned step is the number of pairs of bags that were checked.
 pub fn fill_bags_along_paths<E, S: BuildHasher>(
     graph: &mut Graph<HashSet<NodeIndex, S>, E, petgraph::prelude::Undirected>,
-) {
+    width_bound: Option<usize>,
+) -> Result<(), WidthBoundExceeded> {
+    // The initial bags are the cliques which are reported if the width bound is exceeded
+    let cliques: Vec<Vec<NodeIndex>> = if width_bound.is_some() {
+        graph
+            .node_weights()
+            .map(|bag| bag.iter().cloned().collect())
+            .collect()
+    } else {
+        Vec::new()
+    };
+
     // Finding out which paths between bags have to be checked
-    for mut vec in graph.node_indices().combinations(2) {
+    for (step, mut vec) in graph.node_indices().combinations(2).enumerate() {
         let first_index = vec.pop().expect("Vec should contain two items");
         let second_index = vec.pop().expect("Vec should contain two items");
 
@@ -73,14 +89,26 @@ pub fn fill_bags_along_paths<E, S: BuildHasher>(
             // of the vertices on the path between them
             for node_index in path {
                 if node_index != first_index {
-                    graph
+                    let bag = graph
                         .node_weight_mut(node_index)
-                        .expect("Bag for the vertex should exist")
-                        .extend(intersection_vec.iter().cloned());
+                        .expect("Bag for the vertex should exist");
+                    bag.extend(intersection_vec.iter().cloned());
+
+                    if let Some(width_bound) = width_bound {
+                        if bag.len() > width_bound + 1 {
+                            return Err(WidthBoundExceeded::new(
+                                width_bound,
+                                step + 1,
+                                &cliques[node_index.index()],
+                            ));
+                        }
+                    }
                 }
             }
         }
     }
+
+    Ok(())
 }
 
 /// Given a tree graph with bags (HashSets) as Vertices, checks all 2-combinations of bags for non-empty-intersection
@@ -88,10 +116,14 @@ pub fn fill_bags_along_paths<E, S: BuildHasher>(
 ///
 /// This is done by identifying the tree with a rooted tree and therefore searching for paths of
 /// two vertices by searching for the common ancestor of these two vertices.
+///
+/// If a width bound is given, stops as soon as a bag contains more than width_bound + 1 vertices.
+/// The returned step is the number of vertices of the initial graph whose bags were filled up.
 pub fn fill_bags_along_paths_using_structure<E: Default + Debug, S: Default + BuildHasher>(
     graph: &mut Graph<HashSet<NodeIndex, S>, E, petgraph::prelude::Undirected>,
     clique_graph_map: &HashMap<NodeIndex, HashSet<NodeIndex, S>, S>,
-) -> HashMap<NodeIndex, (NodeIndex, usize), S> {
+    width_bound: Option<usize>,
+) -> Result<HashMap<NodeIndex, (NodeIndex, usize), S>, WidthBoundExceeded> {
     let mut tree_predecessor_map: HashMap<NodeIndex, (NodeIndex, usize), S> = Default::default();
     let root = graph
         .node_indices()
@@ -99,7 +131,17 @@ pub fn fill_bags_along_paths_using_structure<E: Default + Debug, S: Default + Bu
         .expect("Graph shouldn't be empty");
     setup_predecessors(&graph, &mut tree_predecessor_map, root);
 
-    for vertex_in_initial_graph in clique_graph_map.keys() {
+    // The initial bags are the cliques which are reported if the width bound is exceeded
+    let cliques: Vec<Vec<NodeIndex>> = if width_bound.is_some() {
+        graph
+            .node_weights()
+            .map(|bag| bag.iter().cloned().collect())
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    for (step, vertex_in_initial_graph) in clique_graph_map.keys().enumerate() {
         fill_bags_until_common_predecessor(
             graph,
             &tree_predecessor_map,
@@ -107,10 +149,26 @@ pub fn fill_bags_along_paths_using_structure<E: Default + Debug, S: Default + Bu
             &clique_graph_map
                 .get(vertex_in_initial_graph)
                 .expect("key should exist by loop invariant"),
-        )
+        );
+
+        if let Some(width_bound) = width_bound {
+            if let Some(overflowing_vertex) = graph.node_indices().find(|vertex| {
+                graph
+                    .node_weight(*vertex)
+                    .expect("Bag for the vertex should exist")
+                    .len()
+                    > width_bound + 1
+            }) {
+                return Err(WidthBoundExceeded::new(
+                    width_bound,
+                    step + 1,
+                    &cliques[overflowing_vertex.index()],
+                ));
+            }
+        }
     }
 
-    tree_predecessor_map
+    Ok(tree_predecessor_map)
 }
 
 /// Sets up the predecessor map such that each node has a predecessor going back to the root node.
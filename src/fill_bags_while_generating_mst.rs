@@ -5,6 +5,22 @@ use std::{
 
 use log::trace;
 use petgraph::{graph::NodeIndex, Graph, Undirected};
+use rand::Rng;
+
+use crate::{EdgeWeightValue, SpanningTreeConstructionMethod, WidthBoundExceeded};
+
+/// Restricted candidate list used when randomizing prim's algorithm in
+/// [fill_bags_while_generating_mst_randomized].
+///
+/// TopK Chooses among the k best candidates
+///
+/// Alpha Chooses among the candidates whose value is at most best + alpha * (worst - best). So an
+/// alpha of 0 is purely greedy and an alpha of 1 is purely random
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub enum RestrictedCandidateList {
+    TopK(usize),
+    Alpha(f64),
+}
 
 /// The function computes a [tree decomposition][https://en.wikipedia.org/wiki/Tree_decomposition]
 /// with the vertices having bags (HashSets) as labels
@@ -12,41 +28,100 @@ use petgraph::{graph::NodeIndex, Graph, Undirected};
 /// prim's algorithm and the edge labels in the clique graph as edge weights. Whenever a new vertex
 /// is added to the spanning tree, the bags of the current spanning tree are filled up/updated
 /// according to the [tree decomposition criteria][https://en.wikipedia.org/wiki/Tree_decomposition#Definition].
+/// The vertices of the tree decomposition have the same indices as the vertices of the clique graph
+/// whose cliques they contain.
+///
+/// If a width bound is given, stops as soon as a bag contains more than width_bound + 1 vertices
+/// and returns the step and the clique that caused the overflow (see [WidthBoundExceeded]).
 pub fn fill_bags_while_generating_mst<N, E, O: Ord, S: Default + BuildHasher + Clone>(
     clique_graph: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
-    edge_weight_heuristic: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    edge_weight_heuristic: impl Fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
     clique_graph_map: HashMap<NodeIndex, HashSet<NodeIndex, S>, S>,
-) -> Graph<HashSet<NodeIndex, S>, O, Undirected> {
+    width_bound: Option<usize>,
+) -> Result<Graph<HashSet<NodeIndex, S>, O, Undirected>, WidthBoundExceeded> {
+    let root = clique_graph
+        .node_indices()
+        .next()
+        .expect("Graph shouldn't be empty");
+    fill_bags_while_generating_mst_from_root(
+        clique_graph,
+        &clique_graph_map,
+        root,
+        |result_graph, _, currently_interesting_vertices| {
+            find_cheapest_vertex(
+                clique_graph,
+                result_graph,
+                &edge_weight_heuristic,
+                currently_interesting_vertices,
+            )
+        },
+        &edge_weight_heuristic,
+        false,
+        width_bound,
+    )
+}
+
+/// Runs prim's algorithm on the clique graph starting at the root and fills up/updates the bags of
+/// the current spanning tree whenever a vertex is added (see [fill_bags_while_generating_mst]).
+///
+/// In each step select_candidate gets the result graph, the map from the vertices of the clique
+/// graph to the vertices of the result graph and the currently interesting vertices and returns the
+/// tuple of vertices (from the result and the clique graph respectively) that is connected next.
+/// The new edge gets the weight computed by edge_weight from the bags of its endpoints. If
+/// update_edges is true, the interesting vertices are updated while filling up the bags (see
+/// [fill_bags_while_generating_mst_update_edges]).
+///
+/// If a width bound is given, stops as soon as a bag contains more than width_bound + 1 vertices
+/// and returns the step and the clique that caused the overflow (see [WidthBoundExceeded]).
+fn fill_bags_while_generating_mst_from_root<O, S: Default + BuildHasher + Clone>(
+    clique_graph: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    clique_graph_map: &HashMap<NodeIndex, HashSet<NodeIndex, S>, S>,
+    root: NodeIndex,
+    mut select_candidate: impl FnMut(
+        &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+        &HashMap<NodeIndex, NodeIndex, S>,
+        &HashSet<(NodeIndex, NodeIndex), S>,
+    ) -> (NodeIndex, NodeIndex),
+    edge_weight: impl Fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    update_edges: bool,
+    width_bound: Option<usize>,
+) -> Result<Graph<HashSet<NodeIndex, S>, O, Undirected>, WidthBoundExceeded> {
     let mut result_graph: Graph<HashSet<NodeIndex, S>, O, Undirected> = Graph::new_undirected();
     // Maps the vertex indices from the clique graph to the corresponding vertex indices in the result graph
     let mut node_index_map: HashMap<NodeIndex, NodeIndex, S> = Default::default();
-    let mut vertex_iter = clique_graph.node_indices();
-
-    let first_vertex_clique = vertex_iter.next().expect("Graph shouldn't be empty");
 
     // Keeps track of the remaining vertices from the clique graph that still need to be added to
     // the result_graph
-    let mut clique_graph_remaining_vertices: HashSet<NodeIndex, S> = vertex_iter.collect();
+    let mut clique_graph_remaining_vertices: HashSet<NodeIndex, S> = clique_graph
+        .node_indices()
+        .filter(|vertex| *vertex != root)
+        .collect();
 
     // Keeps track of the vertices that could be added to the current sub-tree-graph
     // First Tuple entry is node_index from the result graph that has an outgoing edge
     // Second tuple entry is node_index from the clique graph that is the interesting vertex
     let mut currently_interesting_vertices: HashSet<(NodeIndex, NodeIndex), S> = Default::default();
 
-    let first_vertex_res = result_graph.add_node(
+    let root_res = result_graph.add_node(
         clique_graph
-            .node_weight(first_vertex_clique)
-            .expect("Vertices in clique graph should have bags as weights")
+            .node_weight(root)
+            .expect("Root should be a vertex of the clique graph")
             .clone(),
     );
 
-    // Add vertices that are reachable from first vertex
-    for neighbor in clique_graph.neighbors(first_vertex_clique) {
-        currently_interesting_vertices.insert((first_vertex_res, neighbor));
+    // Add vertices that are reachable from root
+    for neighbor in clique_graph.neighbors(root) {
+        currently_interesting_vertices.insert((root_res, neighbor));
     }
-    node_index_map.insert(first_vertex_clique, first_vertex_res);
-
-    let mut current_treewidth = 0;
+    node_index_map.insert(root, root_res);
+    check_width_bound(
+        &result_graph,
+        width_bound,
+        node_index_map.len(),
+        clique_graph
+            .node_weight(root)
+            .expect("Vertices in clique graph should have bags as weights"),
+    )?;
 
     while !clique_graph_remaining_vertices.is_empty() {
         // DEBUG
@@ -56,66 +131,121 @@ pub fn fill_bags_while_generating_mst<N, E, O: Ord, S: Default + BuildHasher + C
                 clique_graph_remaining_vertices.len()
             );
         }
-
-        if current_treewidth != crate::find_width_of_tree_decomposition(&result_graph) {
-            current_treewidth = crate::find_width_of_tree_decomposition(&result_graph);
-            // DEBUG
-            // println!("Max bagsize increased to: {}", current_treewidth + 1);
-        }
-        // The cheapest_old_vertex_res is one of the vertices from the already constructed tree that the new vertex
+        // The chosen_old_vertex_res is one of the vertices from the already constructed tree that the new vertex
         // is being attached to
-        // The cheapest_new_vertex_clique is the new vertex that is being added to the tree. The NodeIndex corresponds
+        // The chosen_new_vertex_clique is the new vertex that is being added to the tree. The NodeIndex corresponds
         // to the vertex in the clique graph and not the result graph and thus still needs to be translated.
-        let (cheapest_old_vertex_res, cheapest_new_vertex_clique) = find_cheapest_vertex(
-            &clique_graph,
+        let (chosen_old_vertex_res, chosen_new_vertex_clique) = select_candidate(
             &result_graph,
-            edge_weight_heuristic,
+            &node_index_map,
             &currently_interesting_vertices,
         );
-        clique_graph_remaining_vertices.remove(&cheapest_new_vertex_clique);
+        clique_graph_remaining_vertices.remove(&chosen_new_vertex_clique);
 
         // Update result graph
-        let cheapest_new_vertex_res = result_graph.add_node(
+        let chosen_new_vertex_res = result_graph.add_node(
             clique_graph
-                .node_weight(cheapest_new_vertex_clique)
+                .node_weight(chosen_new_vertex_clique)
                 .expect("Vertices in clique graph should have bags as weights")
                 .clone(),
         );
 
-        node_index_map.insert(cheapest_new_vertex_clique, cheapest_new_vertex_res);
+        node_index_map.insert(chosen_new_vertex_clique, chosen_new_vertex_res);
         result_graph.add_edge(
-            cheapest_old_vertex_res,
-            cheapest_new_vertex_res,
-            edge_weight_heuristic(
+            chosen_old_vertex_res,
+            chosen_new_vertex_res,
+            edge_weight(
                 result_graph
-                    .node_weight(cheapest_old_vertex_res)
+                    .node_weight(chosen_old_vertex_res)
                     .expect("Vertices should have bags as weight"),
                 result_graph
-                    .node_weight(cheapest_new_vertex_res)
+                    .node_weight(chosen_new_vertex_res)
                     .expect("Vertices should have bags as weight"),
             ),
         );
 
         // Update currently interesting vertices
-        for neighbor in clique_graph.neighbors(cheapest_new_vertex_clique) {
+        for neighbor in clique_graph.neighbors(chosen_new_vertex_clique) {
             if clique_graph_remaining_vertices.contains(&neighbor) {
-                currently_interesting_vertices.insert((cheapest_new_vertex_res, neighbor));
+                currently_interesting_vertices.insert((chosen_new_vertex_res, neighbor));
             }
         }
 
         currently_interesting_vertices
-            .retain(|(_, vertex_clique)| !vertex_clique.eq(&cheapest_new_vertex_clique));
+            .retain(|(_, vertex_clique)| !vertex_clique.eq(&chosen_new_vertex_clique));
 
-        fill_bags_from_result_graph(
-            &mut result_graph,
-            cheapest_new_vertex_res,
-            cheapest_old_vertex_res,
-            &clique_graph_map,
-            &node_index_map,
+        if update_edges {
+            fill_bags_from_result_graph_updating_edges(
+                &mut result_graph,
+                chosen_new_vertex_res,
+                chosen_old_vertex_res,
+                clique_graph_map,
+                &node_index_map,
+                &mut currently_interesting_vertices,
+            );
+        } else {
+            fill_bags_from_result_graph(
+                &mut result_graph,
+                chosen_new_vertex_res,
+                chosen_old_vertex_res,
+                clique_graph_map,
+                &node_index_map,
+            );
+        }
+
+        check_width_bound(
+            &result_graph,
+            width_bound,
+            node_index_map.len(),
+            clique_graph
+                .node_weight(chosen_new_vertex_clique)
+                .expect("Vertices in clique graph should have bags as weights"),
+        )?;
+    }
+
+    Ok(renumber_by_clique_graph_vertices(
+        result_graph,
+        &node_index_map,
+    ))
+}
+
+/// Returns the result graph with its vertices renumbered such that each vertex has the index of the
+/// vertex of the clique graph it stems from. This way the tree decomposition has the same vertex
+/// indices as the clique graph, like the ones computed from a minimum spanning tree.
+fn renumber_by_clique_graph_vertices<O, S: BuildHasher>(
+    result_graph: Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    node_index_map: &HashMap<NodeIndex, NodeIndex, S>,
+) -> Graph<HashSet<NodeIndex, S>, O, Undirected> {
+    let mut clique_graph_vertices = vec![NodeIndex::end(); result_graph.node_count()];
+    for (vertex_clique_graph, vertex_res_graph) in node_index_map.iter() {
+        clique_graph_vertices[vertex_res_graph.index()] = *vertex_clique_graph;
+    }
+
+    let (vertices, edges) = result_graph.into_nodes_edges();
+    let mut bags: Vec<Option<HashSet<NodeIndex, S>>> = vertices
+        .into_iter()
+        .map(|vertex| Some(vertex.weight))
+        .collect();
+    let mut renumbered_graph = Graph::with_capacity(bags.len(), edges.len());
+    for vertex_clique_graph in 0..bags.len() {
+        let vertex_res_graph = node_index_map
+            .get(&NodeIndex::new(vertex_clique_graph))
+            .expect("All vertices of the clique graph should be in the result graph");
+        renumbered_graph.add_node(
+            bags[vertex_res_graph.index()]
+                .take()
+                .expect("Vertices of the result graph should stem from distinct vertices"),
+        );
+    }
+    for edge in edges {
+        renumbered_graph.add_edge(
+            clique_graph_vertices[edge.source().index()],
+            clique_graph_vertices[edge.target().index()],
+            edge.weight,
         );
     }
 
-    result_graph
+    renumbered_graph
 }
 
 fn fill_bags_from_result_graph<S: BuildHasher + Clone, O>(
@@ -153,6 +283,25 @@ fn fill_bags_from_result_graph<S: BuildHasher + Clone, O>(
     }
 }
 
+/// Returns an error if a width bound is given and the width of the result graph exceeds it. The
+/// step is the number of vertices in the result graph and the clique is the clique of the vertex
+/// that was added last.
+fn check_width_bound<O, S: BuildHasher>(
+    result_graph: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    width_bound: Option<usize>,
+    step: usize,
+    added_clique: &HashSet<NodeIndex, S>,
+) -> Result<(), WidthBoundExceeded> {
+    match width_bound {
+        Some(width_bound)
+            if crate::find_width_of_tree_decomposition(result_graph) > width_bound =>
+        {
+            Err(WidthBoundExceeded::new(width_bound, step, added_clique))
+        }
+        _ => Ok(()),
+    }
+}
+
 /// Finds a path in the given graph between start_vertex and end_vertex
 ///
 /// Panics: Panics if there is no path between start and end_vertex, especially in the case that
@@ -198,94 +347,30 @@ pub fn fill_bags_while_generating_mst_update_edges<
     S: Default + BuildHasher + Clone,
 >(
     clique_graph: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
-    edge_weight_heuristic: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    edge_weight_heuristic: impl Fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
     clique_graph_map: HashMap<NodeIndex, HashSet<NodeIndex, S>, S>,
-) -> Graph<HashSet<NodeIndex, S>, O, Undirected> {
-    let mut result_graph: Graph<HashSet<NodeIndex, S>, O, Undirected> = Graph::new_undirected();
-    // Maps the vertex indices from the clique graph to the corresponding vertex indices in the result graph
-    let mut node_index_map: HashMap<NodeIndex, NodeIndex, S> = Default::default();
-    let mut vertex_iter = clique_graph.node_indices();
-
-    let first_vertex_clique = vertex_iter.next().expect("Graph shouldn't be empty");
-
-    // Keeps track of the remaining vertices from the clique graph that still need to be added to
-    // the result_graph
-    let mut clique_graph_remaining_vertices: HashSet<NodeIndex, S> = vertex_iter.collect();
-
-    // Keeps track of the vertices that could be added to the current sub-tree-graph
-    // First Tuple entry is node_index from the result graph that has an outgoing edge
-    // Second tuple entry is node_index from the clique graph that is the interesting vertex
-    let mut currently_interesting_vertices: HashSet<(NodeIndex, NodeIndex), S> = Default::default();
-
-    let first_vertex_res = result_graph.add_node(
-        clique_graph
-            .node_weight(first_vertex_clique)
-            .expect("Vertices in clique graph should have bags as weights")
-            .clone(),
-    );
-
-    // Add vertices that are reachable from first vertex
-    for neighbor in clique_graph.neighbors(first_vertex_clique) {
-        currently_interesting_vertices.insert((first_vertex_res, neighbor));
-    }
-    node_index_map.insert(first_vertex_clique, first_vertex_res);
-
-    while !clique_graph_remaining_vertices.is_empty() {
-        // The cheapest_old_vertex_res is one of the vertices from the already constructed tree that the new vertex
-        // is being attached to
-        // The cheapest_new_vertex_clique is the new vertex that is being added to the tree. The NodeIndex corresponds
-        // to the vertex in the clique graph and not the result graph and thus still needs to be translated.
-        let (cheapest_old_vertex_res, cheapest_new_vertex_clique) = find_cheapest_vertex(
-            &clique_graph,
-            &result_graph,
-            edge_weight_heuristic,
-            &currently_interesting_vertices,
-        );
-        clique_graph_remaining_vertices.remove(&cheapest_new_vertex_clique);
-
-        // Update result graph
-        let cheapest_new_vertex_res = result_graph.add_node(
-            clique_graph
-                .node_weight(cheapest_new_vertex_clique)
-                .expect("Vertices in clique graph should have bags as weights")
-                .clone(),
-        );
-
-        node_index_map.insert(cheapest_new_vertex_clique, cheapest_new_vertex_res);
-        result_graph.add_edge(
-            cheapest_old_vertex_res,
-            cheapest_new_vertex_res,
-            edge_weight_heuristic(
-                result_graph
-                    .node_weight(cheapest_old_vertex_res)
-                    .expect("Vertices should have bags as weight"),
-                result_graph
-                    .node_weight(cheapest_new_vertex_res)
-                    .expect("Vertices should have bags as weight"),
-            ),
-        );
-
-        // Update currently interesting vertices
-        for neighbor in clique_graph.neighbors(cheapest_new_vertex_clique) {
-            if clique_graph_remaining_vertices.contains(&neighbor) {
-                currently_interesting_vertices.insert((cheapest_new_vertex_res, neighbor));
-            }
-        }
-
-        currently_interesting_vertices
-            .retain(|(_, vertex_clique)| !vertex_clique.eq(&cheapest_new_vertex_clique));
-
-        fill_bags_from_result_graph_updating_edges(
-            &mut result_graph,
-            cheapest_new_vertex_res,
-            cheapest_old_vertex_res,
-            &clique_graph_map,
-            &node_index_map,
-            &mut currently_interesting_vertices,
-        );
-    }
-
-    result_graph
+    width_bound: Option<usize>,
+) -> Result<Graph<HashSet<NodeIndex, S>, O, Undirected>, WidthBoundExceeded> {
+    let root = clique_graph
+        .node_indices()
+        .next()
+        .expect("Graph shouldn't be empty");
+    fill_bags_while_generating_mst_from_root(
+        clique_graph,
+        &clique_graph_map,
+        root,
+        |result_graph, _, currently_interesting_vertices| {
+            find_cheapest_vertex(
+                clique_graph,
+                result_graph,
+                &edge_weight_heuristic,
+                currently_interesting_vertices,
+            )
+        },
+        &edge_weight_heuristic,
+        true,
+        width_bound,
+    )
 }
 
 fn fill_bags_from_result_graph_updating_edges<S: BuildHasher + Clone, O>(
@@ -377,7 +462,7 @@ fn fill_bags_updating_edges<O, S: BuildHasher>(
 fn find_cheapest_vertex<O: Ord, S>(
     clique_graph: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
     result_graph: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
-    edge_weight_heuristic: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    edge_weight_heuristic: impl Fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
     currently_interesting_vertices: &HashSet<(NodeIndex, NodeIndex), S>,
 ) -> (NodeIndex, NodeIndex) {
     *currently_interesting_vertices
@@ -387,9 +472,10 @@ fn find_cheapest_vertex<O: Ord, S>(
 
 pub fn fill_bags_while_generating_mst_using_tree<N, E, O: Ord, S: Default + BuildHasher + Clone>(
     clique_graph: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
-    edge_weight_heuristic: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    edge_weight_heuristic: impl Fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
     clique_graph_map: HashMap<NodeIndex, HashSet<NodeIndex, S>, S>,
-) -> Graph<HashSet<NodeIndex, S>, O, Undirected> {
+    width_bound: Option<usize>,
+) -> Result<Graph<HashSet<NodeIndex, S>, O, Undirected>, WidthBoundExceeded> {
     let mut result_graph: Graph<HashSet<NodeIndex, S>, O, Undirected> = Graph::new_undirected();
     // Maps the vertex indices from the clique graph to the corresponding vertex indices in the result graph
     let mut node_index_map: HashMap<NodeIndex, NodeIndex, S> = Default::default();
@@ -423,12 +509,20 @@ pub fn fill_bags_while_generating_mst_using_tree<N, E, O: Ord, S: Default + Buil
         currently_interesting_vertices.insert((first_vertex_res, neighbor));
     }
     node_index_map.insert(first_vertex_clique, first_vertex_res);
+    check_width_bound(
+        &result_graph,
+        width_bound,
+        node_index_map.len(),
+        clique_graph
+            .node_weight(first_vertex_clique)
+            .expect("Vertices in clique graph should have bags as weights"),
+    )?;
 
     while !clique_graph_remaining_vertices.is_empty() {
         let (cheapest_vertex_res, cheapest_vertex_clique) = find_cheapest_vertex(
             &clique_graph,
             &result_graph,
-            edge_weight_heuristic,
+            &edge_weight_heuristic,
             &currently_interesting_vertices,
         );
         clique_graph_remaining_vertices.remove(&cheapest_vertex_clique);
@@ -500,9 +594,21 @@ pub fn fill_bags_while_generating_mst_using_tree<N, E, O: Ord, S: Default + Buil
                 }
             }
         }
+
+        check_width_bound(
+            &result_graph,
+            width_bound,
+            node_index_map.len(),
+            clique_graph
+                .node_weight(cheapest_vertex_clique)
+                .expect("Vertices in clique graph should have bags as weights"),
+        )?;
     }
 
-    result_graph
+    Ok(renumber_by_clique_graph_vertices(
+        result_graph,
+        &node_index_map,
+    ))
 }
 
 /// Computes a tree decomposition similar to [fill_bags_while_generating_mst] except that instead of
@@ -517,125 +623,600 @@ pub fn fill_bags_while_generating_mst_least_bag_size<
 >(
     clique_graph: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
     clique_graph_map: HashMap<NodeIndex, HashSet<NodeIndex, S>, S>,
+    width_bound: Option<usize>,
+) -> Result<Graph<HashSet<NodeIndex, S>, O, Undirected>, WidthBoundExceeded> {
+    fill_bags_while_generating_mst_least_cost::<N, E, O, S, usize>(
+        clique_graph,
+        clique_graph_map,
+        crate::find_width_of_tree_decomposition,
+        width_bound,
+    )
+}
+
+/// Computes a tree decomposition like [fill_bags_while_generating_mst_least_bag_size] except that
+/// the weight of an edge (u,v) is the cost of the spanning tree according to the cost function if v
+/// was added to the spanning tree and the bags were filled up/updated accordingly. This allows
+/// minimizing other objectives than the width, e.g. vertex weighted objectives (see
+/// [VertexWeightedObjective][crate::VertexWeightedObjective]).
+///
+/// The cost function is evaluated from scratch on a filled up copy of the partial spanning tree for
+/// every edge leaving the spanning tree in every step, so with a cost function that looks at all
+/// bags each step takes time proportional to the number of these edges times the number of bags.
+pub fn fill_bags_while_generating_mst_least_cost<
+    N,
+    E,
+    O: Ord + Default + Clone,
+    S: Default + BuildHasher + Clone,
+    K: Ord,
+>(
+    clique_graph: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    clique_graph_map: HashMap<NodeIndex, HashSet<NodeIndex, S>, S>,
+    cost_function: impl Fn(&Graph<HashSet<NodeIndex, S>, O, Undirected>) -> K,
+    width_bound: Option<usize>,
+) -> Result<Graph<HashSet<NodeIndex, S>, O, Undirected>, WidthBoundExceeded> {
+    let root = clique_graph
+        .node_indices()
+        .next()
+        .expect("Graph shouldn't be empty");
+    fill_bags_while_generating_mst_from_root(
+        clique_graph,
+        &clique_graph_map,
+        root,
+        |result_graph, node_index_map, currently_interesting_vertices| {
+            find_vertex_that_minimizes_cost(
+                clique_graph,
+                result_graph,
+                currently_interesting_vertices,
+                &clique_graph_map,
+                node_index_map,
+                &cost_function,
+            )
+        },
+        |_, _| O::default(),
+        false,
+        width_bound,
+    )
+}
+
+/// Finds the cheapest edge to a vertex not yet in the result graph trying find the vertex that minimizes
+/// the cost of the result graph (e.g. the size of the biggest bag) if the vertex is added.
+///
+/// Returns a tuple with a node index from the result graph in the first and node index from the clique graph
+/// in the second entry. The cheapest edge being the edge between these two nodes only they are different
+/// in different representations (result and clique graph respectively)
+fn find_vertex_that_minimizes_cost<O: Ord + Default + Clone, S: BuildHasher + Clone, K: Ord>(
+    clique_graph: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    result_graph: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    currently_interesting_vertices: &HashSet<(NodeIndex, NodeIndex), S>,
+    clique_graph_map: &HashMap<NodeIndex, HashSet<NodeIndex, S>, S>,
+    node_index_map: &HashMap<NodeIndex, NodeIndex, S>,
+    cost_function: &impl Fn(&Graph<HashSet<NodeIndex, S>, O, Undirected>) -> K,
+) -> (NodeIndex, NodeIndex) {
+    *currently_interesting_vertices
+        .iter()
+        .min_by_key(|(vertex_res_graph, interesting_vertex_clique_graph)| {
+            cost_function(&find_result_graph_after_adding_vertex(
+                clique_graph,
+                result_graph,
+                *vertex_res_graph,
+                *interesting_vertex_clique_graph,
+                clique_graph_map,
+                node_index_map,
+            ))
+        }).expect("There should be interesting vertices since there are vertices left and the graph is connected")
+}
+
+/// Returns the width of the result graph if the given vertex from the clique graph was attached to
+/// the given vertex from the result graph and the bags were filled up/updated accordingly.
+fn find_width_after_adding_vertex<O: Default + Clone, S: BuildHasher + Clone>(
+    clique_graph: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    result_graph: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    vertex_res_graph: NodeIndex,
+    interesting_vertex_clique_graph: NodeIndex,
+    clique_graph_map: &HashMap<NodeIndex, HashSet<NodeIndex, S>, S>,
+    node_index_map: &HashMap<NodeIndex, NodeIndex, S>,
+) -> usize {
+    // Find treewidth (biggest bag size) of
+    crate::find_width_of_tree_decomposition(&find_result_graph_after_adding_vertex(
+        clique_graph,
+        result_graph,
+        vertex_res_graph,
+        interesting_vertex_clique_graph,
+        clique_graph_map,
+        node_index_map,
+    ))
+}
+
+/// Returns the result graph after the given vertex from the clique graph was attached to the given
+/// vertex from the result graph and the bags were filled up/updated accordingly.
+fn find_result_graph_after_adding_vertex<O: Default + Clone, S: BuildHasher + Clone>(
+    clique_graph: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    result_graph: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    vertex_res_graph: NodeIndex,
+    interesting_vertex_clique_graph: NodeIndex,
+    clique_graph_map: &HashMap<NodeIndex, HashSet<NodeIndex, S>, S>,
+    node_index_map: &HashMap<NodeIndex, NodeIndex, S>,
 ) -> Graph<HashSet<NodeIndex, S>, O, Undirected> {
-    let mut result_graph: Graph<HashSet<NodeIndex, S>, O, Undirected> = Graph::new_undirected();
-    // Maps the vertex indices from the clique graph to the corresponding vertex indices in the result graph
-    let mut node_index_map: HashMap<NodeIndex, NodeIndex, S> = Default::default();
-    let mut vertex_iter = clique_graph.node_indices();
+    // Clone result graph
+    let mut result_graph: Graph<HashSet<NodeIndex, S>, O, Undirected> = result_graph.clone();
 
-    let first_vertex_clique = vertex_iter.next().expect("Graph shouldn't be empty");
+    // Update result graph
+    let cheapest_new_vertex_res = result_graph.add_node(
+        clique_graph
+            .node_weight(interesting_vertex_clique_graph)
+            .expect("Vertices in clique graph should have bags as weights")
+            .clone(),
+    );
 
-    // Keeps track of the remaining vertices from the clique graph that still need to be added to
-    // the result_graph
-    let mut clique_graph_remaining_vertices: HashSet<NodeIndex, S> = vertex_iter.collect();
+    result_graph.add_edge(vertex_res_graph, cheapest_new_vertex_res, O::default());
 
-    // Keeps track of the vertices that could be added to the current sub-tree-graph
-    // First Tuple entry is node_index from the result graph that has an outgoing edge
+    fill_bags_from_result_graph(
+        &mut result_graph,
+        cheapest_new_vertex_res,
+        vertex_res_graph,
+        clique_graph_map,
+        node_index_map,
+    );
+
+    result_graph
+}
+
+/// Partial spanning tree that is kept in the beam of [fill_bags_while_generating_mst_beam].
+#[derive(Clone)]
+struct BeamState<O, S> {
+    result_graph: Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    // Maps the vertex indices from the clique graph to the corresponding vertex indices in the result graph
+    node_index_map: HashMap<NodeIndex, NodeIndex, S>,
+    // Maps the vertex indices from the result graph (the position in the vector) to the
+    // corresponding vertex indices in the clique graph
+    clique_graph_vertices: Vec<NodeIndex>,
+    // First tuple entry is node_index from the result graph that has an outgoing edge
     // Second tuple entry is node_index from the clique graph that is the interesting vertex
-    let mut currently_interesting_vertices: HashSet<(NodeIndex, NodeIndex), S> = Default::default();
+    currently_interesting_vertices: HashSet<(NodeIndex, NodeIndex), S>,
+    // Edges of the partial spanning tree given by vertices of the clique graph (sorted), used to
+    // identify partial spanning trees that were reached in different orders
+    tree_edges: Vec<(NodeIndex, NodeIndex)>,
+    width: usize,
+    sum_of_bag_sizes: usize,
+}
+
+/// Extension of a partial spanning tree in the beam of [fill_bags_while_generating_mst_beam] by
+/// attaching the vertex from the clique graph to the vertex from the result graph. It is scored
+/// without constructing the extended partial spanning tree.
+struct BeamExtension<S> {
+    // Position of the extended partial spanning tree in the beam
+    state: usize,
+    old_vertex_res: NodeIndex,
+    new_vertex_clique: NodeIndex,
+    tree_edges: Vec<(NodeIndex, NodeIndex)>,
+    // Vertices of the original graph that are inserted into the bags of the result graph when
+    // filling up the bags, as tuples of the vertex of the result graph and the inserted vertex
+    insertions: HashSet<(NodeIndex, NodeIndex), S>,
+    width: usize,
+    sum_of_bag_sizes: usize,
+}
+
+/// Computes a tree decomposition similar to [fill_bags_while_generating_mst_least_bag_size] except
+/// that instead of greedily committing to one vertex in each step, the best beam_width partial
+/// spanning trees are kept. In each step, all partial spanning trees in the beam are extended by
+/// each of their frontier vertices and the bags are filled up/updated accordingly. The extended
+/// partial spanning trees are scored by the size of their biggest bag with the sum of the sizes of
+/// their bags as a tie breaker and the best beam_width of them form the next beam. Only these are
+/// actually constructed, the others are scored by the vertices that would be inserted into the bags.
+///
+/// A beam width of 1 is (up to tie breaking) the same as
+/// [fill_bags_while_generating_mst_least_bag_size]. If a width bound is given, partial spanning
+/// trees exceeding it are discarded and the computation stops once all of them exceed it.
+pub fn fill_bags_while_generating_mst_beam<
+    O: Ord + Default + Clone,
+    S: Default + BuildHasher + Clone,
+>(
+    clique_graph: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    clique_graph_map: HashMap<NodeIndex, HashSet<NodeIndex, S>, S>,
+    beam_width: usize,
+    width_bound: Option<usize>,
+) -> Result<Graph<HashSet<NodeIndex, S>, O, Undirected>, WidthBoundExceeded> {
+    let first_vertex_clique = clique_graph
+        .node_indices()
+        .next()
+        .expect("Graph shouldn't be empty");
 
+    let mut result_graph: Graph<HashSet<NodeIndex, S>, O, Undirected> = Graph::new_undirected();
     let first_vertex_res = result_graph.add_node(
         clique_graph
             .node_weight(first_vertex_clique)
             .expect("Vertices in clique graph should have bags as weights")
             .clone(),
     );
-
-    // Add vertices that are reachable from first vertex
-    for neighbor in clique_graph.neighbors(first_vertex_clique) {
-        currently_interesting_vertices.insert((first_vertex_res, neighbor));
-    }
+    let mut node_index_map: HashMap<NodeIndex, NodeIndex, S> = Default::default();
     node_index_map.insert(first_vertex_clique, first_vertex_res);
+    // Add vertices that are reachable from first vertex
+    let currently_interesting_vertices: HashSet<(NodeIndex, NodeIndex), S> = clique_graph
+        .neighbors(first_vertex_clique)
+        .map(|neighbor| (first_vertex_res, neighbor))
+        .collect();
+    let first_bag_size = result_graph
+        .node_weight(first_vertex_res)
+        .expect("Vertex should have weight since it was just added")
+        .len();
 
-    while !clique_graph_remaining_vertices.is_empty() {
-        let (cheapest_old_vertex_res, cheapest_vertex_clique) = find_vertex_that_minimizes_bag_size(
-            &clique_graph,
-            &result_graph,
-            &currently_interesting_vertices,
-            &clique_graph_map,
-            &node_index_map,
-        );
-        clique_graph_remaining_vertices.remove(&cheapest_vertex_clique);
+    check_width_bound(
+        &result_graph,
+        width_bound,
+        node_index_map.len(),
+        clique_graph
+            .node_weight(first_vertex_clique)
+            .expect("Vertices in clique graph should have bags as weights"),
+    )?;
+
+    let mut beam = vec![BeamState {
+        result_graph,
+        node_index_map,
+        clique_graph_vertices: vec![first_vertex_clique],
+        currently_interesting_vertices,
+        tree_edges: Vec::new(),
+        width: first_bag_size.saturating_sub(1),
+        sum_of_bag_sizes: first_bag_size,
+    }];
+
+    // In each step, each partial spanning tree in the beam gets one more vertex
+    for step in 2..=clique_graph.node_count() {
+        let mut extensions: Vec<BeamExtension<S>> = Vec::new();
+        let mut seen_trees: HashSet<Vec<(NodeIndex, NodeIndex)>, S> = Default::default();
+
+        for (position, state) in beam.iter().enumerate() {
+            // Sorting makes the result independent of the iteration order of the hash set
+            let mut candidates: Vec<(NodeIndex, NodeIndex)> = state
+                .currently_interesting_vertices
+                .iter()
+                .cloned()
+                .collect();
+            candidates.sort();
+
+            for (old_vertex_res, new_vertex_clique) in candidates {
+                let old_vertex_clique = state.clique_graph_vertices[old_vertex_res.index()];
+                let mut tree_edges = state.tree_edges.clone();
+                tree_edges.push((
+                    old_vertex_clique.min(new_vertex_clique),
+                    old_vertex_clique.max(new_vertex_clique),
+                ));
+                tree_edges.sort();
+                if !seen_trees.insert(tree_edges.clone()) {
+                    continue;
+                }
 
-        // Update result graph
-        let cheapest_new_vertex_res = result_graph.add_node(
-            clique_graph
-                .node_weight(cheapest_vertex_clique)
-                .expect("Vertices in clique graph should have bags as weights")
-                .clone(),
-        );
+                extensions.push(find_beam_extension(
+                    clique_graph,
+                    &clique_graph_map,
+                    state,
+                    position,
+                    old_vertex_res,
+                    new_vertex_clique,
+                    tree_edges,
+                ));
+            }
+        }
 
-        node_index_map.insert(cheapest_vertex_clique, cheapest_new_vertex_res);
-        result_graph.add_edge(
-            cheapest_old_vertex_res,
-            cheapest_new_vertex_res,
-            O::default(),
+        extensions.sort_by_key(|extension| (extension.width, extension.sum_of_bag_sizes));
+        let best_extension = extensions.first().expect(
+            "There should be interesting vertices since there are vertices left and the graph is connected",
         );
-
-        // Update currently interesting vertices
-        for neighbor in clique_graph.neighbors(cheapest_vertex_clique) {
-            if clique_graph_remaining_vertices.contains(&neighbor) {
-                currently_interesting_vertices.insert((cheapest_new_vertex_res, neighbor));
+        if let Some(width_bound) = width_bound {
+            if best_extension.width > width_bound {
+                return Err(WidthBoundExceeded::new(
+                    width_bound,
+                    step,
+                    clique_graph
+                        .node_weight(best_extension.new_vertex_clique)
+                        .expect("Vertices in clique graph should have bags as weights"),
+                ));
             }
         }
+        extensions.retain(|extension| {
+            width_bound.map_or(true, |width_bound| extension.width <= width_bound)
+        });
+        extensions.truncate(beam_width.max(1));
+
+        beam = extensions
+            .into_iter()
+            .map(|extension| {
+                extend_beam_state(clique_graph, beam[extension.state].clone(), extension)
+            })
+            .collect();
+    }
 
-        currently_interesting_vertices
-            .retain(|(_, vertex_clique)| !vertex_clique.eq(&cheapest_vertex_clique));
+    let best_state = beam
+        .into_iter()
+        .next()
+        .expect("Beam should contain at least one partial spanning tree");
+    Ok(renumber_by_clique_graph_vertices(
+        best_state.result_graph,
+        &best_state.node_index_map,
+    ))
+}
 
-        fill_bags_from_result_graph(
-            &mut result_graph,
-            cheapest_new_vertex_res,
-            cheapest_old_vertex_res,
-            &clique_graph_map,
-            &node_index_map,
-        );
+/// Returns the extension of the partial spanning tree obtained by attaching the given vertex from
+/// the clique graph to the given vertex from the result graph of the state together with the
+/// vertices that need to be inserted into the bags when filling them up (like
+/// [fill_bags_from_result_graph]) and the resulting width and sum of the sizes of the bags.
+fn find_beam_extension<O, S: Default + BuildHasher + Clone>(
+    clique_graph: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    clique_graph_map: &HashMap<NodeIndex, HashSet<NodeIndex, S>, S>,
+    state: &BeamState<O, S>,
+    position: usize,
+    old_vertex_res: NodeIndex,
+    new_vertex_clique: NodeIndex,
+    tree_edges: Vec<(NodeIndex, NodeIndex)>,
+) -> BeamExtension<S> {
+    let new_bag = clique_graph
+        .node_weight(new_vertex_clique)
+        .expect("Vertices in clique graph should have bags as weights");
+    let old_bag = state
+        .result_graph
+        .node_weight(old_vertex_res)
+        .expect("Vertex should have bag as weight");
+
+    // The path from the new vertex to a vertex of the result graph leads through the vertex it is
+    // attached to
+    let mut insertions: HashSet<(NodeIndex, NodeIndex), S> = Default::default();
+    for vertex_from_starting_graph in new_bag.difference(old_bag) {
+        if let Some(vertices_in_clique_graph) = clique_graph_map.get(vertex_from_starting_graph) {
+            for vertex_in_clique_graph in vertices_in_clique_graph {
+                if let Some(vertex_res_graph) = state.node_index_map.get(vertex_in_clique_graph) {
+                    if *vertex_res_graph == old_vertex_res {
+                        insertions.insert((old_vertex_res, *vertex_from_starting_graph));
+                        continue;
+                    }
+                    let mut path: Vec<NodeIndex> =
+                        petgraph::algo::simple_paths::all_simple_paths::<Vec<NodeIndex>, _>(
+                            &state.result_graph,
+                            old_vertex_res,
+                            *vertex_res_graph,
+                            0,
+                            None,
+                        )
+                        .next()
+                        .expect("There should be a path in the tree");
+                    // Last element is the vertex containing the vertex from the starting graph
+                    path.pop();
+                    insertions.extend(
+                        path.into_iter()
+                            .map(|vertex_res| (vertex_res, *vertex_from_starting_graph)),
+                    );
+                }
+            }
+        }
     }
+    insertions.retain(|(vertex_res, vertex_from_starting_graph)| {
+        !state
+            .result_graph
+            .node_weight(*vertex_res)
+            .expect("Vertex should have bag as weight")
+            .contains(vertex_from_starting_graph)
+    });
+
+    let mut number_of_insertions: HashMap<NodeIndex, usize, S> = Default::default();
+    for (vertex_res, _) in insertions.iter() {
+        *number_of_insertions.entry(*vertex_res).or_default() += 1;
+    }
+    let biggest_bag_size = number_of_insertions
+        .iter()
+        .map(|(vertex_res, number_of_insertions)| {
+            state
+                .result_graph
+                .node_weight(*vertex_res)
+                .expect("Vertex should have bag as weight")
+                .len()
+                + number_of_insertions
+        })
+        .chain([state.width + 1, new_bag.len()])
+        .max()
+        .expect("There should be bags");
+
+    BeamExtension {
+        state: position,
+        old_vertex_res,
+        new_vertex_clique,
+        tree_edges,
+        width: biggest_bag_size.saturating_sub(1),
+        sum_of_bag_sizes: state.sum_of_bag_sizes + new_bag.len() + insertions.len(),
+        insertions,
+    }
+}
 
-    result_graph
+/// Returns the partial spanning tree obtained by applying the extension to the given state.
+fn extend_beam_state<O: Default, S: BuildHasher + Clone>(
+    clique_graph: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    mut state: BeamState<O, S>,
+    extension: BeamExtension<S>,
+) -> BeamState<O, S> {
+    let new_vertex_res = state.result_graph.add_node(
+        clique_graph
+            .node_weight(extension.new_vertex_clique)
+            .expect("Vertices in clique graph should have bags as weights")
+            .clone(),
+    );
+    state
+        .node_index_map
+        .insert(extension.new_vertex_clique, new_vertex_res);
+    state
+        .clique_graph_vertices
+        .push(extension.new_vertex_clique);
+    state
+        .result_graph
+        .add_edge(extension.old_vertex_res, new_vertex_res, O::default());
+
+    // Update currently interesting vertices
+    for neighbor in clique_graph.neighbors(extension.new_vertex_clique) {
+        if !state.node_index_map.contains_key(&neighbor) {
+            state
+                .currently_interesting_vertices
+                .insert((new_vertex_res, neighbor));
+        }
+    }
+    state
+        .currently_interesting_vertices
+        .retain(|(_, vertex_clique)| *vertex_clique != extension.new_vertex_clique);
+
+    for (vertex_res, vertex_from_starting_graph) in extension.insertions {
+        state
+            .result_graph
+            .node_weight_mut(vertex_res)
+            .expect("Vertex should have bag as weight")
+            .insert(vertex_from_starting_graph);
+    }
+
+    state.tree_edges = extension.tree_edges;
+    state.width = extension.width;
+    state.sum_of_bag_sizes = extension.sum_of_bag_sizes;
+    state
 }
 
-/// Finds the cheapest edge to a vertex not yet in the result graph trying find the vertex that minimizes
-/// the size of the biggest bag in the result graph if the vertex is added.
+/// Computes a tree decomposition like the FillWhilstMST [methods][crate::SpanningTreeConstructionMethod]
+/// except that prim's algorithm starts at the given root and in each step the next vertex is
+/// chosen uniformly at random from a [restricted candidate list][RestrictedCandidateList] instead
+/// of always choosing the cheapest one.
 ///
-/// Returns a tuple with a node index from the result graph in the first and node index from the clique graph
-/// in the second entry. The cheapest edge being the edge between these two nodes only they are different
-/// in different representations (result and clique graph respectively)
-fn find_vertex_that_minimizes_bag_size<O: Ord + Default + Clone, S: BuildHasher + Clone>(
+/// For FillWhilstMSTBagSize the candidates are ranked by the size of the biggest bag after adding
+/// them, for the other FillWhilstMST methods by the edge weights. FillWhilstMSTTree is handled like
+/// FillWhilstMST since both result in the same bags.
+///
+/// If a width bound is given, stops as soon as a bag contains more than width_bound + 1 vertices
+/// and returns the step and the clique that caused the overflow (see [WidthBoundExceeded]).
+///
+/// Panics: Panics if the spanning tree construction method is not one of the FillWhilstMST methods
+#[allow(clippy::too_many_arguments)]
+pub fn fill_bags_while_generating_mst_randomized<
+    O: Ord + Default + Clone + EdgeWeightValue,
+    S: Default + BuildHasher + Clone,
+>(
     clique_graph: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
-    result_graph: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
-    currently_interesting_vertices: &HashSet<(NodeIndex, NodeIndex), S>,
-    clique_graph_map: &HashMap<NodeIndex, HashSet<NodeIndex, S>, S>,
-    node_index_map: &HashMap<NodeIndex, NodeIndex, S>,
-) -> (NodeIndex, NodeIndex) {
-    *currently_interesting_vertices
-        .iter()
-        .min_by_key(|(vertex_res_graph, interesting_vertex_clique_graph)| {
-            // Clone result graph
-            let mut result_graph: Graph<HashSet<NodeIndex, S>, O, Undirected> = result_graph.clone();
-
-            // Update result graph
-            let cheapest_new_vertex_res = result_graph.add_node(
-                clique_graph
-                    .node_weight(*interesting_vertex_clique_graph)
-                    .expect("Vertices in clique graph should have bags as weights")
-                    .clone(),
-            );
+    edge_weight_heuristic: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    clique_graph_map: HashMap<NodeIndex, HashSet<NodeIndex, S>, S>,
+    spanning_tree_construction_method: SpanningTreeConstructionMethod,
+    root: NodeIndex,
+    restricted_candidate_list: RestrictedCandidateList,
+    width_bound: Option<usize>,
+    rng: &mut impl Rng,
+) -> Result<Graph<HashSet<NodeIndex, S>, O, Undirected>, WidthBoundExceeded> {
+    assert!(
+        matches!(
+            spanning_tree_construction_method,
+            SpanningTreeConstructionMethod::FillWhilstMST
+                | SpanningTreeConstructionMethod::FillWhilstMSTEdgeUpdate
+                | SpanningTreeConstructionMethod::FillWhilstMSTTree
+                | SpanningTreeConstructionMethod::FillWhilstMSTBagSize
+        ),
+        "Only the FillWhilstMST methods can be randomized"
+    );
+    let ranked_by_width =
+        spanning_tree_construction_method == SpanningTreeConstructionMethod::FillWhilstMSTBagSize;
+    fill_bags_while_generating_mst_from_root(
+        clique_graph,
+        &clique_graph_map,
+        root,
+        |result_graph, node_index_map, currently_interesting_vertices| {
+            if ranked_by_width {
+                choose_from_restricted_candidate_list(
+                    currently_interesting_vertices
+                        .iter()
+                        .map(|(vertex_res_graph, interesting_vertex_clique_graph)| {
+                            let width = find_width_after_adding_vertex(
+                                clique_graph,
+                                result_graph,
+                                *vertex_res_graph,
+                                *interesting_vertex_clique_graph,
+                                &clique_graph_map,
+                                node_index_map,
+                            );
+                            (
+                                width,
+                                width as f64,
+                                (*vertex_res_graph, *interesting_vertex_clique_graph),
+                            )
+                        })
+                        .collect(),
+                    restricted_candidate_list,
+                    rng,
+                )
+            } else {
+                choose_from_restricted_candidate_list(
+                    currently_interesting_vertices
+                        .iter()
+                        .map(|(vertex_res_graph, interesting_vertex_clique_graph)| {
+                            let weight = edge_weight_heuristic(
+                                result_graph
+                                    .node_weight(*vertex_res_graph)
+                                    .expect("Vertices should have bags as weight"),
+                                clique_graph
+                                    .node_weight(*interesting_vertex_clique_graph)
+                                    .expect("Vertices should have bags as weight"),
+                            );
+                            let value = weight.edge_weight_value();
+                            (
+                                weight,
+                                value,
+                                (*vertex_res_graph, *interesting_vertex_clique_graph),
+                            )
+                        })
+                        .collect(),
+                    restricted_candidate_list,
+                    rng,
+                )
+            }
+        },
+        |first_bag, second_bag| {
+            if ranked_by_width {
+                O::default()
+            } else {
+                edge_weight_heuristic(first_bag, second_bag)
+            }
+        },
+        spanning_tree_construction_method
+            == SpanningTreeConstructionMethod::FillWhilstMSTEdgeUpdate,
+        width_bound,
+    )
+}
 
-            result_graph.add_edge(
-                *vertex_res_graph,
-                cheapest_new_vertex_res,
-                O::default(),
-            );
+/// Chooses one of the candidates uniformly at random from the restricted candidate list.
+///
+/// Each candidate consists of a key it is ranked by, its numeric value (used for
+/// [RestrictedCandidateList::Alpha]) and the tuple of vertices (from the result and the clique
+/// graph respectively) that would be connected.
+fn choose_from_restricted_candidate_list<K: Ord>(
+    mut candidates: Vec<(K, f64, (NodeIndex, NodeIndex))>,
+    restricted_candidate_list: RestrictedCandidateList,
+    rng: &mut impl Rng,
+) -> (NodeIndex, NodeIndex) {
+    // Sort by the vertices as well, so the result only depends on the rng and not on the iteration
+    // order of the interesting vertices
+    candidates.sort_by(
+        |(first_key, _, first_vertices), (second_key, _, second_vertices)| {
+            first_key
+                .cmp(second_key)
+                .then(first_vertices.cmp(second_vertices))
+        },
+    );
 
-            fill_bags_from_result_graph(
-                &mut result_graph,
-                cheapest_new_vertex_res,
-                *vertex_res_graph,
-                clique_graph_map,
-                node_index_map
-            );
+    let number_of_restricted_candidates = match restricted_candidate_list {
+        RestrictedCandidateList::TopK(k) => k.clamp(1, candidates.len()),
+        RestrictedCandidateList::Alpha(alpha) => {
+            let best_value = candidates
+                .first()
+                .expect("There should be interesting vertices since there are vertices left and the graph is connected")
+                .1;
+            let worst_value = candidates
+                .iter()
+                .map(|(_, value, _)| *value)
+                .fold(best_value, f64::max);
+            let threshold = best_value + alpha * (worst_value - best_value);
+            candidates
+                .iter()
+                .take_while(|(_, value, _)| *value <= threshold)
+                .count()
+                .max(1)
+        }
+    };
 
-            // Find treewidth (biggest bag size) of 
-            crate::find_width_of_tree_decomposition(&result_graph)
-        }).expect("There should be interesting vertices since there are vertices left and the graph is connected")
+    candidates[rng.gen_range(0..number_of_restricted_candidates)].2
 }
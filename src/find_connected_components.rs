@@ -30,6 +30,46 @@ where
     })
 }
 
+/// Returns the connected components of the subgraph induced by the vertices.
+pub(crate) fn find_connected_components_of_induced_subgraph<N, E, S: Default + BuildHasher>(
+    graph: &Graph<N, E, Undirected>,
+    vertices: &HashSet<NodeIndex, S>,
+) -> Vec<Vec<NodeIndex>> {
+    let mut visited: HashSet<NodeIndex, S> = Default::default();
+    let mut components = Vec::new();
+    let mut sorted_vertices: Vec<NodeIndex> = vertices.iter().cloned().collect();
+    sorted_vertices.sort();
+    for start_vertex in sorted_vertices {
+        if !visited.insert(start_vertex) {
+            continue;
+        }
+        let mut component = vec![start_vertex];
+        let mut stack = vec![start_vertex];
+        while let Some(vertex) = stack.pop() {
+            for neighbour in graph.neighbors(vertex) {
+                if vertices.contains(&neighbour) && visited.insert(neighbour) {
+                    component.push(neighbour);
+                    stack.push(neighbour);
+                }
+            }
+        }
+        components.push(component);
+    }
+    components
+}
+
+/// Returns the subgraph induced by the vertices. The vertices of the subgraph are weighted by the
+/// vertices of the graph they correspond to.
+pub(crate) fn find_induced_subgraph<N, E: Clone, S: BuildHasher>(
+    graph: &Graph<N, E, Undirected>,
+    vertices: &HashSet<NodeIndex, S>,
+) -> Graph<NodeIndex, E, Undirected> {
+    graph.filter_map(
+        |vertex, _| vertices.contains(&vertex).then_some(vertex),
+        |_, weight| Some(weight.clone()),
+    )
+}
+
 /// Breadth first search implemented iteratively using a stack
 fn breadth_first_search<G, S: Default + BuildHasher>(
     graph: &G,
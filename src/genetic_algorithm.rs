@@ -0,0 +1,356 @@
+use std::{
+    collections::{HashMap, HashSet},
+    hash::BuildHasher,
+};
+
+use petgraph::{graph::NodeIndex, unionfind::UnionFind, visit::EdgeRef, Graph, Undirected};
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{
+    lexicographic_cost, random_edge_exchange, BudgetTracker, FilledSpanningTree, SearchBudget,
+};
+
+/// Configuration of the genetic algorithm over spanning trees of the clique graph.
+///
+/// population_size The number of spanning trees in each generation
+///
+/// elite_size The number of best spanning trees that are copied into the next generation unchanged
+///
+/// tournament_size The number of spanning trees that compete in a tournament when selecting a
+/// parent for crossover
+///
+/// mutation_rate The probability that a child is mutated by an edge exchange after crossover
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub struct GeneticAlgorithmConfiguration {
+    pub population_size: usize,
+    pub elite_size: usize,
+    pub tournament_size: usize,
+    pub mutation_rate: f64,
+}
+
+/// A spanning tree of the clique graph (given by its edges) together with its cost.
+struct Individual {
+    tree_edges: Vec<(NodeIndex, NodeIndex)>,
+    cost: f64,
+}
+
+/// Improves a spanning tree of the given clique graph (e.g. the one returned by
+/// [compute_treewidth_upper_bound][crate::compute_treewidth_upper_bound]) using a genetic algorithm.
+///
+/// The initial population consists of a minimum spanning tree of the clique graph (the same tree
+/// that is used by [SpanningTreeConstructionMethod::MSTAndFill][crate::SpanningTreeConstructionMethod])
+/// and random spanning trees. The fitness of a spanning tree is the width of the tree decomposition
+/// obtained by filling up the bags along the paths of the tree, with the sum of the sizes of the
+/// bags as a tie breaker. The bags are computed incrementally (so mutations only refill the
+/// affected vertices), which results in the same bags as
+/// [fill_bags_along_paths][crate::fill_bags_along_paths] (see the test
+/// `test_edge_exchange_matches_filling_from_scratch` of the spanning tree local search).
+///
+/// In each generation, the best spanning trees are kept and the rest of the population is replaced
+/// by children. Parents are chosen by tournament selection. The child of two parents is obtained by
+/// edge-set crossover: The union of the edges of both parents is repaired to a spanning tree using
+/// Kruskal's algorithm with random weights, preferring edges that are contained in both parents.
+/// Afterwards, the child is mutated by an edge exchange with the configured probability. Each
+/// generation counts as one iteration of the budget.
+///
+/// Returns a tuple with the tree decomposition of the best spanning tree found (with the same vertex
+/// indices as the clique graph) and its width.
+///
+/// Panics: Panics if the clique graph is empty or not connected
+pub fn improve_spanning_tree_using_genetic_algorithm<
+    O: Clone + PartialOrd,
+    S: Default + BuildHasher + Clone,
+>(
+    clique_graph: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    genetic_algorithm_configuration: GeneticAlgorithmConfiguration,
+    search_budget: SearchBudget,
+    rng: &mut impl Rng,
+) -> (Graph<HashSet<NodeIndex, S>, O, Undirected>, usize) {
+    let minimum_spanning_tree: Graph<HashSet<NodeIndex, S>, O, Undirected> =
+        petgraph::data::FromElements::from_elements(petgraph::algo::min_spanning_tree(
+            clique_graph,
+        ));
+    assert_eq!(
+        minimum_spanning_tree.edge_count() + 1,
+        clique_graph.node_count(),
+        "Clique graph should be connected and not empty"
+    );
+
+    let clique_graph_edges: Vec<(NodeIndex, NodeIndex)> = clique_graph
+        .edge_references()
+        .map(|edge| normalize_edge((edge.source(), edge.target())))
+        .collect();
+    let clique_graph_is_tree = clique_graph_edges.len() + 1 == clique_graph.node_count();
+
+    let number_of_vertices_in_original_graph = clique_graph
+        .node_weights()
+        .flatten()
+        .collect::<HashSet<_, S>>()
+        .len();
+    let cost = |filled_spanning_tree: &FilledSpanningTree<S>| {
+        let (width, sum_of_bag_sizes) = filled_spanning_tree.width_and_sum_of_bag_sizes();
+        lexicographic_cost(
+            width,
+            sum_of_bag_sizes,
+            clique_graph.node_count() * number_of_vertices_in_original_graph,
+        )
+    };
+    let evaluate = |tree_edges: Vec<(NodeIndex, NodeIndex)>| {
+        let filled_spanning_tree =
+            FilledSpanningTree::new(clique_graph, tree_edges.iter().cloned());
+        Individual {
+            cost: cost(&filled_spanning_tree),
+            tree_edges,
+        }
+    };
+
+    let population_size = genetic_algorithm_configuration.population_size.max(1);
+    let mut population: Vec<Individual> = Vec::with_capacity(population_size);
+    population.push(evaluate(
+        minimum_spanning_tree
+            .edge_references()
+            .map(|edge| normalize_edge((edge.source(), edge.target())))
+            .collect(),
+    ));
+    while population.len() < population_size {
+        let mut shuffled_edges = clique_graph_edges.clone();
+        shuffled_edges.shuffle(rng);
+        population.push(evaluate(kruskal(clique_graph.node_count(), shuffled_edges)));
+    }
+    sort_population(&mut population);
+
+    let mut budget_tracker = BudgetTracker::new(search_budget);
+    while !clique_graph_is_tree && budget_tracker.next_iteration() {
+        let elite_size = genetic_algorithm_configuration
+            .elite_size
+            .clamp(1, population_size);
+        let mut next_population: Vec<Individual> = Vec::with_capacity(population_size);
+
+        while next_population.len() < population_size - elite_size {
+            let first_parent = tournament_selection(
+                &population,
+                genetic_algorithm_configuration.tournament_size,
+                rng,
+            );
+            let second_parent = tournament_selection(
+                &population,
+                genetic_algorithm_configuration.tournament_size,
+                rng,
+            );
+            let child_edges = crossover(
+                clique_graph.node_count(),
+                &first_parent.tree_edges,
+                &second_parent.tree_edges,
+                rng,
+            );
+
+            let mut filled_spanning_tree =
+                FilledSpanningTree::new(clique_graph, child_edges.iter().cloned());
+            let mut child_edges: HashSet<(NodeIndex, NodeIndex), S> =
+                child_edges.into_iter().collect();
+            if rng.gen::<f64>() < genetic_algorithm_configuration.mutation_rate {
+                let (removed_edge, added_edge) =
+                    random_edge_exchange(&filled_spanning_tree, &clique_graph_edges, rng);
+                filled_spanning_tree.exchange_edges(removed_edge, added_edge);
+                child_edges.remove(&normalize_edge(removed_edge));
+                child_edges.insert(normalize_edge(added_edge));
+            }
+
+            let mut tree_edges: Vec<(NodeIndex, NodeIndex)> = child_edges.into_iter().collect();
+            // Sorting makes the individuals independent of the iteration order of the hash set
+            tree_edges.sort();
+            next_population.push(Individual {
+                cost: cost(&filled_spanning_tree),
+                tree_edges,
+            });
+        }
+
+        population.truncate(elite_size);
+        population.append(&mut next_population);
+        sort_population(&mut population);
+    }
+
+    let best_individual = population
+        .first()
+        .expect("Population should contain at least one individual");
+    let best_tree_decomposition =
+        FilledSpanningTree::new(clique_graph, best_individual.tree_edges.iter().cloned())
+            .to_tree_decomposition(clique_graph);
+    let treewidth = crate::find_width_of_tree_decomposition(&best_tree_decomposition);
+    (best_tree_decomposition, treewidth)
+}
+
+/// Returns the edge-set crossover of the two spanning trees, that is a spanning tree consisting of
+/// edges of the parents. Edges contained in both parents are preferred, otherwise the edges are
+/// chosen randomly.
+fn crossover(
+    number_of_vertices: usize,
+    first_parent: &[(NodeIndex, NodeIndex)],
+    second_parent: &[(NodeIndex, NodeIndex)],
+    rng: &mut impl Rng,
+) -> Vec<(NodeIndex, NodeIndex)> {
+    let mut occurrences: HashMap<(NodeIndex, NodeIndex), usize> = HashMap::new();
+    for edge in first_parent.iter().chain(second_parent.iter()) {
+        *occurrences.entry(*edge).or_default() += 1;
+    }
+    let mut common_edges: Vec<(NodeIndex, NodeIndex)> = Vec::new();
+    let mut other_edges: Vec<(NodeIndex, NodeIndex)> = Vec::new();
+    for edge in first_parent.iter().chain(second_parent.iter()) {
+        match occurrences.remove(edge) {
+            Some(2) => common_edges.push(*edge),
+            Some(_) => other_edges.push(*edge),
+            None => {}
+        }
+    }
+    common_edges.shuffle(rng);
+    other_edges.shuffle(rng);
+
+    // The union of two spanning trees is connected, so repairing it yields a spanning tree again
+    kruskal(
+        number_of_vertices,
+        common_edges.into_iter().chain(other_edges),
+    )
+}
+
+/// Returns the edges of a spanning forest of the graph with the given vertices and edges, choosing
+/// the edges greedily in the given order.
+fn kruskal(
+    number_of_vertices: usize,
+    edges: impl IntoIterator<Item = (NodeIndex, NodeIndex)>,
+) -> Vec<(NodeIndex, NodeIndex)> {
+    let mut components: UnionFind<usize> = UnionFind::new(number_of_vertices);
+    let mut tree_edges: Vec<(NodeIndex, NodeIndex)> = edges
+        .into_iter()
+        .filter(|(first_vertex, second_vertex)| {
+            components.union(first_vertex.index(), second_vertex.index())
+        })
+        .collect();
+    tree_edges.sort();
+    tree_edges
+}
+
+/// Returns the best of tournament_size randomly chosen individuals of the population.
+fn tournament_selection<'a>(
+    population: &'a [Individual],
+    tournament_size: usize,
+    rng: &mut impl Rng,
+) -> &'a Individual {
+    (0..tournament_size.max(1))
+        .map(|_| {
+            population
+                .choose(rng)
+                .expect("Population should contain at least one individual")
+        })
+        .min_by(|first_individual, second_individual| {
+            first_individual.cost.total_cmp(&second_individual.cost)
+        })
+        .expect("Tournament should contain at least one individual")
+}
+
+/// Sorts the population such that the best individuals come first.
+fn sort_population(population: &mut [Individual]) {
+    population.sort_by(|first_individual, second_individual| {
+        first_individual.cost.total_cmp(&second_individual.cost)
+    });
+}
+
+fn normalize_edge((first_vertex, second_vertex): (NodeIndex, NodeIndex)) -> (NodeIndex, NodeIndex) {
+    (
+        first_vertex.min(second_vertex),
+        first_vertex.max(second_vertex),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::RandomState;
+
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::check_tree_decomposition;
+
+    const GENETIC_ALGORITHM_CONFIGURATION: GeneticAlgorithmConfiguration =
+        GeneticAlgorithmConfiguration {
+            population_size: 10,
+            elite_size: 2,
+            tournament_size: 3,
+            mutation_rate: 0.5,
+        };
+
+    #[test]
+    fn test_genetic_algorithm_on_test_graphs() {
+        for i in 1..3 {
+            let test_graph = crate::tests::setup_test_graph(i);
+            let (clique_graph, _, _, _, _, treewidth) =
+                crate::compute_treewidth_upper_bound::<_, _, _, RandomState>(
+                    &test_graph.graph,
+                    crate::constant,
+                    crate::SpanningTreeConstructionMethod::MSTAndFill,
+                    false,
+                    None,
+                );
+
+            let mut rng = StdRng::seed_from_u64(i as u64);
+            let (tree_decomposition, improved_treewidth) =
+                improve_spanning_tree_using_genetic_algorithm(
+                    &clique_graph,
+                    GENETIC_ALGORITHM_CONFIGURATION,
+                    SearchBudget::Iterations(20),
+                    &mut rng,
+                );
+
+            assert!(check_tree_decomposition(
+                &test_graph.graph,
+                &tree_decomposition,
+                &None,
+                &None
+            ));
+            assert!(improved_treewidth <= treewidth);
+            assert_eq!(
+                improved_treewidth, test_graph.treewidth,
+                "Test graph {} failed",
+                i
+            );
+        }
+    }
+
+    #[test]
+    fn test_crossover_returns_spanning_tree() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let (graph, clique_graph) =
+            crate::tests::setup_partial_k_tree_component_with_clique_graph(4, 40, 40, &mut rng);
+        let clique_graph_edges: Vec<(NodeIndex, NodeIndex)> = clique_graph
+            .edge_references()
+            .map(|edge| normalize_edge((edge.source(), edge.target())))
+            .collect();
+
+        for _ in 0..20 {
+            let mut shuffled_edges = clique_graph_edges.clone();
+            shuffled_edges.shuffle(&mut rng);
+            let first_parent = kruskal(clique_graph.node_count(), shuffled_edges.clone());
+            shuffled_edges.shuffle(&mut rng);
+            let second_parent = kruskal(clique_graph.node_count(), shuffled_edges);
+
+            let child = crossover(
+                clique_graph.node_count(),
+                &first_parent,
+                &second_parent,
+                &mut rng,
+            );
+            assert_eq!(child.len() + 1, clique_graph.node_count());
+            assert!(child
+                .iter()
+                .all(|edge| first_parent.contains(edge) || second_parent.contains(edge)));
+
+            let tree_decomposition =
+                FilledSpanningTree::new(&clique_graph, child).to_tree_decomposition(&clique_graph);
+            assert!(check_tree_decomposition(
+                &graph,
+                &tree_decomposition,
+                &None,
+                &None
+            ));
+        }
+    }
+}
@@ -1,37 +1,152 @@
+mod balanced_separators;
 mod check_tree_decomposition;
+mod chordal_graph;
 mod clique_graph_edge_weight_functions;
+mod clique_minimal_separator_decomposition;
 mod compute_treewidth_upper_bound;
 mod construct_clique_graph;
+mod dynamic_programming;
+mod elimination_ordering;
 mod fill_bags_along_paths;
 mod fill_bags_while_generating_mst;
 mod find_connected_components;
 mod find_maximum_cliques;
 mod find_width_of_tree_decomposition;
 mod generate_partial_k_tree;
+mod genetic_algorithm;
+mod local_search;
 mod maximum_minimum_degree_heuristic;
+mod minimal_triangulation;
+mod minimize_tree_decomposition;
+mod minimum_spanning_tree_enumeration;
+mod multi_start;
+mod nice_tree_decomposition;
+mod path_decomposition;
+mod reduction_rules;
+mod spanning_tree_local_search;
+mod torso_refinement;
+mod tree_decomposition_objective;
+mod treedepth;
+mod triangulation;
+mod twin_compression;
+mod uai;
+mod vertex_weights;
 
 // Imports for using the library
+pub use balanced_separators::{
+    compute_graph_partition, find_adhesions, find_balanced_separator, partition_graph,
+    BalancedSeparator, GraphPartition, SeparatorBalance, SeparatorLocation,
+};
 pub(crate) use check_tree_decomposition::check_tree_decomposition;
+pub(crate) use chordal_graph::find_maximal_cliques_of_chordal_graph;
+pub use chordal_graph::{
+    find_perfect_elimination_ordering, is_chordal, maximum_cardinality_search,
+};
 pub use clique_graph_edge_weight_functions::*;
+pub use clique_minimal_separator_decomposition::{
+    compute_treewidth_upper_bound_using_clique_separators,
+    find_clique_minimal_separator_decomposition,
+};
+pub(crate) use compute_treewidth_upper_bound::{
+    compute_tree_decomposition_of_components, find_cliques,
+};
 pub use compute_treewidth_upper_bound::{
-    compute_treewidth_upper_bound, compute_treewidth_upper_bound_not_connected,
-    SpanningTreeConstructionMethod,
+    compute_treewidth_upper_bound, compute_treewidth_upper_bound_bounded,
+    compute_treewidth_upper_bound_not_connected, compute_treewidth_upper_bound_with_objective,
+    compute_treewidth_upper_bound_with_optimality, CliqueGraphTreeDecomposition,
+    SpanningTreeConstructionMethod, WidthBoundExceeded,
 };
 pub(crate) use construct_clique_graph::{construct_clique_graph, construct_clique_graph_with_bags};
+pub use dynamic_programming::{
+    find_maximum_weight_independent_set, find_minimum_vertex_cover, is_k_colorable,
+    solve_dynamic_program, DynamicProgram, KColorability, MaximumWeightIndependentSet,
+    MinimumVertexCover,
+};
+pub use elimination_ordering::{
+    compute_treewidth_upper_bound_using_elimination_ordering,
+    elimination_ordering_from_tree_decomposition, find_elimination_ordering,
+    find_induced_width_of_elimination_ordering, tree_decomposition_from_elimination_ordering,
+    EliminationOrderingHeuristic,
+};
 pub(crate) use fill_bags_along_paths::{
     fill_bags_along_paths, fill_bags_along_paths_using_structure,
 };
+pub use fill_bags_while_generating_mst::RestrictedCandidateList;
 pub(crate) use fill_bags_while_generating_mst::{
-    fill_bags_while_generating_mst, fill_bags_while_generating_mst_least_bag_size,
-    fill_bags_while_generating_mst_update_edges, fill_bags_while_generating_mst_using_tree,
+    fill_bags_while_generating_mst, fill_bags_while_generating_mst_beam,
+    fill_bags_while_generating_mst_least_bag_size, fill_bags_while_generating_mst_least_cost,
+    fill_bags_while_generating_mst_randomized, fill_bags_while_generating_mst_update_edges,
+    fill_bags_while_generating_mst_using_tree,
+};
+pub(crate) use find_connected_components::{
+    find_connected_components, find_connected_components_of_induced_subgraph, find_induced_subgraph,
 };
-pub(crate) use find_connected_components::find_connected_components;
 pub(crate) use find_maximum_cliques::{find_maximal_cliques, find_maximum_cliques_bounded};
 pub(crate) use find_width_of_tree_decomposition::find_width_of_tree_decomposition;
 pub use generate_partial_k_tree::{
     generate_k_tree, generate_partial_k_tree, generate_partial_k_tree_with_guaranteed_treewidth,
 };
+pub use genetic_algorithm::{
+    improve_spanning_tree_using_genetic_algorithm, GeneticAlgorithmConfiguration,
+};
+pub use local_search::{
+    improve_elimination_ordering_using_local_search, improve_tree_decomposition_using_local_search,
+    LocalSearchAcceptance, SearchBudget,
+};
+pub(crate) use local_search::{lexicographic_cost, AnnealingSchedule, BudgetTracker, TabuList};
 pub(crate) use maximum_minimum_degree_heuristic::maximum_minimum_degree_plus;
+pub use minimal_triangulation::{
+    compute_treewidth_upper_bound_using_minimal_triangulation,
+    find_maximal_cliques_of_triangulation, find_minimal_elimination_ordering,
+};
+pub use minimize_tree_decomposition::{minimize_tree_decomposition, MinimizationReport};
+pub use minimum_spanning_tree_enumeration::{
+    compute_treewidth_upper_bound_using_tied_minimum_spanning_trees, MinimumSpanningTreeSelection,
+    TiedMinimumSpanningTrees,
+};
+pub use multi_start::{
+    compute_treewidth_upper_bound_multi_start,
+    compute_treewidth_upper_bound_multi_start_with_objective, MultiStartConfiguration,
+};
+pub use nice_tree_decomposition::{
+    make_binary_tree_decomposition, make_nice_tree_decomposition, NiceBag, NiceNodeType,
+    NiceTreeDecomposition, RootedTreeDecomposition,
+};
+pub use path_decomposition::{
+    compute_pathwidth_upper_bound, compute_pathwidth_upper_bound_using_clique_path,
+    path_decomposition_from_tree_decomposition,
+};
+pub use reduction_rules::{
+    compute_treewidth_upper_bound_using_reductions, expand_tree_decomposition, reduce_graph,
+    ReducedGraph, Reduction, ReductionRule,
+};
+pub use spanning_tree_local_search::improve_spanning_tree_using_local_search;
+pub(crate) use spanning_tree_local_search::{random_edge_exchange, FilledSpanningTree};
+pub use torso_refinement::{
+    find_optimal_elimination_ordering, refine_tree_decomposition_using_torsos,
+};
+pub use tree_decomposition_objective::{
+    find_metrics_of_tree_decomposition, SecondaryObjective, TreeDecompositionMetrics,
+    TreeDecompositionObjective,
+};
+pub use treedepth::{
+    compute_treedepth_upper_bound, find_depth_of_elimination_forest, find_elimination_forest,
+};
+pub use triangulation::{
+    check_triangulation, find_triangulation_of_tree_decomposition, Triangulation,
+};
+pub use twin_compression::{
+    compute_treewidth_upper_bound_using_twin_compression, find_true_twin_classes,
+};
+pub use uai::{
+    construct_junction_tree, parse_uai, read_uai_file, Factor, JunctionTree, UaiError, UaiModel,
+    UaiNetworkType,
+};
+pub use vertex_weights::{
+    compute_vertex_weighted_treewidth_upper_bound, ObjectiveValue,
+    VertexWeightedEdgeWeightFunction, VertexWeightedObjective, VertexWeightedTreeDecomposition,
+    VertexWeights,
+};
 
 // Debug version
 #[cfg(debug_assertions)]
@@ -81,6 +196,18 @@ pub(crate) mod tests {
         SpanningTreeConstructionMethod::MSTAndUseTreeStructure,
     ];
 
+    pub const LOCAL_SEARCH_ACCEPTANCES: [LocalSearchAcceptance; 3] = [
+        LocalSearchAcceptance::HillClimbing,
+        LocalSearchAcceptance::SimulatedAnnealing {
+            initial_temperature: 1.0,
+            cooling_factor: 0.99,
+        },
+        LocalSearchAcceptance::Tabu {
+            tabu_tenure: 3,
+            sampled_moves: 5,
+        },
+    ];
+
     /// Sets up test graph:
     ///
     /// Test graph 0 has:
@@ -290,6 +417,65 @@ pub(crate) mod tests {
         }
     }
 
+    /// Clique graph of a test graph with the edge weights of [negative_intersection]
+    pub type TestCliqueGraph = Graph<
+        std::collections::HashSet<NodeIndex, std::hash::RandomState>,
+        i32,
+        petgraph::prelude::Undirected,
+    >;
+
+    /// Sets up the largest connected component of a random partial k-tree (see
+    /// [generate_partial_k_tree]) together with the clique graph of its maximal cliques using
+    /// [negative_intersection] as edge weight function.
+    pub fn setup_partial_k_tree_component_with_clique_graph(
+        k: usize,
+        n: usize,
+        p: usize,
+        rng: &mut impl rand::Rng,
+    ) -> (
+        Graph<i32, i32, petgraph::prelude::Undirected>,
+        TestCliqueGraph,
+    ) {
+        let mut graph = generate_partial_k_tree(k, n, p, rng).expect("k should be smaller than n");
+        let component = find_connected_components::<Vec<_>, _, _, std::hash::RandomState>(&graph)
+            .max_by_key(|component| component.len())
+            .expect("Graph shouldn't be empty");
+        graph.retain_nodes(|_, vertex| component.contains(&vertex));
+
+        let cliques: Vec<Vec<_>> =
+            find_maximal_cliques::<Vec<_>, _, std::hash::RandomState>(&graph).collect();
+        let clique_graph = construct_clique_graph(cliques, negative_intersection);
+        (graph, clique_graph)
+    }
+
+    /// Sets up the connected test graphs 1 and 2 (see [setup_test_graph]) followed by random
+    /// partial k-trees with n vertices of which p percent of the edges are removed (see
+    /// [generate_partial_k_tree_with_guaranteed_treewidth]), each together with its treewidth. The
+    /// partial k-trees are generated from the seed and may be disconnected.
+    pub fn setup_test_graphs_and_partial_k_trees(
+        seed: u64,
+        number_of_partial_k_trees: usize,
+        k: usize,
+        n: usize,
+        p: usize,
+    ) -> Vec<(Graph<i32, i32, petgraph::prelude::Undirected>, usize)> {
+        let mut rng = <rand::rngs::StdRng as rand::SeedableRng>::seed_from_u64(seed);
+        let mut graphs: Vec<(Graph<i32, i32, petgraph::prelude::Undirected>, usize)> = (1..3)
+            .map(|i| {
+                let test_graph = setup_test_graph(i);
+                (test_graph.graph, test_graph.treewidth)
+            })
+            .collect();
+        for _ in 0..number_of_partial_k_trees {
+            graphs.push((
+                generate_partial_k_tree_with_guaranteed_treewidth(k, n, p, &mut rng)
+                    .expect("k should be smaller than n"),
+                k,
+            ));
+        }
+        graphs
+    }
+
     #[test]
     fn hash_test() {
         let mut test = true;
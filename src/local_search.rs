@@ -0,0 +1,482 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    hash::BuildHasher,
+    time::{Duration, Instant},
+};
+
+use petgraph::{graph::NodeIndex, Graph, Undirected};
+use rand::Rng;
+
+use crate::{
+    elimination_ordering::{adjacency_map, eliminate_vertex},
+    elimination_ordering_from_tree_decomposition, find_width_of_tree_decomposition,
+    tree_decomposition_from_elimination_ordering,
+};
+
+/// Budget for the search based improvement phases.
+///
+/// Iterations Stops after the given number of iterations (or generations)
+///
+/// Time Stops once the given duration has passed
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SearchBudget {
+    Iterations(usize),
+    Time(Duration),
+}
+
+/// Different acceptance criteria for the local search.
+///
+/// HillClimbing Samples a random move and accepts it if it doesn't make the solution worse
+///
+/// SimulatedAnnealing Samples a random move and accepts it if it doesn't make the solution worse or
+/// otherwise with probability exp(-delta / temperature). The temperature is multiplied by the
+/// cooling factor after each iteration
+///
+/// Tabu Samples a number of random moves and applies the best one that is not tabu, even if it
+/// makes the solution worse. The vertices/edges touched by a move stay tabu for the given number of
+/// iterations, unless a tabu move would improve the best solution found so far
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub enum LocalSearchAcceptance {
+    HillClimbing,
+    SimulatedAnnealing {
+        initial_temperature: f64,
+        cooling_factor: f64,
+    },
+    Tabu {
+        tabu_tenure: usize,
+        sampled_moves: usize,
+    },
+}
+
+/// Keeps track of the budget of a search.
+pub(crate) struct BudgetTracker {
+    budget: SearchBudget,
+    start: Instant,
+    iterations: usize,
+}
+
+impl BudgetTracker {
+    pub(crate) fn new(budget: SearchBudget) -> Self {
+        BudgetTracker {
+            budget,
+            start: Instant::now(),
+            iterations: 0,
+        }
+    }
+
+    /// Returns true and counts an iteration if the budget is not used up yet.
+    pub(crate) fn next_iteration(&mut self) -> bool {
+        let budget_left = match self.budget {
+            SearchBudget::Iterations(iterations) => self.iterations < iterations,
+            SearchBudget::Time(duration) => self.start.elapsed() < duration,
+        };
+        self.iterations += 1;
+        budget_left
+    }
+}
+
+/// Returns true if a move changing the cost of the current solution by delta should be accepted
+/// when using hill climbing or simulated annealing. The temperature is ignored for hill climbing.
+pub(crate) fn accept_move(
+    acceptance: LocalSearchAcceptance,
+    delta: f64,
+    temperature: f64,
+    rng: &mut impl Rng,
+) -> bool {
+    if delta <= 0.0 {
+        return true;
+    }
+    match acceptance {
+        LocalSearchAcceptance::SimulatedAnnealing { .. } => {
+            temperature > 0.0 && rng.gen::<f64>() < (-delta / temperature).exp()
+        }
+        _ => false,
+    }
+}
+
+/// Keeps track of the temperature when using simulated annealing. For the other acceptance criteria
+/// the temperature is 0.
+pub(crate) struct AnnealingSchedule {
+    acceptance: LocalSearchAcceptance,
+    temperature: f64,
+}
+
+impl AnnealingSchedule {
+    pub(crate) fn new(acceptance: LocalSearchAcceptance) -> Self {
+        let temperature = match acceptance {
+            LocalSearchAcceptance::SimulatedAnnealing {
+                initial_temperature,
+                ..
+            } => initial_temperature,
+            _ => 0.0,
+        };
+        AnnealingSchedule {
+            acceptance,
+            temperature,
+        }
+    }
+
+    /// Returns true if a move changing the cost of the current solution by delta should be accepted
+    /// at the current temperature (see [accept_move]).
+    pub(crate) fn accept(&self, delta: f64, rng: &mut impl Rng) -> bool {
+        accept_move(self.acceptance, delta, self.temperature, rng)
+    }
+
+    /// Multiplies the temperature by the cooling factor when using simulated annealing.
+    pub(crate) fn cool(&mut self) {
+        if let LocalSearchAcceptance::SimulatedAnnealing { cooling_factor, .. } = self.acceptance {
+            self.temperature *= cooling_factor;
+        }
+    }
+}
+
+/// Attributes of recent moves (e.g. the moved vertices or the exchanged edges) together with the
+/// iteration until which they are tabu.
+pub(crate) struct TabuList<T> {
+    entries: VecDeque<(T, usize)>,
+}
+
+impl<T: PartialEq> TabuList<T> {
+    pub(crate) fn new() -> Self {
+        TabuList {
+            entries: VecDeque::new(),
+        }
+    }
+
+    /// Removes the attributes that are no longer tabu in the given iteration.
+    pub(crate) fn expire(&mut self, iteration: usize) {
+        while self
+            .entries
+            .front()
+            .is_some_and(|(_, tabu_until)| *tabu_until < iteration)
+        {
+            self.entries.pop_front();
+        }
+    }
+
+    /// Returns true if the attribute is tabu.
+    pub(crate) fn contains(&self, attribute: &T) -> bool {
+        self.entries
+            .iter()
+            .any(|(tabu_attribute, _)| tabu_attribute == attribute)
+    }
+
+    /// Makes the attribute tabu until the given iteration.
+    pub(crate) fn insert(&mut self, attribute: T, tabu_until: usize) {
+        self.entries.push_back((attribute, tabu_until));
+    }
+}
+
+/// Returns the cost of a solution whose tree decomposition has the given width and sum of bag
+/// sizes, so that solutions are compared by their width first and the sum of the bag sizes second.
+/// The scale has to be an upper bound for the sum of the bag sizes.
+pub(crate) fn lexicographic_cost(width: usize, sum_of_bag_sizes: usize, scale: usize) -> f64 {
+    width as f64 + sum_of_bag_sizes as f64 / (scale as f64 + 1.0)
+}
+
+/// A move in the neighbourhood of an elimination ordering.
+///
+/// Swap Swaps the vertices at the two positions
+///
+/// Shift Removes the vertex at the first position and inserts it at the second position
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum OrderingMove {
+    Swap(usize, usize),
+    Shift(usize, usize),
+}
+
+impl OrderingMove {
+    fn random(length: usize, rng: &mut impl Rng) -> Self {
+        let first_position = rng.gen_range(0..length);
+        let mut second_position = rng.gen_range(0..length - 1);
+        if second_position >= first_position {
+            second_position += 1;
+        }
+        if rng.gen::<bool>() {
+            OrderingMove::Swap(first_position, second_position)
+        } else {
+            OrderingMove::Shift(first_position, second_position)
+        }
+    }
+
+    fn apply(&self, elimination_ordering: &mut Vec<NodeIndex>) {
+        match *self {
+            OrderingMove::Swap(first_position, second_position) => {
+                elimination_ordering.swap(first_position, second_position)
+            }
+            OrderingMove::Shift(from_position, to_position) => {
+                let vertex = elimination_ordering.remove(from_position);
+                elimination_ordering.insert(to_position, vertex);
+            }
+        }
+    }
+
+    /// Returns the vertices that are moved by this move in the given elimination ordering.
+    fn moved_vertices(&self, elimination_ordering: &[NodeIndex]) -> Vec<NodeIndex> {
+        match *self {
+            OrderingMove::Swap(first_position, second_position) => vec![
+                elimination_ordering[first_position],
+                elimination_ordering[second_position],
+            ],
+            OrderingMove::Shift(from_position, _) => vec![elimination_ordering[from_position]],
+        }
+    }
+}
+
+/// Improves the given tree decomposition using local search over elimination orderings.
+///
+/// Does this by converting the tree decomposition into an elimination ordering (see
+/// [elimination_ordering_from_tree_decomposition]), improving the ordering using
+/// [improve_elimination_ordering_using_local_search] and converting the best ordering found back
+/// into a tree decomposition using the elimination game. Since the tree decomposition can be the
+/// result of any [SpanningTreeConstructionMethod][crate::SpanningTreeConstructionMethod], this
+/// phase can be run after any of them.
+///
+/// Returns a tuple with the improved tree decomposition and its width, which is at most the width
+/// of the given tree decomposition.
+pub fn improve_tree_decomposition_using_local_search<
+    N,
+    E,
+    O: Default,
+    EO,
+    S: Default + BuildHasher + Clone,
+>(
+    graph: &Graph<N, E, Undirected>,
+    tree_decomposition: &Graph<HashSet<NodeIndex, S>, EO, Undirected>,
+    local_search_acceptance: LocalSearchAcceptance,
+    search_budget: SearchBudget,
+    rng: &mut impl Rng,
+) -> (Graph<HashSet<NodeIndex, S>, O, Undirected>, usize) {
+    let elimination_ordering = elimination_ordering_from_tree_decomposition(tree_decomposition);
+    let improved_elimination_ordering = improve_elimination_ordering_using_local_search::<N, E, S>(
+        graph,
+        elimination_ordering,
+        local_search_acceptance,
+        search_budget,
+        rng,
+    );
+
+    let improved_tree_decomposition: Graph<HashSet<NodeIndex, S>, O, Undirected> =
+        tree_decomposition_from_elimination_ordering(graph, &improved_elimination_ordering);
+    let treewidth = find_width_of_tree_decomposition(&improved_tree_decomposition);
+
+    (improved_tree_decomposition, treewidth)
+}
+
+/// Improves the given elimination ordering using swap and shift moves, accepting moves according
+/// to the given acceptance criterion until the budget is used up.
+///
+/// Orderings are compared by their induced width first and the sum of the sizes of the bags in the
+/// elimination game second (so the search can move along plateaus of equal width).
+///
+/// Returns the best elimination ordering found.
+pub fn improve_elimination_ordering_using_local_search<N, E, S: Default + BuildHasher + Clone>(
+    graph: &Graph<N, E, Undirected>,
+    elimination_ordering: Vec<NodeIndex>,
+    local_search_acceptance: LocalSearchAcceptance,
+    search_budget: SearchBudget,
+    rng: &mut impl Rng,
+) -> Vec<NodeIndex> {
+    let adjacency: HashMap<NodeIndex, HashSet<NodeIndex, S>, S> = adjacency_map(graph);
+    if elimination_ordering.len() < 2 {
+        return elimination_ordering;
+    }
+    let cost = |elimination_ordering: &[NodeIndex]| {
+        let (induced_width, sum_of_bag_sizes) =
+            find_induced_width_and_sum_of_bag_sizes(&adjacency, elimination_ordering);
+        lexicographic_cost(
+            induced_width,
+            sum_of_bag_sizes,
+            graph.node_count() * graph.node_count(),
+        )
+    };
+
+    let mut current_cost = cost(&elimination_ordering);
+    let mut best_cost = current_cost;
+    let mut best_elimination_ordering = elimination_ordering.clone();
+    let mut current_elimination_ordering = elimination_ordering;
+
+    let mut annealing_schedule = AnnealingSchedule::new(local_search_acceptance);
+    // Vertices that were moved recently
+    let mut tabu_list: TabuList<NodeIndex> = TabuList::new();
+    let mut budget_tracker = BudgetTracker::new(search_budget);
+    let mut iteration = 0;
+
+    while budget_tracker.next_iteration() {
+        iteration += 1;
+        match local_search_acceptance {
+            LocalSearchAcceptance::HillClimbing
+            | LocalSearchAcceptance::SimulatedAnnealing { .. } => {
+                let ordering_move = OrderingMove::random(current_elimination_ordering.len(), rng);
+                let mut candidate_elimination_ordering = current_elimination_ordering.clone();
+                ordering_move.apply(&mut candidate_elimination_ordering);
+                let candidate_cost = cost(&candidate_elimination_ordering);
+
+                if annealing_schedule.accept(candidate_cost - current_cost, rng) {
+                    current_elimination_ordering = candidate_elimination_ordering;
+                    current_cost = candidate_cost;
+                }
+                annealing_schedule.cool();
+            }
+            LocalSearchAcceptance::Tabu {
+                tabu_tenure,
+                sampled_moves,
+            } => {
+                tabu_list.expire(iteration);
+
+                let mut best_candidate: Option<(f64, Vec<NodeIndex>, Vec<NodeIndex>)> = None;
+                for _ in 0..sampled_moves.max(1) {
+                    let ordering_move =
+                        OrderingMove::random(current_elimination_ordering.len(), rng);
+                    let moved_vertices =
+                        ordering_move.moved_vertices(&current_elimination_ordering);
+                    let mut candidate_elimination_ordering = current_elimination_ordering.clone();
+                    ordering_move.apply(&mut candidate_elimination_ordering);
+                    let candidate_cost = cost(&candidate_elimination_ordering);
+
+                    let is_tabu = moved_vertices
+                        .iter()
+                        .any(|vertex| tabu_list.contains(vertex));
+                    // Aspiration criterion: tabu moves are allowed if they improve the best solution
+                    if (!is_tabu || candidate_cost < best_cost)
+                        && best_candidate
+                            .as_ref()
+                            .map_or(true, |(best_candidate_cost, _, _)| {
+                                candidate_cost < *best_candidate_cost
+                            })
+                    {
+                        best_candidate = Some((
+                            candidate_cost,
+                            candidate_elimination_ordering,
+                            moved_vertices,
+                        ));
+                    }
+                }
+
+                if let Some((candidate_cost, candidate_elimination_ordering, moved_vertices)) =
+                    best_candidate
+                {
+                    current_elimination_ordering = candidate_elimination_ordering;
+                    current_cost = candidate_cost;
+                    for vertex in moved_vertices {
+                        tabu_list.insert(vertex, iteration + tabu_tenure);
+                    }
+                }
+            }
+        }
+
+        if current_cost < best_cost {
+            best_cost = current_cost;
+            best_elimination_ordering.clone_from(&current_elimination_ordering);
+        }
+    }
+
+    best_elimination_ordering
+}
+
+/// Plays the elimination game on the given adjacency and returns the induced width of the
+/// elimination ordering and the sum of the sizes of the bags that are created.
+fn find_induced_width_and_sum_of_bag_sizes<S: Default + BuildHasher + Clone>(
+    adjacency: &HashMap<NodeIndex, HashSet<NodeIndex, S>, S>,
+    elimination_ordering: &[NodeIndex],
+) -> (usize, usize) {
+    let mut adjacency = adjacency.clone();
+    let mut induced_width = 0;
+    let mut sum_of_bag_sizes = 0;
+
+    for vertex in elimination_ordering {
+        let number_of_neighbours = eliminate_vertex(&mut adjacency, *vertex, true).len();
+        induced_width = induced_width.max(number_of_neighbours);
+        sum_of_bag_sizes += number_of_neighbours + 1;
+    }
+
+    (induced_width, sum_of_bag_sizes)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::RandomState;
+
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::{
+        check_tree_decomposition, find_induced_width_of_elimination_ordering,
+        tests::LOCAL_SEARCH_ACCEPTANCES,
+    };
+
+    #[test]
+    fn test_local_search_improves_tree_decomposition() {
+        for i in 1..3 {
+            let test_graph = crate::tests::setup_test_graph(i);
+            for computation_method in crate::tests::COMPUTATION_METHODS {
+                let (_, tree_decomposition, _, _, _, treewidth) =
+                    crate::compute_treewidth_upper_bound::<_, _, _, RandomState>(
+                        &test_graph.graph,
+                        crate::constant,
+                        computation_method,
+                        false,
+                        None,
+                    );
+
+                for local_search_acceptance in LOCAL_SEARCH_ACCEPTANCES {
+                    let mut rng = StdRng::seed_from_u64(i as u64);
+                    let (improved_tree_decomposition, improved_treewidth) =
+                        improve_tree_decomposition_using_local_search::<_, _, i32, _, _>(
+                            &test_graph.graph,
+                            &tree_decomposition,
+                            local_search_acceptance,
+                            SearchBudget::Iterations(200),
+                            &mut rng,
+                        );
+
+                    assert!(check_tree_decomposition(
+                        &test_graph.graph,
+                        &improved_tree_decomposition,
+                        &None,
+                        &None
+                    ));
+                    assert!(improved_treewidth <= treewidth);
+                    assert_eq!(
+                        improved_treewidth, test_graph.treewidth,
+                        "Test graph {} failed with computation method {:?} and acceptance {:?}",
+                        i, computation_method, local_search_acceptance
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_local_search_does_not_worsen_elimination_ordering() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let graph = crate::generate_partial_k_tree(5, 40, 30, &mut rng)
+            .expect("k should be smaller than n");
+        let elimination_ordering: Vec<NodeIndex> = graph.node_indices().collect();
+        let induced_width = find_induced_width_of_elimination_ordering::<_, _, RandomState>(
+            &graph,
+            &elimination_ordering,
+        );
+
+        for local_search_acceptance in LOCAL_SEARCH_ACCEPTANCES {
+            let improved_elimination_ordering =
+                improve_elimination_ordering_using_local_search::<_, _, RandomState>(
+                    &graph,
+                    elimination_ordering.clone(),
+                    local_search_acceptance,
+                    SearchBudget::Time(Duration::from_millis(200)),
+                    &mut rng,
+                );
+            let mut sorted_elimination_ordering = improved_elimination_ordering.clone();
+            sorted_elimination_ordering.sort();
+            assert_eq!(sorted_elimination_ordering, elimination_ordering);
+
+            let improved_induced_width = find_induced_width_of_elimination_ordering::<
+                _,
+                _,
+                RandomState,
+            >(&graph, &improved_elimination_ordering);
+            assert!(improved_induced_width <= induced_width);
+        }
+    }
+}
@@ -0,0 +1,268 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet},
+    fmt::Debug,
+    hash::BuildHasher,
+};
+
+use petgraph::{graph::NodeIndex, Graph, Undirected};
+
+use crate::{
+    compute_treewidth_upper_bound::compute_treewidth_upper_bound_from_cliques,
+    elimination_ordering::{adjacency_map, eliminate_vertex},
+    CliqueGraphTreeDecomposition, SpanningTreeConstructionMethod,
+};
+
+/// Computes a minimal elimination ordering of the graph using MCS-M (Berry, Blair, Heggernes and
+/// Peyton), i.e. an elimination ordering whose fill edges form a minimal triangulation of the
+/// graph: No proper subset of the fill edges results in a chordal graph.
+///
+/// Like [maximum cardinality search][crate::maximum_cardinality_search] the vertices are visited in
+/// the order of their weights (ties are broken by the smallest node index). After visiting a vertex
+/// v, the weight of every unvisited vertex u is increased if there is a path from v to u whose
+/// inner vertices are unvisited and have a smaller weight than u. The elimination ordering is the
+/// reverse of the order in which the vertices were visited.
+pub fn find_minimal_elimination_ordering<N, E>(graph: &Graph<N, E, Undirected>) -> Vec<NodeIndex> {
+    find_minimal_elimination_ordering_with_labels(graph).0
+}
+
+/// Computes a minimal elimination ordering like [find_minimal_elimination_ordering] and also returns
+/// for each vertex (by its index) its weight at the time it was visited by MCS-M.
+pub(crate) fn find_minimal_elimination_ordering_with_labels<N, E>(
+    graph: &Graph<N, E, Undirected>,
+) -> (Vec<NodeIndex>, Vec<usize>) {
+    let number_of_vertices = graph.node_count();
+    let mut weights: Vec<usize> = vec![0; number_of_vertices];
+    let mut labels: Vec<usize> = vec![0; number_of_vertices];
+    let mut visited = vec![false; number_of_vertices];
+    let mut ordering = Vec::with_capacity(number_of_vertices);
+
+    while let Some(vertex) = graph
+        .node_indices()
+        .filter(|vertex| !visited[vertex.index()])
+        .max_by_key(|vertex| (weights[vertex.index()], Reverse(vertex.index())))
+    {
+        visited[vertex.index()] = true;
+        labels[vertex.index()] = weights[vertex.index()];
+        ordering.push(vertex);
+
+        // Bottleneck shortest paths from the vertex through unvisited vertices. The cost of a path
+        // is the maximum weight of its inner vertices plus one (0 if there are none), so a vertex u
+        // is reached as described above iff its cost is at most the weight of u
+        let mut costs: Vec<Option<usize>> = vec![None; number_of_vertices];
+        let mut heap: BinaryHeap<Reverse<(usize, NodeIndex)>> = BinaryHeap::new();
+        for neighbour in graph.neighbors(vertex) {
+            if !visited[neighbour.index()] && costs[neighbour.index()].is_none() {
+                costs[neighbour.index()] = Some(0);
+                heap.push(Reverse((0, neighbour)));
+            }
+        }
+        while let Some(Reverse((cost, current_vertex))) = heap.pop() {
+            if costs[current_vertex.index()] != Some(cost) {
+                continue;
+            }
+            let cost_through_current_vertex = cost.max(weights[current_vertex.index()] + 1);
+            for neighbour in graph.neighbors(current_vertex) {
+                if visited[neighbour.index()] {
+                    continue;
+                }
+                if costs[neighbour.index()].map_or(true, |cost| cost > cost_through_current_vertex)
+                {
+                    costs[neighbour.index()] = Some(cost_through_current_vertex);
+                    heap.push(Reverse((cost_through_current_vertex, neighbour)));
+                }
+            }
+        }
+
+        for (index, cost) in costs.into_iter().enumerate() {
+            if cost.is_some_and(|cost| cost <= weights[index]) {
+                weights[index] += 1;
+            }
+        }
+    }
+
+    ordering.reverse();
+    (ordering, labels)
+}
+
+/// Returns the maximal cliques of the triangulation of the graph that results from eliminating the
+/// vertices in the given order. There are at most as many of them as the graph has vertices.
+///
+/// The elimination ordering is a perfect elimination ordering of the triangulation, so the maximal
+/// cliques are found as for [chordal graphs][crate::find_perfect_elimination_ordering]: Each
+/// consists of a vertex and its neighbours at the time of its elimination.
+///
+/// Panics: Panics if the elimination ordering doesn't contain every vertex of the graph exactly
+/// once.
+pub fn find_maximal_cliques_of_triangulation<N, E, S: Default + BuildHasher + Clone>(
+    graph: &Graph<N, E, Undirected>,
+    elimination_ordering: &[NodeIndex],
+) -> Vec<Vec<NodeIndex>> {
+    assert_eq!(
+        elimination_ordering.len(),
+        graph.node_count(),
+        "Elimination ordering should contain every vertex exactly once"
+    );
+    let positions: HashMap<NodeIndex, usize, S> = elimination_ordering
+        .iter()
+        .enumerate()
+        .map(|(position, vertex)| (*vertex, position))
+        .collect();
+
+    let mut adjacency: HashMap<NodeIndex, HashSet<NodeIndex, S>, S> = adjacency_map(graph);
+    let later_neighbours: Vec<HashSet<NodeIndex, S>> = elimination_ordering
+        .iter()
+        .map(|vertex| eliminate_vertex(&mut adjacency, *vertex, true))
+        .collect();
+
+    let mut is_maximal = vec![true; elimination_ordering.len()];
+    for neighbours in later_neighbours.iter() {
+        if let Some(first_later_neighbour_position) = neighbours
+            .iter()
+            .map(|neighbour| positions[neighbour])
+            .min()
+        {
+            if neighbours.len() == later_neighbours[first_later_neighbour_position].len() + 1 {
+                is_maximal[first_later_neighbour_position] = false;
+            }
+        }
+    }
+
+    later_neighbours
+        .into_iter()
+        .zip(elimination_ordering)
+        .zip(is_maximal)
+        .filter_map(|((neighbours, vertex), is_maximal)| {
+            is_maximal.then(|| {
+                let mut clique: Vec<NodeIndex> = neighbours.into_iter().collect();
+                clique.push(*vertex);
+                clique
+            })
+        })
+        .collect()
+}
+
+/// Computes an upper bound for the treewidth like [compute_treewidth_upper_bound][
+/// crate::compute_treewidth_upper_bound] but uses the maximal cliques of a minimal triangulation
+/// (see [find_minimal_elimination_ordering]) as the vertices of the clique graph instead of the
+/// maximal cliques of the graph.
+///
+/// There are at most n of these cliques and they can be found in polynomial time, whereas the
+/// number of maximal cliques of the graph can be exponential. The cliques of the triangulation
+/// cover all edges of the graph, so the clique graph and the spanning tree construction methods are
+/// used as before.
+pub fn compute_treewidth_upper_bound_using_minimal_triangulation<
+    N: Clone,
+    E: Clone,
+    O: Clone + Ord + Default + Debug,
+    S: Default + BuildHasher + Clone,
+>(
+    graph: &Graph<N, E, Undirected>,
+    edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    treewidth_computation_method: SpanningTreeConstructionMethod,
+    check_tree_decomposition_bool: bool,
+) -> CliqueGraphTreeDecomposition<O, S> {
+    let elimination_ordering = find_minimal_elimination_ordering(graph);
+    let cliques = find_maximal_cliques_of_triangulation::<N, E, S>(graph, &elimination_ordering);
+
+    compute_treewidth_upper_bound_from_cliques(
+        graph,
+        cliques,
+        edge_weight_function,
+        treewidth_computation_method,
+        check_tree_decomposition_bool,
+        None,
+        None,
+    )
+    .expect("There is no width bound that could be exceeded")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::RandomState;
+
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::{is_chordal, negative_intersection};
+
+    /// Returns the graph together with the fill edges of the elimination ordering.
+    fn triangulate(
+        graph: &Graph<i32, i32, Undirected>,
+        elimination_ordering: &[NodeIndex],
+    ) -> (Graph<i32, i32, Undirected>, Vec<(NodeIndex, NodeIndex)>) {
+        let mut triangulation = graph.clone();
+        let mut fill_edges = Vec::new();
+        let mut adjacency: HashMap<NodeIndex, HashSet<NodeIndex, RandomState>, RandomState> =
+            adjacency_map(graph);
+        for vertex in elimination_ordering {
+            let neighbours: Vec<NodeIndex> = eliminate_vertex(&mut adjacency, *vertex, true)
+                .into_iter()
+                .collect();
+            for (i, first_neighbour) in neighbours.iter().enumerate() {
+                for second_neighbour in neighbours.iter().skip(i + 1) {
+                    if triangulation
+                        .find_edge(*first_neighbour, *second_neighbour)
+                        .is_none()
+                    {
+                        triangulation.add_edge(*first_neighbour, *second_neighbour, 0);
+                        fill_edges.push((*first_neighbour, *second_neighbour));
+                    }
+                }
+            }
+        }
+        (triangulation, fill_edges)
+    }
+
+    #[test]
+    fn test_minimal_elimination_ordering_gives_minimal_triangulation() {
+        let mut rng = StdRng::seed_from_u64(36);
+        for _ in 0..10 {
+            let graph = crate::generate_partial_k_tree(4, 20, 40, &mut rng)
+                .expect("k should be smaller than n");
+            let elimination_ordering = find_minimal_elimination_ordering(&graph);
+            let (triangulation, fill_edges) = triangulate(&graph, &elimination_ordering);
+            assert!(is_chordal::<_, _, RandomState>(&triangulation));
+
+            // A triangulation is minimal iff no single fill edge can be removed
+            for (first_vertex, second_vertex) in fill_edges {
+                let mut smaller_triangulation = triangulation.clone();
+                let edge = smaller_triangulation
+                    .find_edge(first_vertex, second_vertex)
+                    .expect("Fill edge should be in the triangulation");
+                smaller_triangulation.remove_edge(edge);
+                assert!(!is_chordal::<_, _, RandomState>(&smaller_triangulation));
+            }
+
+            let cliques = find_maximal_cliques_of_triangulation::<_, _, RandomState>(
+                &graph,
+                &elimination_ordering,
+            );
+            assert!(cliques.len() <= graph.node_count());
+            assert_eq!(
+                cliques.iter().map(|clique| clique.len()).max().unwrap_or(0) - 1,
+                crate::find_induced_width_of_elimination_ordering::<_, _, RandomState>(
+                    &graph,
+                    &elimination_ordering
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn test_treewidth_heuristic_using_minimal_triangulation() {
+        for i in 1..3 {
+            let test_graph = crate::tests::setup_test_graph(i);
+            for computation_method in crate::tests::COMPUTATION_METHODS {
+                let (_, _, _, _, _, computed_treewidth) =
+                    compute_treewidth_upper_bound_using_minimal_triangulation::<_, _, _, RandomState>(
+                        &test_graph.graph,
+                        negative_intersection,
+                        computation_method,
+                        true,
+                    );
+                assert!(computed_treewidth >= test_graph.treewidth);
+            }
+        }
+    }
+}
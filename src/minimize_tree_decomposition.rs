@@ -0,0 +1,326 @@
+use std::{
+    cmp::Reverse,
+    collections::{BTreeSet, HashMap, HashSet},
+    hash::BuildHasher,
+};
+
+use petgraph::{graph::NodeIndex, visit::EdgeRef, Graph, Undirected};
+
+use crate::find_width_of_tree_decomposition;
+
+/// Result of [minimize_tree_decomposition].
+///
+/// width_before The width of the tree decomposition before the minimization
+///
+/// width_after The width of the tree decomposition after the minimization
+///
+/// total_size_before The sum of the sizes of the bags before the minimization
+///
+/// total_size_after The sum of the sizes of the bags after the minimization
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MinimizationReport {
+    pub width_before: usize,
+    pub width_after: usize,
+    pub total_size_before: usize,
+    pub total_size_after: usize,
+}
+
+/// Removes redundant occurrences of vertices from the bags of a tree decomposition of the graph
+/// and contracts bags that are subsets of a neighbouring bag.
+///
+/// An occurrence of a vertex v in a bag is redundant if the bag is a leaf of the subtree of bags
+/// containing v and every neighbour of v in the graph is contained in another bag of that subtree.
+/// Removing it keeps the subtree connected and all edges covered. The redundant occurrences are
+/// removed one at a time, always from the biggest bag, until no occurrence is redundant anymore.
+///
+/// Afterwards each bag that is a subset of a neighbouring bag is removed and its other neighbours
+/// are connected to that neighbouring bag instead, keeping the weights of their edges.
+///
+/// Returns the width and the total size of the bags before and after the minimization.
+pub fn minimize_tree_decomposition<N, E, O: Clone, S: Default + BuildHasher + Clone>(
+    graph: &Graph<N, E, Undirected>,
+    tree_decomposition: &mut Graph<HashSet<NodeIndex, S>, O, Undirected>,
+) -> MinimizationReport {
+    let width_before = find_width_of_tree_decomposition(tree_decomposition);
+    let total_size_before = find_total_size(tree_decomposition);
+
+    // Maps the vertices of the graph to the bags containing them
+    let mut bags_of_vertex: HashMap<NodeIndex, HashSet<NodeIndex, S>, S> = Default::default();
+    for bag in tree_decomposition.node_indices() {
+        for vertex in tree_decomposition
+            .node_weight(bag)
+            .expect("Bags should exist in the tree decomposition")
+        {
+            bags_of_vertex.entry(*vertex).or_default().insert(bag);
+        }
+    }
+    let mut redundant_occurrences = RedundantOccurrences::default();
+    // Removing a vertex from a bag only affects the redundant occurrences of the vertex itself and
+    // of the vertices in that bag, so only those need to be checked again
+    let mut touched_vertices: Vec<NodeIndex> = graph.node_indices().collect();
+    loop {
+        for vertex in touched_vertices.drain(..) {
+            redundant_occurrences.update(
+                vertex,
+                find_redundant_occurrence::<N, E, O, S>(
+                    graph,
+                    tree_decomposition,
+                    bags_of_vertex.get(&vertex).unwrap_or(&Default::default()),
+                    vertex,
+                )
+                .map(|bag| {
+                    (
+                        tree_decomposition
+                            .node_weight(bag)
+                            .expect("Bags should exist in the tree decomposition")
+                            .len(),
+                        bag,
+                    )
+                }),
+            );
+        }
+
+        let Some((vertex, bag)) = redundant_occurrences.biggest() else {
+            break;
+        };
+        let bag_content = tree_decomposition
+            .node_weight_mut(bag)
+            .expect("Bags should exist in the tree decomposition");
+        bag_content.remove(&vertex);
+        bags_of_vertex
+            .get_mut(&vertex)
+            .expect("Vertex should be contained in the bag")
+            .remove(&bag);
+        touched_vertices.push(vertex);
+        touched_vertices.extend(bag_content.iter().cloned());
+    }
+
+    while let Some((bag, superset_bag)) = find_bag_contained_in_neighbour(tree_decomposition) {
+        let other_neighbours: Vec<(NodeIndex, O)> = tree_decomposition
+            .edges(bag)
+            .filter(|edge| edge.target() != superset_bag)
+            .map(|edge| (edge.target(), edge.weight().clone()))
+            .collect();
+        for (neighbour, weight) in other_neighbours {
+            tree_decomposition.add_edge(superset_bag, neighbour, weight);
+        }
+        tree_decomposition.remove_node(bag);
+    }
+
+    MinimizationReport {
+        width_before,
+        width_after: find_width_of_tree_decomposition(tree_decomposition),
+        total_size_before,
+        total_size_after: find_total_size(tree_decomposition),
+    }
+}
+
+/// Redundant occurrences of the vertices (see [minimize_tree_decomposition]), at most one per
+/// vertex, ordered by the size of their bag and the vertex as a tie breaker.
+#[derive(Default)]
+struct RedundantOccurrences {
+    // Each entry consists of the size of the bag, the vertex and the bag
+    ordered_occurrences: BTreeSet<(usize, Reverse<NodeIndex>, NodeIndex)>,
+    occurrence_of_vertex: HashMap<NodeIndex, (usize, NodeIndex)>,
+}
+
+impl RedundantOccurrences {
+    /// Replaces the redundant occurrence of the vertex by the given bag (and its size).
+    fn update(&mut self, vertex: NodeIndex, occurrence: Option<(usize, NodeIndex)>) {
+        if let Some((bag_size, bag)) = self.occurrence_of_vertex.remove(&vertex) {
+            self.ordered_occurrences
+                .remove(&(bag_size, Reverse(vertex), bag));
+        }
+        if let Some((bag_size, bag)) = occurrence {
+            self.ordered_occurrences
+                .insert((bag_size, Reverse(vertex), bag));
+            self.occurrence_of_vertex.insert(vertex, (bag_size, bag));
+        }
+    }
+
+    /// Returns the vertex and the bag of the redundant occurrence in the biggest bag, preferring
+    /// smaller vertices.
+    fn biggest(&self) -> Option<(NodeIndex, NodeIndex)> {
+        self.ordered_occurrences
+            .last()
+            .map(|(_, Reverse(vertex), bag)| (*vertex, *bag))
+    }
+}
+
+/// Returns the biggest bag containing a redundant occurrence of the vertex (see
+/// [minimize_tree_decomposition]) if there is one. The bags containing the vertex need to be given.
+fn find_redundant_occurrence<N, E, O, S: Default + BuildHasher>(
+    graph: &Graph<N, E, Undirected>,
+    tree_decomposition: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    bags_containing_vertex: &HashSet<NodeIndex, S>,
+    vertex: NodeIndex,
+) -> Option<NodeIndex> {
+    // The vertex needs to stay in at least one bag
+    if bags_containing_vertex.len() < 2 {
+        return None;
+    }
+
+    // Number of bags containing the vertex that also contain the neighbour
+    let mut number_of_bags_covering_edge: HashMap<NodeIndex, usize, S> = Default::default();
+    for neighbour in graph
+        .neighbors(vertex)
+        .filter(|neighbour| *neighbour != vertex)
+    {
+        number_of_bags_covering_edge.insert(
+            neighbour,
+            bags_containing_vertex
+                .iter()
+                .filter(|bag| {
+                    tree_decomposition
+                        .node_weight(**bag)
+                        .expect("Bags should exist in the tree decomposition")
+                        .contains(&neighbour)
+                })
+                .count(),
+        );
+    }
+
+    bags_containing_vertex
+        .iter()
+        .filter(|bag| {
+            tree_decomposition
+                .neighbors(**bag)
+                .filter(|neighbour| bags_containing_vertex.contains(neighbour))
+                .count()
+                <= 1
+        })
+        .filter(|bag| {
+            let bag = tree_decomposition
+                .node_weight(**bag)
+                .expect("Bags should exist in the tree decomposition");
+            number_of_bags_covering_edge
+                .iter()
+                .all(|(neighbour, number_of_bags)| {
+                    *number_of_bags > usize::from(bag.contains(neighbour))
+                })
+        })
+        .max_by_key(|bag| {
+            (
+                tree_decomposition
+                    .node_weight(**bag)
+                    .expect("Bags should exist in the tree decomposition")
+                    .len(),
+                std::cmp::Reverse(**bag),
+            )
+        })
+        .cloned()
+}
+
+/// Returns a bag that is a subset of one of its neighbours together with that neighbour.
+fn find_bag_contained_in_neighbour<O, S: BuildHasher>(
+    tree_decomposition: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+) -> Option<(NodeIndex, NodeIndex)> {
+    tree_decomposition.edge_indices().find_map(|edge| {
+        let (first_bag, second_bag) = tree_decomposition
+            .edge_endpoints(edge)
+            .expect("Edge should exist in the tree decomposition");
+        let first_bag_content = tree_decomposition
+            .node_weight(first_bag)
+            .expect("Bags should exist in the tree decomposition");
+        let second_bag_content = tree_decomposition
+            .node_weight(second_bag)
+            .expect("Bags should exist in the tree decomposition");
+        if first_bag_content.is_subset(second_bag_content) {
+            Some((first_bag, second_bag))
+        } else if second_bag_content.is_subset(first_bag_content) {
+            Some((second_bag, first_bag))
+        } else {
+            None
+        }
+    })
+}
+
+/// Returns the sum of the sizes of the bags of the tree decomposition.
+fn find_total_size<O, S>(
+    tree_decomposition: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+) -> usize {
+    tree_decomposition.node_weights().map(|bag| bag.len()).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::RandomState;
+
+    use super::*;
+    use crate::{
+        check_tree_decomposition, compute_treewidth_upper_bound, negative_intersection,
+        SpanningTreeConstructionMethod,
+    };
+
+    #[test]
+    fn test_minimize_tree_decomposition_of_path() {
+        let mut graph: Graph<i32, i32, Undirected> = Graph::new_undirected();
+        let vertices: Vec<NodeIndex> = (0..3).map(|i| graph.add_node(i)).collect();
+        graph.add_edge(vertices[0], vertices[1], 0);
+        graph.add_edge(vertices[1], vertices[2], 0);
+
+        let mut tree_decomposition: Graph<HashSet<NodeIndex, RandomState>, i32, Undirected> =
+            Graph::new_undirected();
+        let first_bag = tree_decomposition.add_node(vertices.iter().cloned().collect());
+        let second_bag = tree_decomposition.add_node(vertices[1..].iter().cloned().collect());
+        let third_bag = tree_decomposition.add_node(HashSet::from_iter([vertices[1]]));
+        tree_decomposition.add_edge(first_bag, second_bag, 0);
+        tree_decomposition.add_edge(second_bag, third_bag, 0);
+
+        let report = minimize_tree_decomposition(&graph, &mut tree_decomposition);
+        assert_eq!(
+            report,
+            MinimizationReport {
+                width_before: 2,
+                width_after: 1,
+                total_size_before: 6,
+                total_size_after: 4
+            }
+        );
+        assert!(check_tree_decomposition(
+            &graph,
+            &tree_decomposition,
+            &None,
+            &None
+        ));
+    }
+
+    #[test]
+    fn test_minimize_tree_decomposition_keeps_tree_decomposition_valid() {
+        for (graph, treewidth) in
+            crate::tests::setup_test_graphs_and_partial_k_trees(40, 10, 4, 25, 40)
+        {
+            for computation_method in [
+                SpanningTreeConstructionMethod::MSTAndFill,
+                SpanningTreeConstructionMethod::FillWhilstMST,
+            ] {
+                let (_, mut tree_decomposition, _, _, _, computed_treewidth) =
+                    compute_treewidth_upper_bound::<_, _, _, RandomState>(
+                        &graph,
+                        negative_intersection,
+                        computation_method,
+                        false,
+                        None,
+                    );
+
+                let report = minimize_tree_decomposition(&graph, &mut tree_decomposition);
+                assert!(check_tree_decomposition(
+                    &graph,
+                    &tree_decomposition,
+                    &None,
+                    &None
+                ));
+                assert_eq!(report.width_before, computed_treewidth);
+                assert!(report.width_after <= report.width_before);
+                assert!(report.width_after >= treewidth);
+                // No bag is a subset of a neighbouring bag anymore
+                for bag in tree_decomposition.node_indices() {
+                    assert!(tree_decomposition.neighbors(bag).all(|neighbour| {
+                        !tree_decomposition[bag].is_subset(&tree_decomposition[neighbour])
+                    }));
+                }
+                assert!(report.total_size_after <= report.total_size_before);
+            }
+        }
+    }
+}
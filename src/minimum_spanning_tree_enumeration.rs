@@ -0,0 +1,496 @@
+use std::{collections::HashSet, fmt::Debug, hash::BuildHasher};
+
+use petgraph::{graph::NodeIndex, unionfind::UnionFind, visit::EdgeRef, Graph, Undirected};
+use rand::{seq::SliceRandom, Rng};
+
+use crate::*;
+
+/// Different ways of choosing the minimum spanning trees of the clique graph that are filled up in
+/// [compute_treewidth_upper_bound_using_tied_minimum_spanning_trees].
+///
+/// Enumerate Enumerates the minimum spanning trees (in a deterministic order) until the given
+/// number of minimum spanning trees is reached
+///
+/// Sample Samples the given number of minimum spanning trees by running kruskal's algorithm with
+/// the edges of the same weight in random order. Duplicates are only evaluated once
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MinimumSpanningTreeSelection {
+    Enumerate(usize),
+    Sample(usize),
+}
+
+/// Result of [compute_treewidth_upper_bound_using_tied_minimum_spanning_trees].
+///
+/// tree_decomposition The tree decomposition obtained from the best minimum spanning tree (with the
+/// same vertex indices as the clique graph)
+///
+/// treewidth The width of the tree decomposition
+///
+/// number_of_minimum_spanning_trees The number of (distinct) minimum spanning trees that were
+/// filled up
+///
+/// is_clique_tree True if no bag had to be filled up, i.e. the minimum spanning tree is a clique
+/// tree. In that case the graph is chordal and the treewidth is exact, since the width is the size
+/// of the biggest clique minus one
+#[derive(Clone, Debug)]
+pub struct TiedMinimumSpanningTrees<O, S> {
+    pub tree_decomposition: Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    pub treewidth: usize,
+    pub number_of_minimum_spanning_trees: usize,
+    pub is_clique_tree: bool,
+}
+
+/// Computes an upper bound for the treewidth like
+/// [SpanningTreeConstructionMethod::MSTAndUseTreeStructure] except that instead of filling up a
+/// single minimum spanning tree of the clique graph, several minimum spanning trees (that differ
+/// in the choice among edges of the same weight) are filled up and the one with the smallest width
+/// (and the smallest sum of bag sizes as a tie breaker) is returned.
+///
+/// See [MinimumSpanningTreeSelection] for how the minimum spanning trees are chosen. The rng is
+/// only used when sampling.
+///
+/// For chordal graphs and the [negative_intersection] edge weights (without a clique bound), the
+/// minimum spanning trees of the clique graph are exactly the clique trees. So if the enumeration
+/// is not cut off by the cap, a clique tree is found and certified by
+/// [TiedMinimumSpanningTrees::is_clique_tree].
+///
+/// Can also check the tree decomposition for correctness after computation. If so, will panic if
+/// the tree decomposition is incorrect.
+///
+/// Panics: Panics if the graph is empty or not connected
+pub fn compute_treewidth_upper_bound_using_tied_minimum_spanning_trees<
+    N: Clone,
+    E: Clone,
+    O: Clone + Ord + Default + Debug,
+    S: Default + BuildHasher + Clone,
+>(
+    graph: &Graph<N, E, Undirected>,
+    edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    minimum_spanning_tree_selection: MinimumSpanningTreeSelection,
+    check_tree_decomposition_bool: bool,
+    clique_bound: Option<usize>,
+    rng: &mut impl Rng,
+) -> TiedMinimumSpanningTrees<O, S> {
+    let cliques = find_cliques::<N, E, S>(graph, clique_bound);
+    let (clique_graph, clique_graph_map) =
+        construct_clique_graph_with_bags(cliques, edge_weight_function);
+
+    let minimum_spanning_trees = match minimum_spanning_tree_selection {
+        MinimumSpanningTreeSelection::Enumerate(maximum_number_of_minimum_spanning_trees) => {
+            enumerate_minimum_spanning_trees(
+                &clique_graph,
+                maximum_number_of_minimum_spanning_trees.max(1),
+            )
+        }
+        MinimumSpanningTreeSelection::Sample(number_of_samples) => {
+            sample_minimum_spanning_trees(&clique_graph, number_of_samples.max(1), rng)
+        }
+    };
+    let number_of_minimum_spanning_trees = minimum_spanning_trees.len();
+
+    // Tree decomposition, width and sum of bag sizes of the best minimum spanning tree so far
+    let mut best_tree_decomposition = None;
+    for tree_edges in minimum_spanning_trees {
+        let mut tree_decomposition =
+            clique_graph.filter_map(|_, bag| Some(bag.clone()), |_, _| None::<O>);
+        for (first_vertex, second_vertex) in tree_edges {
+            let edge_weight = clique_graph
+                .find_edge(first_vertex, second_vertex)
+                .and_then(|edge| clique_graph.edge_weight(edge))
+                .expect("Tree edges should be edges of the clique graph")
+                .clone();
+            tree_decomposition.add_edge(first_vertex, second_vertex, edge_weight);
+        }
+        fill_bags_along_paths_using_structure(&mut tree_decomposition, &clique_graph_map, None)
+            .expect("There is no width bound that could be exceeded");
+
+        let width = find_width_of_tree_decomposition(&tree_decomposition);
+        let sum_of_bag_sizes: usize = tree_decomposition.node_weights().map(|bag| bag.len()).sum();
+        if best_tree_decomposition.as_ref().map_or(
+            true,
+            |(_, best_width, best_sum_of_bag_sizes)| {
+                (width, sum_of_bag_sizes) < (*best_width, *best_sum_of_bag_sizes)
+            },
+        ) {
+            best_tree_decomposition = Some((tree_decomposition, width, sum_of_bag_sizes));
+        }
+    }
+    let (tree_decomposition, treewidth, sum_of_bag_sizes) =
+        best_tree_decomposition.expect("There should be at least one minimum spanning tree");
+
+    if check_tree_decomposition_bool {
+        assert!(
+            check_tree_decomposition(graph, &tree_decomposition, &None, &None),
+            "Tree decomposition is invalid. See previous print statements for reason."
+        );
+    }
+
+    let sum_of_clique_sizes: usize = clique_graph.node_weights().map(|bag| bag.len()).sum();
+    TiedMinimumSpanningTrees {
+        tree_decomposition,
+        treewidth,
+        number_of_minimum_spanning_trees,
+        is_clique_tree: sum_of_bag_sizes == sum_of_clique_sizes,
+    }
+}
+
+/// Union find data structure without path compression that allows undoing the last unions.
+struct UndoableUnionFind {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    number_of_components: usize,
+    // Roots that were attached to another root, in the order of the unions
+    history: Vec<usize>,
+}
+
+impl UndoableUnionFind {
+    fn new(n: usize) -> Self {
+        UndoableUnionFind {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+            number_of_components: n,
+            history: Vec::new(),
+        }
+    }
+
+    fn find(&self, mut x: usize) -> usize {
+        while self.parent[x] != x {
+            x = self.parent[x];
+        }
+        x
+    }
+
+    /// Unites the components of x and y. Returns false if they were already in the same
+    /// component.
+    fn union(&mut self, x: usize, y: usize) -> bool {
+        let (mut root_x, mut root_y) = (self.find(x), self.find(y));
+        if root_x == root_y {
+            return false;
+        }
+        if self.size[root_x] < self.size[root_y] {
+            std::mem::swap(&mut root_x, &mut root_y);
+        }
+        self.parent[root_y] = root_x;
+        self.size[root_x] += self.size[root_y];
+        self.number_of_components -= 1;
+        self.history.push(root_y);
+        true
+    }
+
+    /// Undoes the last successful union.
+    fn undo(&mut self) {
+        let root_y = self.history.pop().expect("There should be a union to undo");
+        let root_x = self.parent[root_y];
+        self.size[root_x] -= self.size[root_y];
+        self.parent[root_y] = root_y;
+        self.number_of_components += 1;
+    }
+}
+
+/// Returns the edges of the clique graph sorted by weight together with the index of their weight
+/// class (edges with the same weight) and the index of the last edge of each weight class.
+fn edges_grouped_by_weight<O: Ord, S>(
+    clique_graph: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+) -> (Vec<(NodeIndex, NodeIndex, usize)>, Vec<usize>) {
+    let mut edges: Vec<_> = clique_graph.edge_references().collect();
+    edges.sort_by(|first_edge, second_edge| first_edge.weight().cmp(second_edge.weight()));
+
+    let mut grouped_edges = Vec::with_capacity(edges.len());
+    let mut last_edge_of_weight_class: Vec<usize> = Vec::new();
+    for (position, edge) in edges.iter().enumerate() {
+        if position > 0 && edges[position - 1].weight() != edge.weight() {
+            last_edge_of_weight_class.push(position - 1);
+        }
+        grouped_edges.push((
+            edge.source(),
+            edge.target(),
+            last_edge_of_weight_class.len(),
+        ));
+    }
+    if !edges.is_empty() {
+        last_edge_of_weight_class.push(edges.len() - 1);
+    }
+
+    (grouped_edges, last_edge_of_weight_class)
+}
+
+/// Enumerates up to maximum_number_of_minimum_spanning_trees minimum spanning trees of the clique
+/// graph.
+///
+/// Uses the fact that the components after adding all edges up to some weight are the same for
+/// all minimum spanning trees. So kruskal's algorithm is run with backtracking over which edges of
+/// a weight class are added, discarding choices that don't result in these components.
+///
+/// Panics: Panics if the clique graph is not connected
+fn enumerate_minimum_spanning_trees<O: Ord, S>(
+    clique_graph: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    maximum_number_of_minimum_spanning_trees: usize,
+) -> Vec<Vec<(NodeIndex, NodeIndex)>> {
+    let (edges, last_edge_of_weight_class) = edges_grouped_by_weight(clique_graph);
+
+    // Number of components after adding the edges of each weight class using kruskal's algorithm
+    let mut expected_number_of_components = Vec::with_capacity(last_edge_of_weight_class.len());
+    let mut union_find = UndoableUnionFind::new(clique_graph.node_count());
+    for (position, (first_vertex, second_vertex, weight_class)) in edges.iter().enumerate() {
+        union_find.union(first_vertex.index(), second_vertex.index());
+        if position == last_edge_of_weight_class[*weight_class] {
+            expected_number_of_components.push(union_find.number_of_components);
+        }
+    }
+    assert!(
+        union_find.number_of_components <= 1,
+        "Clique graph should be connected"
+    );
+
+    let mut union_find = UndoableUnionFind::new(clique_graph.node_count());
+    // Positions of the edges that were considered for the current spanning tree, with true if they
+    // were added and false if they were skipped although they would have connected two components
+    let mut decisions: Vec<(usize, bool)> = Vec::new();
+    let mut minimum_spanning_trees = Vec::new();
+    let mut position = 0;
+
+    loop {
+        // Extend the current choice by adding every edge that connects two components
+        let mut feasible = true;
+        while position < edges.len() {
+            let (first_vertex, second_vertex, weight_class) = edges[position];
+            if union_find.union(first_vertex.index(), second_vertex.index()) {
+                decisions.push((position, true));
+            }
+            position += 1;
+            if position - 1 == last_edge_of_weight_class[weight_class]
+                && union_find.number_of_components != expected_number_of_components[weight_class]
+            {
+                feasible = false;
+                break;
+            }
+        }
+        if feasible {
+            minimum_spanning_trees.push(
+                decisions
+                    .iter()
+                    .filter(|(_, added)| *added)
+                    .map(|(edge_position, _)| (edges[*edge_position].0, edges[*edge_position].1))
+                    .collect(),
+            );
+            if minimum_spanning_trees.len() >= maximum_number_of_minimum_spanning_trees {
+                break;
+            }
+        }
+
+        // Backtrack to the last added edge that can be skipped instead, that is the remaining
+        // edges of its weight class can still result in the expected components
+        let mut next_position = None;
+        while let Some((edge_position, added)) = decisions.pop() {
+            if !added {
+                continue;
+            }
+            union_find.undo();
+            let weight_class = edges[edge_position].2;
+            let remaining_edges_in_weight_class =
+                last_edge_of_weight_class[weight_class] - edge_position;
+            let missing_unions =
+                union_find.number_of_components - expected_number_of_components[weight_class];
+            if remaining_edges_in_weight_class >= missing_unions {
+                decisions.push((edge_position, false));
+                next_position = Some(edge_position + 1);
+                break;
+            }
+        }
+        match next_position {
+            Some(next_position) => position = next_position,
+            None => break,
+        }
+    }
+
+    minimum_spanning_trees
+}
+
+/// Samples minimum spanning trees of the clique graph by running kruskal's algorithm with the edges
+/// of each weight class in random order. Returns the distinct minimum spanning trees found.
+fn sample_minimum_spanning_trees<O: Ord, S: Default + BuildHasher>(
+    clique_graph: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    number_of_samples: usize,
+    rng: &mut impl Rng,
+) -> Vec<Vec<(NodeIndex, NodeIndex)>> {
+    let (mut edges, last_edge_of_weight_class) = edges_grouped_by_weight(clique_graph);
+    let mut seen_minimum_spanning_trees: HashSet<Vec<(NodeIndex, NodeIndex)>, S> =
+        Default::default();
+    let mut minimum_spanning_trees = Vec::new();
+
+    for _ in 0..number_of_samples {
+        let mut first_edge_of_weight_class = 0;
+        for last_edge in last_edge_of_weight_class.iter() {
+            edges[first_edge_of_weight_class..=*last_edge].shuffle(rng);
+            first_edge_of_weight_class = last_edge + 1;
+        }
+
+        let mut union_find: UnionFind<usize> = UnionFind::new(clique_graph.node_count());
+        let mut tree_edges: Vec<(NodeIndex, NodeIndex)> = edges
+            .iter()
+            .filter(|(first_vertex, second_vertex, _)| {
+                union_find.union(first_vertex.index(), second_vertex.index())
+            })
+            .map(|(first_vertex, second_vertex, _)| {
+                (
+                    *first_vertex.min(second_vertex),
+                    *first_vertex.max(second_vertex),
+                )
+            })
+            .collect();
+        tree_edges.sort();
+
+        if seen_minimum_spanning_trees.insert(tree_edges.clone()) {
+            minimum_spanning_trees.push(tree_edges);
+        }
+    }
+
+    minimum_spanning_trees
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::RandomState;
+
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn test_tied_minimum_spanning_trees_on_test_graphs() {
+        for i in 1..3 {
+            let test_graph = crate::tests::setup_test_graph(i);
+            let (_, _, _, _, _, treewidth) = compute_treewidth_upper_bound::<_, _, _, RandomState>(
+                &test_graph.graph,
+                negative_intersection,
+                SpanningTreeConstructionMethod::MSTAndUseTreeStructure,
+                false,
+                None,
+            );
+
+            for minimum_spanning_tree_selection in [
+                MinimumSpanningTreeSelection::Enumerate(100),
+                MinimumSpanningTreeSelection::Sample(100),
+            ] {
+                let mut rng = StdRng::seed_from_u64(i as u64);
+                let tied_minimum_spanning_trees =
+                    compute_treewidth_upper_bound_using_tied_minimum_spanning_trees::<
+                        _,
+                        _,
+                        _,
+                        RandomState,
+                    >(
+                        &test_graph.graph,
+                        negative_intersection,
+                        minimum_spanning_tree_selection,
+                        true,
+                        None,
+                        &mut rng,
+                    );
+
+                assert!(tied_minimum_spanning_trees.number_of_minimum_spanning_trees >= 1);
+                assert!(tied_minimum_spanning_trees.treewidth <= treewidth);
+                assert!(tied_minimum_spanning_trees.treewidth >= test_graph.treewidth);
+            }
+        }
+    }
+
+    #[test]
+    fn test_enumerated_minimum_spanning_trees_are_distinct_minimum_spanning_trees() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let (_, clique_graph) =
+            crate::tests::setup_partial_k_tree_component_with_clique_graph(4, 30, 30, &mut rng);
+        let minimum_spanning_tree: Graph<HashSet<NodeIndex, RandomState>, i32, Undirected> =
+            petgraph::data::FromElements::from_elements(petgraph::algo::min_spanning_tree(
+                &clique_graph,
+            ));
+        let minimum_weight: i32 = minimum_spanning_tree.edge_weights().sum();
+
+        let minimum_spanning_trees = enumerate_minimum_spanning_trees(&clique_graph, 50);
+        let distinct_minimum_spanning_trees: HashSet<Vec<(NodeIndex, NodeIndex)>> =
+            minimum_spanning_trees
+                .iter()
+                .map(|tree_edges| {
+                    let mut tree_edges: Vec<_> = tree_edges
+                        .iter()
+                        .map(|(first_vertex, second_vertex)| {
+                            (
+                                *first_vertex.min(second_vertex),
+                                *first_vertex.max(second_vertex),
+                            )
+                        })
+                        .collect();
+                    tree_edges.sort();
+                    tree_edges
+                })
+                .collect();
+        assert_eq!(
+            distinct_minimum_spanning_trees.len(),
+            minimum_spanning_trees.len()
+        );
+
+        for tree_edges in minimum_spanning_trees {
+            let mut union_find: UnionFind<usize> = UnionFind::new(clique_graph.node_count());
+            let mut weight = 0;
+            for (first_vertex, second_vertex) in tree_edges.iter() {
+                assert!(union_find.union(first_vertex.index(), second_vertex.index()));
+                weight += clique_graph
+                    .edge_weight(
+                        clique_graph
+                            .find_edge(*first_vertex, *second_vertex)
+                            .expect("Tree edges should be edges of the clique graph"),
+                    )
+                    .expect("Edges should have weights");
+            }
+            assert_eq!(weight, minimum_weight);
+        }
+    }
+
+    #[test]
+    fn test_all_minimum_spanning_trees_are_enumerated() {
+        // The clique graph of a star with four leaves is a complete graph on four vertices with
+        // equal edge weights, which has 16 spanning trees
+        let mut graph: Graph<i32, i32, Undirected> = Graph::new_undirected();
+        let center = graph.add_node(0);
+        for i in 1..5 {
+            let leaf = graph.add_node(i);
+            graph.add_edge(center, leaf, 0);
+        }
+        let cliques: Vec<Vec<_>> = find_maximal_cliques::<Vec<_>, _, RandomState>(&graph).collect();
+        let clique_graph: Graph<HashSet<NodeIndex, RandomState>, i32, Undirected> =
+            construct_clique_graph(cliques, negative_intersection);
+
+        assert_eq!(
+            enumerate_minimum_spanning_trees(&clique_graph, 100).len(),
+            16
+        );
+        assert_eq!(
+            enumerate_minimum_spanning_trees(&clique_graph, 10).len(),
+            10
+        );
+    }
+
+    #[test]
+    fn test_clique_tree_is_certified_for_k_trees() {
+        for k in 1..5 {
+            let graph = generate_k_tree(k, 20).expect("k should be smaller than n");
+            let tied_minimum_spanning_trees =
+                compute_treewidth_upper_bound_using_tied_minimum_spanning_trees::<
+                    _,
+                    _,
+                    _,
+                    RandomState,
+                >(
+                    &graph,
+                    negative_intersection,
+                    MinimumSpanningTreeSelection::Enumerate(1000),
+                    true,
+                    None,
+                    &mut StdRng::seed_from_u64(0),
+                );
+
+            assert!(tied_minimum_spanning_trees.is_clique_tree);
+            assert_eq!(tied_minimum_spanning_trees.treewidth, k);
+        }
+    }
+}
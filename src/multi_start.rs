@@ -0,0 +1,285 @@
+use std::{collections::HashSet, fmt::Debug, hash::BuildHasher};
+
+use petgraph::{graph::NodeIndex, Graph, Undirected};
+use rand::Rng;
+
+use crate::*;
+
+/// Configuration of the multi start (GRASP) version of the FillWhilstMST methods.
+///
+/// restricted_candidate_list The [restricted candidate list][RestrictedCandidateList] the next
+/// vertex of prim's algorithm is chosen from
+///
+/// root The vertex of the clique graph prim's algorithm starts at. If None, a random root is
+/// chosen for each restart. The vertices of the clique graph are numbered in the order the cliques
+/// are found, so they are the same as in the clique graph returned by
+/// [compute_treewidth_upper_bound]
+///
+/// number_of_restarts The number of randomized runs of prim's algorithm
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub struct MultiStartConfiguration {
+    pub restricted_candidate_list: RestrictedCandidateList,
+    pub root: Option<NodeIndex>,
+    pub number_of_restarts: usize,
+}
+
+/// Computes an upper bound for the treewidth by running a randomized version of the given
+/// FillWhilstMST method multiple times (see [fill_bags_while_generating_mst_randomized]).
+///
+/// The clique graph is only constructed once. Afterwards, for each restart prim's algorithm starts
+/// at the configured (or a random) root and chooses the next vertex randomly from the restricted
+/// candidate list. The randomness only depends on the given rng, so seeding it makes the result
+/// reproducible.
+///
+/// Returns a tuple with the best tree decomposition found and the widths of all restarts (in the
+/// order of the restarts). The width of the best tree decomposition is the minimum of these widths.
+///
+/// Can also check each tree decomposition for correctness after computation. If so, will panic if
+/// one of the tree decompositions is incorrect.
+///
+/// Panics: Panics if the graph is empty or the method is not one of the FillWhilstMST methods
+pub fn compute_treewidth_upper_bound_multi_start<
+    N: Clone,
+    E: Clone,
+    O: Clone + Ord + Default + Debug + EdgeWeightValue,
+    S: Default + BuildHasher + Clone,
+>(
+    graph: &Graph<N, E, Undirected>,
+    edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    treewidth_computation_method: SpanningTreeConstructionMethod,
+    multi_start_configuration: MultiStartConfiguration,
+    check_tree_decomposition_bool: bool,
+    clique_bound: Option<usize>,
+    rng: &mut impl Rng,
+) -> (Graph<HashSet<NodeIndex, S>, O, Undirected>, Vec<usize>) {
+    let (best_tree_decomposition, metrics) =
+        compute_treewidth_upper_bound_multi_start_with_objective(
+            graph,
+            edge_weight_function,
+            treewidth_computation_method,
+            multi_start_configuration,
+            &TreeDecompositionObjective::default(),
+            check_tree_decomposition_bool,
+            clique_bound,
+            rng,
+        );
+
+    (
+        best_tree_decomposition,
+        metrics.iter().map(|metrics| metrics.width).collect(),
+    )
+}
+
+/// Computes an upper bound for the treewidth like [compute_treewidth_upper_bound_multi_start]
+/// except that the best tree decomposition is chosen according to the given objective, i.e. ties
+/// between restarts of the same width are broken by the tie breakers of the objective.
+///
+/// Returns a tuple with the best tree decomposition found and the metrics of all restarts (in the
+/// order of the restarts).
+///
+/// Panics: Panics if the graph is empty or the method is not one of the FillWhilstMST methods
+#[allow(clippy::too_many_arguments)]
+pub fn compute_treewidth_upper_bound_multi_start_with_objective<
+    N: Clone,
+    E: Clone,
+    O: Clone + Ord + Default + Debug + EdgeWeightValue,
+    S: Default + BuildHasher + Clone,
+>(
+    graph: &Graph<N, E, Undirected>,
+    edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    treewidth_computation_method: SpanningTreeConstructionMethod,
+    multi_start_configuration: MultiStartConfiguration,
+    objective: &TreeDecompositionObjective,
+    check_tree_decomposition_bool: bool,
+    clique_bound: Option<usize>,
+    rng: &mut impl Rng,
+) -> (
+    Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    Vec<TreeDecompositionMetrics>,
+) {
+    let cliques = find_cliques::<N, E, S>(graph, clique_bound);
+    let (clique_graph, clique_graph_map) =
+        construct_clique_graph_with_bags(cliques, edge_weight_function);
+
+    let mut best_tree_decomposition: Option<Graph<HashSet<NodeIndex, S>, O, Undirected>> = None;
+    let mut best_key: Option<Vec<u128>> = None;
+    let mut metrics = Vec::with_capacity(multi_start_configuration.number_of_restarts);
+
+    for _ in 0..multi_start_configuration.number_of_restarts.max(1) {
+        let root = multi_start_configuration
+            .root
+            .unwrap_or_else(|| NodeIndex::new(rng.gen_range(0..clique_graph.node_count())));
+
+        let tree_decomposition = fill_bags_while_generating_mst_randomized(
+            &clique_graph,
+            edge_weight_function,
+            clique_graph_map.clone(),
+            treewidth_computation_method,
+            root,
+            multi_start_configuration.restricted_candidate_list,
+            None,
+            rng,
+        )
+        .expect("There is no width bound that could be exceeded");
+
+        if check_tree_decomposition_bool {
+            assert!(
+                check_tree_decomposition(graph, &tree_decomposition, &None, &None),
+                "Tree decomposition is invalid. See previous print statements for reason."
+            );
+        }
+        let tree_decomposition_metrics =
+            find_metrics_of_tree_decomposition(graph, &tree_decomposition);
+        let key = objective.key_of(&tree_decomposition_metrics);
+
+        if best_key.as_ref().map_or(true, |best_key| key < *best_key) {
+            best_tree_decomposition = Some(tree_decomposition);
+            best_key = Some(key);
+        }
+        metrics.push(tree_decomposition_metrics);
+    }
+
+    (
+        best_tree_decomposition.expect("There should be at least one restart"),
+        metrics,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::{BuildHasherDefault, RandomState};
+
+    use rand::{rngs::StdRng, SeedableRng};
+    use rustc_hash::FxHasher;
+
+    use super::*;
+
+    const FILL_WHILST_MST_METHODS: [SpanningTreeConstructionMethod; 4] = [
+        SpanningTreeConstructionMethod::FillWhilstMST,
+        SpanningTreeConstructionMethod::FillWhilstMSTEdgeUpdate,
+        SpanningTreeConstructionMethod::FillWhilstMSTTree,
+        SpanningTreeConstructionMethod::FillWhilstMSTBagSize,
+    ];
+
+    #[test]
+    fn test_multi_start_on_test_graphs() {
+        for i in 1..3 {
+            let test_graph = crate::tests::setup_test_graph(i);
+            for computation_method in FILL_WHILST_MST_METHODS {
+                for restricted_candidate_list in [
+                    RestrictedCandidateList::TopK(3),
+                    RestrictedCandidateList::Alpha(0.5),
+                ] {
+                    let mut rng = StdRng::seed_from_u64(i as u64);
+                    let (best_tree_decomposition, widths) =
+                        compute_treewidth_upper_bound_multi_start::<_, _, _, RandomState>(
+                            &test_graph.graph,
+                            negative_intersection,
+                            computation_method,
+                            MultiStartConfiguration {
+                                restricted_candidate_list,
+                                root: None,
+                                number_of_restarts: 20,
+                            },
+                            true,
+                            None,
+                            &mut rng,
+                        );
+
+                    assert_eq!(widths.len(), 20);
+                    let best_width = find_width_of_tree_decomposition(&best_tree_decomposition);
+                    assert_eq!(Some(&best_width), widths.iter().min());
+                    assert_eq!(
+                        best_width, test_graph.treewidth,
+                        "Test graph {} failed with computation method {:?}",
+                        i, computation_method
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_randomized_fill_respects_width_bound() {
+        for i in 1..3 {
+            let test_graph = crate::tests::setup_test_graph(i);
+            let cliques = find_cliques::<_, _, RandomState>(&test_graph.graph, None);
+            let (clique_graph, clique_graph_map): (Graph<_, i32, _>, _) =
+                construct_clique_graph_with_bags(cliques, negative_intersection::<RandomState>);
+
+            for computation_method in FILL_WHILST_MST_METHODS {
+                let mut rng = StdRng::seed_from_u64(34);
+                // The treewidth is a lower bound for the width of every tree decomposition
+                let width_bound_exceeded = fill_bags_while_generating_mst_randomized(
+                    &clique_graph,
+                    negative_intersection,
+                    clique_graph_map.clone(),
+                    computation_method,
+                    NodeIndex::new(0),
+                    RestrictedCandidateList::TopK(3),
+                    Some(test_graph.treewidth - 1),
+                    &mut rng,
+                )
+                .expect_err("Width bound should be exceeded");
+                assert_eq!(width_bound_exceeded.width_bound, test_graph.treewidth - 1);
+                assert!(width_bound_exceeded.step >= 1);
+
+                let tree_decomposition = fill_bags_while_generating_mst_randomized(
+                    &clique_graph,
+                    negative_intersection,
+                    clique_graph_map.clone(),
+                    computation_method,
+                    NodeIndex::new(0),
+                    RestrictedCandidateList::TopK(3),
+                    Some(test_graph.graph.node_count()),
+                    &mut rng,
+                )
+                .expect("Width bound should not be exceeded");
+                assert!(check_tree_decomposition(
+                    &test_graph.graph,
+                    &tree_decomposition,
+                    &None,
+                    &None
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn test_multi_start_is_reproducible_with_seeded_rng() {
+        let test_graph = crate::tests::setup_test_graph(1);
+        let multi_start_configuration = MultiStartConfiguration {
+            restricted_candidate_list: RestrictedCandidateList::Alpha(1.0),
+            root: Some(NodeIndex::new(0)),
+            number_of_restarts: 10,
+        };
+
+        // The cliques are enumerated in the iteration order of the hash sets, so a deterministic
+        // hasher is needed as well
+        let (first_tree_decomposition, first_widths) =
+            compute_treewidth_upper_bound_multi_start::<_, _, _, BuildHasherDefault<FxHasher>>(
+                &test_graph.graph,
+                constant,
+                SpanningTreeConstructionMethod::FillWhilstMST,
+                multi_start_configuration,
+                true,
+                None,
+                &mut StdRng::seed_from_u64(42),
+            );
+        let (second_tree_decomposition, second_widths) =
+            compute_treewidth_upper_bound_multi_start::<_, _, _, BuildHasherDefault<FxHasher>>(
+                &test_graph.graph,
+                constant,
+                SpanningTreeConstructionMethod::FillWhilstMST,
+                multi_start_configuration,
+                true,
+                None,
+                &mut StdRng::seed_from_u64(42),
+            );
+
+        assert_eq!(first_widths, second_widths);
+        assert!(first_tree_decomposition
+            .node_weights()
+            .eq(second_tree_decomposition.node_weights()));
+    }
+}
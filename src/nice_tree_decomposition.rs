@@ -0,0 +1,480 @@
+use std::{
+    collections::{HashMap, HashSet},
+    hash::BuildHasher,
+};
+
+use itertools::Itertools;
+use petgraph::{graph::NodeIndex, Directed, Graph, Undirected};
+
+/// The types of the nodes of a [nice tree decomposition][make_nice_tree_decomposition].
+///
+/// Leaf A node without children and with an empty bag
+///
+/// Introduce A node with one child whose bag is the bag of the child plus the given vertex
+///
+/// IntroduceEdge A node with one child with the same bag that introduces the given edge between
+/// two vertices of the bag. Every edge of the graph is introduced by exactly one such node.
+///
+/// Forget A node with one child whose bag is the bag of the child without the given vertex
+///
+/// Join A node with two children that have the same bag as the node
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NiceNodeType {
+    Leaf,
+    Introduce(NodeIndex),
+    IntroduceEdge(NodeIndex, NodeIndex),
+    Forget(NodeIndex),
+    Join,
+}
+
+/// A node of a nice tree decomposition.
+///
+/// node_type The type of the node
+///
+/// bag The vertices of the graph in the bag of the node
+#[derive(Clone, Debug)]
+pub struct NiceBag<S> {
+    pub node_type: NiceNodeType,
+    pub bag: HashSet<NodeIndex, S>,
+}
+
+/// A rooted tree decomposition.
+///
+/// tree The tree whose edges are directed from the parent to the child
+///
+/// root The root of the tree
+#[derive(Clone, Debug)]
+pub struct RootedTreeDecomposition<B> {
+    pub tree: Graph<B, (), Directed>,
+    pub root: NodeIndex,
+}
+
+/// A rooted nice tree decomposition (see [make_nice_tree_decomposition]).
+pub type NiceTreeDecomposition<S> = RootedTreeDecomposition<NiceBag<S>>;
+
+/// Converts the tree decomposition of the graph into a nice tree decomposition rooted at the given
+/// bag (or the first bag if root is None).
+///
+/// In a nice tree decomposition the root and the leaves have empty bags and every other node is an
+/// introduce, forget or join node (see [NiceNodeType]). If introduce_edges is true, an introduce
+/// edge node is added for every edge of the graph right before one of its endpoints is forgotten.
+/// The bags of the nice tree decomposition are subsets of the bags of the given tree decomposition,
+/// so the width is preserved. If the tree decomposition is a forest (e.g. of a graph that is not
+/// connected), the nice tree decompositions of its trees are joined below the root.
+///
+/// Panics: Panics if the tree decomposition is empty or not a tree decomposition of the graph.
+pub fn make_nice_tree_decomposition<N, E, O, S: Default + BuildHasher + Clone>(
+    graph: &Graph<N, E, Undirected>,
+    tree_decomposition: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    root: Option<NodeIndex>,
+    introduce_edges: bool,
+) -> NiceTreeDecomposition<S> {
+    let mut nice_tree_decomposition = NiceTreeDecompositionBuilder {
+        graph,
+        tree: Graph::new(),
+        introduced_edges: Default::default(),
+        introduce_edges,
+    };
+
+    // For each bag of the tree decomposition the node of the nice tree decomposition with that bag
+    // at the top of the nice tree decomposition of its subtree
+    let mut top_nodes: HashMap<NodeIndex, NodeIndex, S> = Default::default();
+    let roots = find_roots_of_trees::<O, S>(tree_decomposition, root);
+    for (bag, children) in children_in_post_order::<O, S>(tree_decomposition, &roots) {
+        let bag_content = tree_decomposition
+            .node_weight(bag)
+            .expect("Bags should exist in the tree decomposition");
+        let mut branches: Vec<NodeIndex> = children
+            .iter()
+            .map(|child| nice_tree_decomposition.transform_bag(top_nodes[child], bag_content))
+            .collect();
+        if branches.is_empty() {
+            let leaf =
+                nice_tree_decomposition.add_node(NiceNodeType::Leaf, Default::default(), &[]);
+            branches.push(nice_tree_decomposition.transform_bag(leaf, bag_content));
+        }
+
+        let top_node = branches
+            .into_iter()
+            .reduce(|first_branch, second_branch| {
+                nice_tree_decomposition.add_node(
+                    NiceNodeType::Join,
+                    bag_content.clone(),
+                    &[first_branch, second_branch],
+                )
+            })
+            .expect("There should be at least one branch");
+        top_nodes.insert(bag, top_node);
+    }
+
+    let root = roots
+        .iter()
+        .map(|root| nice_tree_decomposition.transform_bag(top_nodes[root], &Default::default()))
+        .collect_vec()
+        .into_iter()
+        .reduce(|first_branch, second_branch| {
+            nice_tree_decomposition.add_node(
+                NiceNodeType::Join,
+                Default::default(),
+                &[first_branch, second_branch],
+            )
+        })
+        .expect("Tree decomposition should not be empty");
+
+    RootedTreeDecomposition {
+        tree: nice_tree_decomposition.tree,
+        root,
+    }
+}
+
+/// Converts the tree decomposition into a rooted tree decomposition (rooted at the given bag or the
+/// first bag if root is None) in which every node has at most two children. A bag with more
+/// children is replaced by a path of copies of it, each having one of the children and the next
+/// copy as its children. If the tree decomposition is a forest, its trees become the children of a
+/// new root with an empty bag.
+///
+/// Panics: Panics if the tree decomposition is empty.
+pub fn make_binary_tree_decomposition<O, S: Default + BuildHasher + Clone>(
+    tree_decomposition: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    root: Option<NodeIndex>,
+) -> RootedTreeDecomposition<HashSet<NodeIndex, S>> {
+    let mut tree: Graph<HashSet<NodeIndex, S>, (), Directed> = Graph::new();
+    let mut new_nodes: HashMap<NodeIndex, NodeIndex, S> = Default::default();
+    let roots = find_roots_of_trees::<O, S>(tree_decomposition, root);
+    for (bag, children) in children_in_post_order::<O, S>(tree_decomposition, &roots) {
+        let bag_content = tree_decomposition
+            .node_weight(bag)
+            .expect("Bags should exist in the tree decomposition");
+        let new_node = tree.add_node(bag_content.clone());
+        new_nodes.insert(bag, new_node);
+
+        let children = children.iter().map(|child| new_nodes[child]).collect_vec();
+        add_children_using_copies(&mut tree, new_node, children);
+    }
+
+    let root = match roots.as_slice() {
+        [] => panic!("Tree decomposition should not be empty"),
+        [root] => new_nodes[root],
+        _ => {
+            let root = tree.add_node(Default::default());
+            let children = roots.iter().map(|root| new_nodes[root]).collect_vec();
+            add_children_using_copies(&mut tree, root, children);
+            root
+        }
+    };
+    RootedTreeDecomposition { tree, root }
+}
+
+/// Adds the edges from the node to the children, using a path of copies of the node if there are
+/// more than two children (see [make_binary_tree_decomposition]).
+fn add_children_using_copies<S: Clone>(
+    tree: &mut Graph<HashSet<NodeIndex, S>, (), Directed>,
+    mut node: NodeIndex,
+    children: Vec<NodeIndex>,
+) {
+    let number_of_children = children.len();
+    for (child_number, child) in children.into_iter().enumerate() {
+        if child_number >= 1 && number_of_children - child_number >= 2 {
+            let copy = tree.add_node(
+                tree.node_weight(node)
+                    .expect("Node should exist in the tree")
+                    .clone(),
+            );
+            tree.add_edge(node, copy, ());
+            node = copy;
+        }
+        tree.add_edge(node, child, ());
+    }
+}
+
+/// Returns a root for each tree of the tree decomposition, which is a forest if the graph is not
+/// connected. The given bag (or the first bag if root is None) is the first root, the other trees
+/// are rooted at their bag with the smallest index.
+fn find_roots_of_trees<O, S: Default + BuildHasher>(
+    tree_decomposition: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    root: Option<NodeIndex>,
+) -> Vec<NodeIndex> {
+    let mut roots: Vec<NodeIndex> = Vec::new();
+    let mut visited: HashSet<NodeIndex, S> = Default::default();
+    for bag in root.into_iter().chain(tree_decomposition.node_indices()) {
+        if visited.contains(&bag) {
+            continue;
+        }
+        roots.push(bag);
+        let mut stack = vec![bag];
+        visited.insert(bag);
+        while let Some(bag) = stack.pop() {
+            for neighbour in tree_decomposition.neighbors(bag) {
+                if visited.insert(neighbour) {
+                    stack.push(neighbour);
+                }
+            }
+        }
+    }
+    roots
+}
+
+/// Returns the bags of the tree decomposition together with their children when each tree is
+/// rooted at the given root of it (see [find_roots_of_trees]). Children come before their parents.
+fn children_in_post_order<O, S>(
+    tree_decomposition: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    roots: &[NodeIndex],
+) -> Vec<(NodeIndex, Vec<NodeIndex>)> {
+    let mut pre_order: Vec<(NodeIndex, Vec<NodeIndex>)> = Vec::new();
+    let mut stack: Vec<(NodeIndex, Option<NodeIndex>)> =
+        roots.iter().map(|root| (*root, None)).collect();
+    while let Some((bag, parent)) = stack.pop() {
+        let children = tree_decomposition
+            .neighbors(bag)
+            .filter(|neighbour| Some(*neighbour) != parent)
+            .sorted()
+            .collect_vec();
+        stack.extend(children.iter().map(|child| (*child, Some(bag))));
+        pre_order.push((bag, children));
+    }
+    pre_order.reverse();
+    pre_order
+}
+
+/// State while building a nice tree decomposition.
+struct NiceTreeDecompositionBuilder<'a, N, E, S> {
+    graph: &'a Graph<N, E, Undirected>,
+    tree: Graph<NiceBag<S>, (), Directed>,
+    introduced_edges: HashSet<(NodeIndex, NodeIndex), S>,
+    introduce_edges: bool,
+}
+
+impl<N, E, S: Default + BuildHasher + Clone> NiceTreeDecompositionBuilder<'_, N, E, S> {
+    fn add_node(
+        &mut self,
+        node_type: NiceNodeType,
+        bag: HashSet<NodeIndex, S>,
+        children: &[NodeIndex],
+    ) -> NodeIndex {
+        let node = self.tree.add_node(NiceBag { node_type, bag });
+        for child in children {
+            self.tree.add_edge(node, *child, ());
+        }
+        node
+    }
+
+    fn bag(&self, node: NodeIndex) -> &HashSet<NodeIndex, S> {
+        &self
+            .tree
+            .node_weight(node)
+            .expect("Nodes should exist in the nice tree decomposition")
+            .bag
+    }
+
+    /// Adds forget nodes for the vertices of the bag of the node that are not in the target bag and
+    /// then introduce nodes for the vertices of the target bag that are not in the bag of the node.
+    /// Returns the last added node whose bag is the target bag.
+    fn transform_bag(&mut self, node: NodeIndex, target_bag: &HashSet<NodeIndex, S>) -> NodeIndex {
+        let mut node = node;
+        let vertices_to_forget = self
+            .bag(node)
+            .difference(target_bag)
+            .cloned()
+            .sorted()
+            .collect_vec();
+        for vertex in vertices_to_forget {
+            node = self.forget(node, vertex);
+        }
+
+        let vertices_to_introduce = target_bag
+            .difference(self.bag(node))
+            .cloned()
+            .sorted()
+            .collect_vec();
+        for vertex in vertices_to_introduce {
+            let mut bag = self.bag(node).clone();
+            bag.insert(vertex);
+            node = self.add_node(NiceNodeType::Introduce(vertex), bag, &[node]);
+        }
+        node
+    }
+
+    /// Adds a forget node for the vertex above the node. If edges are introduced, the edges between
+    /// the vertex and the other vertices of the bag that were not introduced yet are introduced
+    /// right before.
+    fn forget(&mut self, node: NodeIndex, vertex: NodeIndex) -> NodeIndex {
+        let mut node = node;
+        if self.introduce_edges {
+            let neighbours = self
+                .bag(node)
+                .iter()
+                .filter(|neighbour| {
+                    **neighbour != vertex && self.graph.contains_edge(vertex, **neighbour)
+                })
+                .cloned()
+                .sorted()
+                .collect_vec();
+            for neighbour in neighbours {
+                let edge = (vertex.min(neighbour), vertex.max(neighbour));
+                if self.introduced_edges.insert(edge) {
+                    let bag = self.bag(node).clone();
+                    node = self.add_node(NiceNodeType::IntroduceEdge(edge.0, edge.1), bag, &[node]);
+                }
+            }
+        }
+
+        let mut bag = self.bag(node).clone();
+        bag.remove(&vertex);
+        self.add_node(NiceNodeType::Forget(vertex), bag, &[node])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::RandomState;
+
+    use petgraph::visit::EdgeRef;
+
+    use super::*;
+    use crate::{check_tree_decomposition, compute_treewidth_upper_bound, negative_intersection};
+
+    /// Checks the properties of the node types and returns the introduced edges.
+    fn check_nice_tree_decomposition(
+        nice_tree_decomposition: &NiceTreeDecomposition<RandomState>,
+    ) -> Vec<(NodeIndex, NodeIndex)> {
+        let tree = &nice_tree_decomposition.tree;
+        assert!(tree[nice_tree_decomposition.root].bag.is_empty());
+        let mut introduced_edges = Vec::new();
+        for node in tree.node_indices() {
+            let children: Vec<&NiceBag<RandomState>> =
+                tree.edges(node).map(|edge| &tree[edge.target()]).collect();
+            let bag = &tree[node].bag;
+            match tree[node].node_type {
+                NiceNodeType::Leaf => assert!(children.is_empty() && bag.is_empty()),
+                NiceNodeType::Introduce(vertex) => {
+                    assert_eq!(children.len(), 1);
+                    assert!(!children[0].bag.contains(&vertex));
+                    let mut child_bag = children[0].bag.clone();
+                    child_bag.insert(vertex);
+                    assert_eq!(&child_bag, bag);
+                }
+                NiceNodeType::IntroduceEdge(first_vertex, second_vertex) => {
+                    assert_eq!(children.len(), 1);
+                    assert_eq!(&children[0].bag, bag);
+                    assert!(bag.contains(&first_vertex) && bag.contains(&second_vertex));
+                    introduced_edges.push((first_vertex, second_vertex));
+                }
+                NiceNodeType::Forget(vertex) => {
+                    assert_eq!(children.len(), 1);
+                    assert!(children[0].bag.contains(&vertex));
+                    let mut child_bag = children[0].bag.clone();
+                    child_bag.remove(&vertex);
+                    assert_eq!(&child_bag, bag);
+                }
+                NiceNodeType::Join => {
+                    assert_eq!(children.len(), 2);
+                    assert!(children.iter().all(|child| &child.bag == bag));
+                }
+            }
+        }
+        introduced_edges
+    }
+
+    #[test]
+    fn test_make_nice_tree_decomposition() {
+        for i in 1..3 {
+            let test_graph = crate::tests::setup_test_graph(i);
+            let (_, tree_decomposition, _, _, _, treewidth) =
+                compute_treewidth_upper_bound::<_, _, _, RandomState>(
+                    &test_graph.graph,
+                    negative_intersection,
+                    crate::SpanningTreeConstructionMethod::MSTAndFill,
+                    false,
+                    None,
+                );
+
+            for introduce_edges in [false, true] {
+                let nice_tree_decomposition = make_nice_tree_decomposition(
+                    &test_graph.graph,
+                    &tree_decomposition,
+                    None,
+                    introduce_edges,
+                );
+                let introduced_edges = check_nice_tree_decomposition(&nice_tree_decomposition);
+                if introduce_edges {
+                    assert_eq!(
+                        introduced_edges.into_iter().sorted().collect_vec(),
+                        test_graph
+                            .graph
+                            .edge_references()
+                            .map(|edge| (
+                                edge.source().min(edge.target()),
+                                edge.source().max(edge.target())
+                            ))
+                            .sorted()
+                            .collect_vec()
+                    );
+                } else {
+                    assert!(introduced_edges.is_empty());
+                }
+
+                let undirected_tree: Graph<HashSet<NodeIndex, RandomState>, (), Undirected> =
+                    nice_tree_decomposition
+                        .tree
+                        .map(|_, node| node.bag.clone(), |_, _| ())
+                        .into_edge_type();
+                assert!(check_tree_decomposition(
+                    &test_graph.graph,
+                    &undirected_tree,
+                    &None,
+                    &None
+                ));
+                assert_eq!(
+                    crate::find_width_of_tree_decomposition(&undirected_tree),
+                    treewidth
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_make_binary_tree_decomposition() {
+        // Star with five leaves
+        let mut tree_decomposition: Graph<HashSet<NodeIndex, RandomState>, i32, Undirected> =
+            Graph::new_undirected();
+        let center = tree_decomposition.add_node(HashSet::from_iter([NodeIndex::new(0)]));
+        for i in 1..6 {
+            let leaf = tree_decomposition
+                .add_node(HashSet::from_iter([NodeIndex::new(0), NodeIndex::new(i)]));
+            tree_decomposition.add_edge(center, leaf, 0);
+        }
+
+        let binary_tree_decomposition = make_binary_tree_decomposition(&tree_decomposition, None);
+        let tree = &binary_tree_decomposition.tree;
+        assert_eq!(tree.node_count(), 9);
+        assert_eq!(tree.edge_count(), 8);
+        assert!(tree
+            .node_indices()
+            .all(|node| tree.neighbors(node).count() <= 2));
+        assert_eq!(tree[binary_tree_decomposition.root].len(), 1);
+        assert_eq!(
+            petgraph::algo::connected_components(tree),
+            1,
+            "Binary tree decomposition should be connected"
+        );
+    }
+
+    #[test]
+    fn test_make_binary_tree_decomposition_of_forest() {
+        // Three bags without edges between them
+        let mut tree_decomposition: Graph<HashSet<NodeIndex, RandomState>, i32, Undirected> =
+            Graph::new_undirected();
+        for i in 0..3 {
+            tree_decomposition.add_node(HashSet::from_iter([NodeIndex::new(i)]));
+        }
+
+        let binary_tree_decomposition = make_binary_tree_decomposition(&tree_decomposition, None);
+        let tree = &binary_tree_decomposition.tree;
+        assert_eq!(tree.node_count(), 5);
+        assert!(tree[binary_tree_decomposition.root].is_empty());
+        assert!(tree
+            .node_indices()
+            .all(|node| tree.neighbors(node).count() <= 2));
+        assert_eq!(petgraph::algo::connected_components(tree), 1);
+    }
+}
@@ -0,0 +1,388 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt::Debug,
+    hash::BuildHasher,
+};
+
+use petgraph::{graph::NodeIndex, Graph, Undirected};
+
+use crate::{
+    check_tree_decomposition, compute_treewidth_upper_bound, find_cliques,
+    SpanningTreeConstructionMethod,
+};
+
+/// Converts a tree decomposition into a path decomposition, given as the sequence of its bags.
+///
+/// Uses a heavy path decomposition of the tree rooted at its first bag: The bags of the heavy path
+/// (starting at the root and always continuing with the child with the biggest subtree) form the
+/// spine of the path. Before each bag of the spine, the path decompositions of the subtrees hanging
+/// off it are inserted recursively, adding the vertices of the spine bag to each of their bags.
+/// Each of these subtrees has at most half the size of the tree, so the width of the path
+/// decomposition is at most (w + 1)(log_2(n) + 1) - 1 for a tree decomposition of width w with n
+/// bags. Bags that are subsets of a neighbouring bag are removed afterwards.
+pub fn path_decomposition_from_tree_decomposition<O, S: Default + BuildHasher + Clone>(
+    tree_decomposition: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+) -> Vec<HashSet<NodeIndex, S>> {
+    let root = match tree_decomposition.node_indices().next() {
+        Some(root) => root,
+        None => return Vec::new(),
+    };
+
+    // Children and subtree sizes of the rooted tree decomposition
+    let mut children: HashMap<NodeIndex, Vec<NodeIndex>, S> = Default::default();
+    let mut pre_order = Vec::with_capacity(tree_decomposition.node_count());
+    let mut stack: Vec<(NodeIndex, Option<NodeIndex>)> = vec![(root, None)];
+    while let Some((bag, parent)) = stack.pop() {
+        let bag_children: Vec<NodeIndex> = tree_decomposition
+            .neighbors(bag)
+            .filter(|neighbour| Some(*neighbour) != parent)
+            .collect();
+        stack.extend(bag_children.iter().map(|child| (*child, Some(bag))));
+        children.insert(bag, bag_children);
+        pre_order.push(bag);
+    }
+    let mut subtree_sizes: HashMap<NodeIndex, usize, S> = Default::default();
+    for bag in pre_order.iter().rev() {
+        let size = 1 + children[bag]
+            .iter()
+            .map(|child| subtree_sizes[child])
+            .sum::<usize>();
+        subtree_sizes.insert(*bag, size);
+    }
+
+    let mut path_decomposition = Vec::new();
+    append_path_decomposition_of_subtree(
+        tree_decomposition,
+        &children,
+        &subtree_sizes,
+        root,
+        &mut path_decomposition,
+    );
+    remove_redundant_bags(path_decomposition)
+}
+
+/// Computes an upper bound for the pathwidth by computing a tree decomposition using
+/// [compute_treewidth_upper_bound] and converting it into a path decomposition (see
+/// [path_decomposition_from_tree_decomposition]).
+///
+/// Returns the bags of the path decomposition and its width.
+///
+/// Panics: If check_tree_decomposition_bool is true, panics if the path decomposition is incorrect.
+pub fn compute_pathwidth_upper_bound<
+    N: Clone,
+    E: Clone,
+    O: Clone + Ord + Default + Debug,
+    S: Default + BuildHasher + Clone,
+>(
+    graph: &Graph<N, E, Undirected>,
+    edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    treewidth_computation_method: SpanningTreeConstructionMethod,
+    check_tree_decomposition_bool: bool,
+    clique_bound: Option<usize>,
+) -> (Vec<HashSet<NodeIndex, S>>, usize) {
+    let (_, tree_decomposition, _, _, _, _) = compute_treewidth_upper_bound(
+        graph,
+        edge_weight_function,
+        treewidth_computation_method,
+        false,
+        clique_bound,
+    );
+    let path_decomposition = path_decomposition_from_tree_decomposition(&tree_decomposition);
+
+    finish_path_decomposition(graph, path_decomposition, check_tree_decomposition_bool)
+}
+
+/// Computes an upper bound for the pathwidth by arranging the maximal cliques of the graph (or the
+/// cliques given by the clique bound, see [compute_treewidth_upper_bound]) in a path instead of a
+/// spanning tree of the clique graph and filling up the bags along the path.
+///
+/// The path starts with the first clique and is extended greedily at either end by the clique that
+/// minimizes the edge weight function together with the bag at that end. Afterwards each vertex is
+/// added to all bags between its first and its last occurrence.
+///
+/// Returns the bags of the path decomposition and its width. The graph doesn't need to be
+/// connected.
+///
+/// Panics: If check_tree_decomposition_bool is true, panics if the path decomposition is incorrect.
+pub fn compute_pathwidth_upper_bound_using_clique_path<
+    N: Clone,
+    E: Clone,
+    O: Clone + Ord + Default + Debug,
+    S: Default + BuildHasher + Clone,
+>(
+    graph: &Graph<N, E, Undirected>,
+    edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    check_tree_decomposition_bool: bool,
+    clique_bound: Option<usize>,
+) -> (Vec<HashSet<NodeIndex, S>>, usize) {
+    let mut remaining_cliques: Vec<HashSet<NodeIndex, S>> =
+        find_cliques::<N, E, S>(graph, clique_bound)
+            .into_iter()
+            .map(|clique| clique.into_iter().collect())
+            .collect();
+    if remaining_cliques.is_empty() {
+        return (Vec::new(), 0);
+    }
+
+    let mut path_decomposition = VecDeque::new();
+    path_decomposition.push_back(remaining_cliques.swap_remove(0));
+    while !remaining_cliques.is_empty() {
+        let front = path_decomposition
+            .front()
+            .expect("Path decomposition should not be empty");
+        let back = path_decomposition
+            .back()
+            .expect("Path decomposition should not be empty");
+        let (clique_position, add_to_front, _) = remaining_cliques
+            .iter()
+            .enumerate()
+            .flat_map(|(position, clique)| {
+                [
+                    (position, true, edge_weight_function(front, clique)),
+                    (position, false, edge_weight_function(back, clique)),
+                ]
+            })
+            .min_by(|(_, _, first_weight), (_, _, second_weight)| first_weight.cmp(second_weight))
+            .expect("There should be remaining cliques");
+
+        let clique = remaining_cliques.swap_remove(clique_position);
+        if add_to_front {
+            path_decomposition.push_front(clique);
+        } else {
+            path_decomposition.push_back(clique);
+        }
+    }
+
+    // Fill up the bags between the first and last occurrence of each vertex
+    let mut path_decomposition: Vec<HashSet<NodeIndex, S>> = path_decomposition.into();
+    let mut occurrences: HashMap<NodeIndex, (usize, usize), S> = Default::default();
+    for (position, bag) in path_decomposition.iter().enumerate() {
+        for vertex in bag.iter() {
+            occurrences
+                .entry(*vertex)
+                .and_modify(|(_, last)| *last = position)
+                .or_insert((position, position));
+        }
+    }
+    for (vertex, (first, last)) in occurrences {
+        for bag in path_decomposition[first..=last].iter_mut() {
+            bag.insert(vertex);
+        }
+    }
+
+    finish_path_decomposition(
+        graph,
+        remove_redundant_bags(path_decomposition),
+        check_tree_decomposition_bool,
+    )
+}
+
+/// Appends the path decomposition of the subtree rooted at the bag (see
+/// [path_decomposition_from_tree_decomposition]) to the given bags.
+fn append_path_decomposition_of_subtree<O, S: Default + BuildHasher + Clone>(
+    tree_decomposition: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    children: &HashMap<NodeIndex, Vec<NodeIndex>, S>,
+    subtree_sizes: &HashMap<NodeIndex, usize, S>,
+    root: NodeIndex,
+    path_decomposition: &mut Vec<HashSet<NodeIndex, S>>,
+) {
+    let mut spine_bag = Some(root);
+    while let Some(bag) = spine_bag {
+        let bag_content = tree_decomposition
+            .node_weight(bag)
+            .expect("Bags should exist in the tree decomposition");
+        let heavy_child = children[&bag]
+            .iter()
+            .max_by_key(|child| subtree_sizes[*child])
+            .cloned();
+
+        for light_child in children[&bag]
+            .iter()
+            .filter(|child| Some(**child) != heavy_child)
+        {
+            let first_new_bag = path_decomposition.len();
+            append_path_decomposition_of_subtree(
+                tree_decomposition,
+                children,
+                subtree_sizes,
+                *light_child,
+                path_decomposition,
+            );
+            for new_bag in path_decomposition[first_new_bag..].iter_mut() {
+                new_bag.extend(bag_content.iter().cloned());
+            }
+        }
+        path_decomposition.push(bag_content.clone());
+
+        spine_bag = heavy_child;
+    }
+}
+
+/// Removes the bags that are subsets of a neighbouring bag.
+fn remove_redundant_bags<S: BuildHasher>(
+    path_decomposition: Vec<HashSet<NodeIndex, S>>,
+) -> Vec<HashSet<NodeIndex, S>> {
+    let mut reduced_path_decomposition: Vec<HashSet<NodeIndex, S>> = Vec::new();
+    for bag in path_decomposition {
+        match reduced_path_decomposition.last() {
+            Some(previous_bag) if bag.is_subset(previous_bag) => {}
+            Some(previous_bag) if previous_bag.is_subset(&bag) => {
+                *reduced_path_decomposition
+                    .last_mut()
+                    .expect("There should be a previous bag") = bag;
+            }
+            _ => reduced_path_decomposition.push(bag),
+        }
+    }
+    reduced_path_decomposition
+}
+
+/// Checks the path decomposition if required and returns it together with its width.
+fn finish_path_decomposition<N, E, S: Default + BuildHasher + Clone>(
+    graph: &Graph<N, E, Undirected>,
+    path_decomposition: Vec<HashSet<NodeIndex, S>>,
+    check_tree_decomposition_bool: bool,
+) -> (Vec<HashSet<NodeIndex, S>>, usize) {
+    if check_tree_decomposition_bool {
+        let mut path: Graph<HashSet<NodeIndex, S>, (), Undirected> = Graph::new_undirected();
+        let mut previous_bag: Option<NodeIndex> = None;
+        for bag in path_decomposition.iter() {
+            let new_bag = path.add_node(bag.clone());
+            if let Some(previous_bag) = previous_bag {
+                path.add_edge(previous_bag, new_bag, ());
+            }
+            previous_bag = Some(new_bag);
+        }
+        assert!(
+            check_tree_decomposition(graph, &path, &None, &None),
+            "Path decomposition is invalid. See previous print statements for reason."
+        );
+    }
+    let pathwidth = path_decomposition
+        .iter()
+        .map(|bag| bag.len())
+        .max()
+        .unwrap_or(0)
+        .saturating_sub(1);
+
+    (path_decomposition, pathwidth)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::{BuildHasherDefault, RandomState};
+
+    use rustc_hash::FxHasher;
+
+    use super::*;
+    use crate::{find_width_of_tree_decomposition, negative_intersection};
+
+    /// Converts the tree decomposition into a path decomposition, checks it and asserts the bound
+    /// of (w + 1)(log_2(n) + 1) - 1 on its width (see [path_decomposition_from_tree_decomposition]).
+    /// Returns the width of the path decomposition.
+    fn assert_path_decomposition_respects_bound<N, E, O>(
+        graph: &Graph<N, E, Undirected>,
+        tree_decomposition: &Graph<HashSet<NodeIndex, RandomState>, O, Undirected>,
+    ) -> usize {
+        let (_, pathwidth) = finish_path_decomposition(
+            graph,
+            path_decomposition_from_tree_decomposition(tree_decomposition),
+            true,
+        );
+        let treewidth = find_width_of_tree_decomposition(tree_decomposition);
+        let number_of_bags = tree_decomposition.node_count();
+        assert!(
+            pathwidth < (treewidth + 1) * (number_of_bags.ilog2() as usize + 1),
+            "Path decomposition of width {} exceeds the bound for width {} and {} bags",
+            pathwidth,
+            treewidth,
+            number_of_bags
+        );
+        pathwidth
+    }
+
+    #[test]
+    fn test_path_decomposition_of_binary_tree() {
+        // Complete binary tree with 15 vertices has pathwidth 2
+        let mut graph: Graph<i32, i32, Undirected> = Graph::new_undirected();
+        let vertices: Vec<NodeIndex> = (0..15).map(|i| graph.add_node(i)).collect();
+        for i in 1..15 {
+            graph.add_edge(vertices[(i - 1) / 2], vertices[i], 0);
+        }
+
+        let (_, tree_decomposition, _, _, _, treewidth) =
+            compute_treewidth_upper_bound::<_, _, _, RandomState>(
+                &graph,
+                negative_intersection,
+                SpanningTreeConstructionMethod::MSTAndFill,
+                true,
+                None,
+            );
+        assert_eq!(treewidth, 1);
+        assert_eq!(tree_decomposition.node_count(), 14);
+        let pathwidth = assert_path_decomposition_respects_bound(&graph, &tree_decomposition);
+        assert!(pathwidth >= 2);
+    }
+
+    #[test]
+    fn test_clique_path_grows_at_both_ends() {
+        let mut graph: Graph<i32, i32, Undirected> = Graph::new_undirected();
+        let vertices: Vec<NodeIndex> = (0..7).map(|i| graph.add_node(i)).collect();
+        for i in 1..7 {
+            graph.add_edge(vertices[i - 1], vertices[i], 0);
+        }
+        // With this hasher the first clique found is the edge between vertex 1 and 2, so the path
+        // needs to be extended at the front by the edge between vertex 0 and 1
+        let first_clique: HashSet<NodeIndex, BuildHasherDefault<FxHasher>> =
+            find_cliques::<_, _, BuildHasherDefault<FxHasher>>(&graph, None)[0]
+                .iter()
+                .cloned()
+                .collect();
+        assert_eq!(first_clique, HashSet::from_iter([vertices[1], vertices[2]]));
+
+        let (path_decomposition, pathwidth) = compute_pathwidth_upper_bound_using_clique_path::<
+            _,
+            _,
+            _,
+            BuildHasherDefault<FxHasher>,
+        >(&graph, negative_intersection, true, None);
+        assert_eq!(pathwidth, 1);
+        let expected_path_decomposition: Vec<HashSet<NodeIndex, BuildHasherDefault<FxHasher>>> = (1
+            ..7)
+            .map(|i| HashSet::from_iter([vertices[i - 1], vertices[i]]))
+            .collect();
+        assert!(
+            path_decomposition == expected_path_decomposition
+                || path_decomposition
+                    .iter()
+                    .eq(expected_path_decomposition.iter().rev())
+        );
+    }
+
+    #[test]
+    fn test_pathwidth_heuristics() {
+        for (graph, treewidth) in
+            crate::tests::setup_test_graphs_and_partial_k_trees(44, 5, 3, 30, 50)
+        {
+            let (_, tree_decomposition, _, _, _, _) =
+                compute_treewidth_upper_bound::<_, _, _, RandomState>(
+                    &graph,
+                    negative_intersection,
+                    SpanningTreeConstructionMethod::FillWhilstMST,
+                    true,
+                    None,
+                );
+            let tree_decomposition_pathwidth =
+                assert_path_decomposition_respects_bound(&graph, &tree_decomposition);
+            let (_, clique_path_pathwidth) =
+                compute_pathwidth_upper_bound_using_clique_path::<_, _, _, RandomState>(
+                    &graph,
+                    negative_intersection,
+                    true,
+                    None,
+                );
+            assert!(tree_decomposition_pathwidth >= treewidth);
+            assert!(clique_path_pathwidth >= treewidth);
+        }
+    }
+}
@@ -0,0 +1,473 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Debug,
+    hash::BuildHasher,
+};
+
+use itertools::Itertools;
+use petgraph::{graph::NodeIndex, Graph, Undirected};
+
+use crate::{
+    check_tree_decomposition, compute_treewidth_upper_bound_using_clique_separators,
+    elimination_ordering::{adjacency_map, eliminate_vertex},
+    find_width_of_tree_decomposition, maximum_minimum_degree_plus, SpanningTreeConstructionMethod,
+};
+
+/// The safe reduction rules by Bodlaender, Koster and van den Eijkhof. Each of them eliminates
+/// vertices (making their neighbourhood a clique) without increasing the treewidth beyond the
+/// maximum of the current lower bound and the treewidth of the reduced graph.
+///
+/// Islet Removes a vertex of degree 0
+///
+/// Twig Removes a vertex of degree 1, the lower bound is at least 1
+///
+/// Series Eliminates a vertex of degree 2 if the lower bound is at least 2
+///
+/// Triangle Eliminates a vertex of degree 3 that has two adjacent neighbours if the lower bound is
+/// at least 3
+///
+/// Buddy Eliminates two vertices of degree 3 with the same neighbours if the lower bound is at
+/// least 3
+///
+/// Cube Eliminates the vertices a, b, c and d of degree 3 of a cube with a missing corner, where a
+/// is adjacent to b, c and d and each pair of b, c and d has another common neighbour, if the
+/// lower bound is at least 3
+///
+/// Simplicial Removes a vertex whose neighbourhood is a clique, the lower bound is at least its
+/// degree
+///
+/// AlmostSimplicial Eliminates a vertex whose neighbourhood without one of its neighbours is a
+/// clique if the lower bound is at least its degree
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReductionRule {
+    Islet,
+    Twig,
+    Series,
+    Triangle,
+    Buddy,
+    Cube,
+    Simplicial,
+    AlmostSimplicial,
+}
+
+/// Entry of the undo log of [reduce_graph].
+///
+/// rule The reduction rule that eliminated the vertex
+///
+/// vertex The eliminated vertex (of the original graph)
+///
+/// neighbours The neighbours of the vertex at the time of its elimination. They form a clique in
+/// the graph after the elimination.
+#[derive(Clone, Debug)]
+pub struct Reduction<S> {
+    pub rule: ReductionRule,
+    pub vertex: NodeIndex,
+    pub neighbours: HashSet<NodeIndex, S>,
+}
+
+/// Result of [reduce_graph].
+///
+/// graph The reduced graph whose vertices are weighted by the corresponding vertices of the
+/// original graph
+///
+/// undo_log The reductions in the order they were applied
+///
+/// lower_bound A lower bound for the treewidth of the original graph. The treewidth of the
+/// original graph is the maximum of the lower bound and the treewidth of the reduced graph.
+#[derive(Clone, Debug)]
+pub struct ReducedGraph<S> {
+    pub graph: Graph<NodeIndex, (), Undirected>,
+    pub undo_log: Vec<Reduction<S>>,
+    pub lower_bound: usize,
+}
+
+/// Applies the [reduction rules][ReductionRule] to the graph until none of them applies anymore.
+///
+/// The given lower bound for the treewidth of the graph is used by the rules and raised whenever a
+/// rule allows it. The rules are tried on the vertices in the order of their node indices and
+/// in the order of the variants of [ReductionRule].
+///
+/// The tree decomposition of the reduced graph can be expanded to one of the original graph using
+/// [expand_tree_decomposition].
+pub fn reduce_graph<N, E, S: Default + BuildHasher + Clone>(
+    graph: &Graph<N, E, Undirected>,
+    lower_bound: usize,
+) -> ReducedGraph<S> {
+    let mut adjacency: HashMap<NodeIndex, HashSet<NodeIndex, S>, S> = adjacency_map(graph);
+    let mut undo_log = Vec::new();
+    let mut lower_bound = lower_bound;
+
+    let mut reduced = true;
+    while reduced {
+        reduced = false;
+        for vertex in adjacency.keys().cloned().sorted().collect_vec() {
+            if let Some((rule, vertices_to_eliminate)) =
+                find_applicable_rule(&adjacency, vertex, &mut lower_bound)
+            {
+                for vertex_to_eliminate in vertices_to_eliminate {
+                    let neighbours = eliminate_vertex(&mut adjacency, vertex_to_eliminate, true);
+                    undo_log.push(Reduction {
+                        rule,
+                        vertex: vertex_to_eliminate,
+                        neighbours,
+                    });
+                }
+                reduced = true;
+            }
+        }
+    }
+
+    let mut reduced_graph: Graph<NodeIndex, (), Undirected> = Graph::new_undirected();
+    let mut node_index_map: HashMap<NodeIndex, NodeIndex, S> = Default::default();
+    for vertex in adjacency.keys().sorted() {
+        node_index_map.insert(*vertex, reduced_graph.add_node(*vertex));
+    }
+    for (vertex, neighbours) in adjacency.iter() {
+        for neighbour in neighbours.iter().filter(|neighbour| vertex < *neighbour) {
+            reduced_graph.add_edge(node_index_map[vertex], node_index_map[neighbour], ());
+        }
+    }
+
+    ReducedGraph {
+        graph: reduced_graph,
+        undo_log,
+        lower_bound,
+    }
+}
+
+/// Expands a tree decomposition of the reduced graph to a tree decomposition of the original graph
+/// by undoing the reductions in reverse order: For each eliminated vertex a bag containing it and
+/// its neighbours is attached to a bag containing the neighbours. The edges of the given tree
+/// decomposition keep their weights, new edges get the default weight.
+///
+/// The width of the result is the maximum of the width of the given tree decomposition and the
+/// lower bound of the reduced graph.
+///
+/// Panics: Panics if the tree decomposition isn't a tree decomposition of the reduced graph.
+pub fn expand_tree_decomposition<O: Clone + Default, S: Default + BuildHasher + Clone>(
+    reduced_graph: &ReducedGraph<S>,
+    tree_decomposition: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+) -> Graph<HashSet<NodeIndex, S>, O, Undirected> {
+    let mut expanded_tree_decomposition: Graph<HashSet<NodeIndex, S>, O, Undirected> =
+        tree_decomposition.map(
+            |_, bag| {
+                bag.iter()
+                    .map(|vertex| {
+                        *reduced_graph
+                            .graph
+                            .node_weight(*vertex)
+                            .expect("Bags should contain vertices of the reduced graph")
+                    })
+                    .collect()
+            },
+            |_, weight| weight.clone(),
+        );
+
+    for reduction in reduced_graph.undo_log.iter().rev() {
+        let mut bag = reduction.neighbours.clone();
+        bag.insert(reduction.vertex);
+        let parent_bag = expanded_tree_decomposition
+            .node_indices()
+            .find(|bag_index| {
+                reduction.neighbours.is_subset(
+                    expanded_tree_decomposition
+                        .node_weight(*bag_index)
+                        .expect("Bags should exist in the tree decomposition"),
+                )
+            });
+        let bag_index = expanded_tree_decomposition.add_node(bag);
+        match parent_bag {
+            Some(parent_bag) => {
+                expanded_tree_decomposition.add_edge(bag_index, parent_bag, O::default());
+            }
+            None => assert_eq!(
+                bag_index.index(),
+                0,
+                "Neighbours of an eliminated vertex should be contained in a bag"
+            ),
+        }
+    }
+
+    expanded_tree_decomposition
+}
+
+/// Computes an upper bound for the treewidth by first applying the [reduction rules][reduce_graph]
+/// (using [maximum_minimum_degree_plus] as the initial lower bound), then computing a tree
+/// decomposition of the reduced graph (see
+/// [compute_treewidth_upper_bound_using_clique_separators], as the reduced graph might not be
+/// connected) and finally expanding it to a tree decomposition of the graph.
+///
+/// Returns the tree decomposition and its width.
+///
+/// Panics: If check_tree_decomposition_bool is true, panics if the resulting tree decomposition is
+/// incorrect.
+pub fn compute_treewidth_upper_bound_using_reductions<
+    N: Clone,
+    E: Clone,
+    O: Clone + Ord + Default + Debug,
+    S: Default + BuildHasher + Clone,
+>(
+    graph: &Graph<N, E, Undirected>,
+    edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    treewidth_computation_method: SpanningTreeConstructionMethod,
+    check_tree_decomposition_bool: bool,
+    clique_bound: Option<usize>,
+) -> (Graph<HashSet<NodeIndex, S>, O, Undirected>, usize) {
+    let lower_bound = maximum_minimum_degree_plus(&graph.map(|_, _| (), |_, _| ()));
+    let reduced_graph = reduce_graph::<N, E, S>(graph, lower_bound);
+
+    let reduced_tree_decomposition = if reduced_graph.graph.node_count() == 0 {
+        Graph::new_undirected()
+    } else {
+        compute_treewidth_upper_bound_using_clique_separators(
+            &reduced_graph.graph,
+            edge_weight_function,
+            treewidth_computation_method,
+            false,
+            clique_bound,
+        )
+        .0
+    };
+    let tree_decomposition = expand_tree_decomposition(&reduced_graph, &reduced_tree_decomposition);
+
+    if check_tree_decomposition_bool {
+        assert!(
+            check_tree_decomposition(graph, &tree_decomposition, &None, &None),
+            "Tree decomposition is invalid. See previous print statements for reason."
+        );
+    }
+    let treewidth = find_width_of_tree_decomposition(&tree_decomposition);
+
+    (tree_decomposition, treewidth)
+}
+
+/// Returns the first reduction rule that applies to the vertex together with the vertices to
+/// eliminate in order, raising the lower bound if the rule allows it.
+fn find_applicable_rule<S: BuildHasher>(
+    adjacency: &HashMap<NodeIndex, HashSet<NodeIndex, S>, S>,
+    vertex: NodeIndex,
+    lower_bound: &mut usize,
+) -> Option<(ReductionRule, Vec<NodeIndex>)> {
+    // Vertex might have been eliminated by the buddy or cube rule already
+    let neighbours = adjacency.get(&vertex)?;
+    let degree = neighbours.len();
+    let are_adjacent = |first_vertex: &NodeIndex, second_vertex: &NodeIndex| {
+        adjacency
+            .get(first_vertex)
+            .expect("Neighbours should be in the adjacency")
+            .contains(second_vertex)
+    };
+
+    if degree == 0 {
+        return Some((ReductionRule::Islet, vec![vertex]));
+    }
+    if degree == 1 {
+        *lower_bound = (*lower_bound).max(1);
+        return Some((ReductionRule::Twig, vec![vertex]));
+    }
+    if degree == 2 && *lower_bound >= 2 {
+        return Some((ReductionRule::Series, vec![vertex]));
+    }
+    if degree == 3 && *lower_bound >= 3 {
+        if neighbours
+            .iter()
+            .tuple_combinations()
+            .any(|(first_neighbour, second_neighbour)| {
+                are_adjacent(first_neighbour, second_neighbour)
+            })
+        {
+            return Some((ReductionRule::Triangle, vec![vertex]));
+        }
+
+        let first_neighbour = neighbours
+            .iter()
+            .next()
+            .expect("Vertex should have three neighbours");
+        if let Some(buddy) = adjacency
+            .get(first_neighbour)
+            .expect("Neighbours should be in the adjacency")
+            .iter()
+            .filter(|buddy| **buddy != vertex)
+            .find(|buddy| {
+                adjacency
+                    .get(buddy)
+                    .expect("Neighbours should be in the adjacency")
+                    == neighbours
+            })
+        {
+            return Some((ReductionRule::Buddy, vec![vertex, *buddy]));
+        }
+
+        // The vertex is a, its neighbours are b, c and d. The other neighbours of each pair of
+        // them need to share exactly one vertex and the three shared vertices need to be distinct.
+        let other_neighbours: Vec<(NodeIndex, &HashSet<NodeIndex, S>)> = neighbours
+            .iter()
+            .map(|neighbour| {
+                (
+                    *neighbour,
+                    adjacency
+                        .get(neighbour)
+                        .expect("Neighbours should be in the adjacency"),
+                )
+            })
+            .filter(|(_, neighbours_of_neighbour)| neighbours_of_neighbour.len() == 3)
+            .collect();
+        if other_neighbours.len() == 3 {
+            let shared_vertices: Vec<Vec<NodeIndex>> = other_neighbours
+                .iter()
+                .tuple_combinations()
+                .map(|((_, first_neighbours), (_, second_neighbours))| {
+                    first_neighbours
+                        .intersection(second_neighbours)
+                        .filter(|shared_vertex| **shared_vertex != vertex)
+                        .cloned()
+                        .collect()
+                })
+                .collect();
+            if shared_vertices.iter().all(|shared| shared.len() == 1)
+                && shared_vertices.iter().map(|shared| shared[0]).all_unique()
+            {
+                let mut vertices_to_eliminate: Vec<NodeIndex> = other_neighbours
+                    .iter()
+                    .map(|(neighbour, _)| *neighbour)
+                    .collect();
+                vertices_to_eliminate.push(vertex);
+                return Some((ReductionRule::Cube, vertices_to_eliminate));
+            }
+        }
+    }
+
+    if is_clique(adjacency, neighbours.iter()) {
+        *lower_bound = (*lower_bound).max(degree);
+        return Some((ReductionRule::Simplicial, vec![vertex]));
+    }
+    if degree <= *lower_bound
+        && neighbours.iter().any(|excluded_neighbour| {
+            is_clique(
+                adjacency,
+                neighbours
+                    .iter()
+                    .filter(|neighbour| *neighbour != excluded_neighbour),
+            )
+        })
+    {
+        return Some((ReductionRule::AlmostSimplicial, vec![vertex]));
+    }
+
+    None
+}
+
+/// Returns true if the vertices are pairwise adjacent.
+fn is_clique<'a, S: BuildHasher>(
+    adjacency: &HashMap<NodeIndex, HashSet<NodeIndex, S>, S>,
+    vertices: impl Iterator<Item = &'a NodeIndex> + Clone,
+) -> bool {
+    vertices
+        .tuple_combinations()
+        .all(|(first_vertex, second_vertex)| {
+            adjacency
+                .get(first_vertex)
+                .expect("Vertices should be in the adjacency")
+                .contains(second_vertex)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::RandomState;
+
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::negative_intersection;
+
+    #[test]
+    fn test_partial_k_trees_are_reduced_completely() {
+        // Partial k-trees with k at most 3 have treewidth at most 3 and are reduced to the empty
+        // graph if the lower bound is the treewidth
+        let mut rng = StdRng::seed_from_u64(38);
+        for k in 1..4 {
+            let graph = crate::generate_k_tree(k, 20).expect("k should be smaller than n");
+            let reduced_graph = reduce_graph::<_, _, RandomState>(&graph, 0);
+            assert_eq!(reduced_graph.graph.node_count(), 0);
+            assert_eq!(reduced_graph.lower_bound, k);
+
+            let graph = crate::generate_partial_k_tree(k, 20, 30, &mut rng)
+                .expect("k should be smaller than n");
+            let reduced_graph = reduce_graph::<_, _, RandomState>(&graph, k);
+            assert_eq!(reduced_graph.graph.node_count(), 0);
+
+            let tree_decomposition: Graph<HashSet<NodeIndex, RandomState>, i32, Undirected> =
+                expand_tree_decomposition(&reduced_graph, &Graph::new_undirected());
+            assert!(check_tree_decomposition(
+                &graph,
+                &tree_decomposition,
+                &None,
+                &None
+            ));
+            assert!(find_width_of_tree_decomposition(&tree_decomposition) <= k);
+        }
+    }
+
+    #[test]
+    fn test_cube_rule() {
+        // Cube without one corner, the corners v, w and x are also adjacent to a common vertex y
+        let mut graph: Graph<i32, i32, Undirected> = Graph::new_undirected();
+        let vertices: Vec<NodeIndex> = (0..8).map(|i| graph.add_node(i)).collect();
+        let (a, b, c, d, v, w, x, y) = (0, 1, 2, 3, 4, 5, 6, 7);
+        for (first_vertex, second_vertex) in [
+            (a, b),
+            (a, c),
+            (a, d),
+            (b, w),
+            (b, x),
+            (c, v),
+            (c, x),
+            (d, v),
+            (d, w),
+            (y, v),
+            (y, w),
+            (y, x),
+        ] {
+            graph.add_edge(vertices[first_vertex], vertices[second_vertex], 0);
+        }
+
+        let reduced_graph = reduce_graph::<_, _, RandomState>(&graph, 3);
+        assert_eq!(reduced_graph.undo_log[0].rule, ReductionRule::Cube);
+        assert_eq!(reduced_graph.undo_log[3].vertex, vertices[a]);
+    }
+
+    #[test]
+    fn test_treewidth_heuristic_using_reductions() {
+        for i in 0..3 {
+            let test_graph = crate::tests::setup_test_graph(i);
+            for computation_method in crate::tests::COMPUTATION_METHODS {
+                let (_, computed_treewidth) =
+                    compute_treewidth_upper_bound_using_reductions::<_, _, _, RandomState>(
+                        &test_graph.graph,
+                        negative_intersection,
+                        computation_method,
+                        true,
+                        None,
+                    );
+                assert_eq!(computed_treewidth, test_graph.treewidth);
+            }
+        }
+
+        for (graph, treewidth) in
+            crate::tests::setup_test_graphs_and_partial_k_trees(38, 10, 5, 30, 30)
+        {
+            let (_, computed_treewidth) =
+                compute_treewidth_upper_bound_using_reductions::<_, _, _, RandomState>(
+                    &graph,
+                    negative_intersection,
+                    SpanningTreeConstructionMethod::FillWhilstMST,
+                    true,
+                    None,
+                );
+            assert!(computed_treewidth >= treewidth);
+            // The lower bound raised by the rules is still a lower bound
+            assert!(reduce_graph::<_, _, RandomState>(&graph, 0).lower_bound <= treewidth);
+        }
+    }
+}
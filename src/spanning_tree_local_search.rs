@@ -0,0 +1,681 @@
+use std::{
+    collections::{HashMap, HashSet},
+    hash::BuildHasher,
+};
+
+use petgraph::{graph::NodeIndex, visit::EdgeRef, Graph, Undirected};
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{
+    lexicographic_cost, AnnealingSchedule, BudgetTracker, LocalSearchAcceptance, SearchBudget,
+    TabuList,
+};
+
+/// Spanning tree of a clique graph together with the bags that are obtained by filling up the
+/// cliques along the paths of the tree.
+///
+/// Instead of storing the bags directly, for each vertex of the original graph the set of vertices
+/// of the spanning tree whose bags contain it is stored. This set is the smallest subtree of the
+/// spanning tree containing all cliques with the vertex, which allows updating the bags
+/// incrementally when exchanging an edge of the spanning tree.
+pub(crate) struct FilledSpanningTree<S> {
+    tree_adjacency: HashMap<NodeIndex, HashSet<NodeIndex, S>, S>,
+    // Maps the vertices of the original graph to the vertices of the clique graph containing them
+    clique_graph_map: HashMap<NodeIndex, HashSet<NodeIndex, S>, S>,
+    // Maps the vertices of the original graph to the vertices of the tree whose bags contain them
+    occurrences: HashMap<NodeIndex, HashSet<NodeIndex, S>, S>,
+    bag_sizes: HashMap<NodeIndex, usize, S>,
+}
+
+impl<S: Default + BuildHasher + Clone> FilledSpanningTree<S> {
+    /// Constructs the spanning tree with the given edges on the vertices of the clique graph and
+    /// fills up the bags.
+    pub(crate) fn new<O>(
+        clique_graph: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+        tree_edges: impl IntoIterator<Item = (NodeIndex, NodeIndex)>,
+    ) -> Self {
+        let mut tree_adjacency: HashMap<NodeIndex, HashSet<NodeIndex, S>, S> = Default::default();
+        let mut bag_sizes: HashMap<NodeIndex, usize, S> = Default::default();
+        let mut clique_graph_map: HashMap<NodeIndex, HashSet<NodeIndex, S>, S> = Default::default();
+        for clique_graph_vertex in clique_graph.node_indices() {
+            tree_adjacency.insert(clique_graph_vertex, Default::default());
+            bag_sizes.insert(clique_graph_vertex, 0);
+            for vertex_in_clique in clique_graph
+                .node_weight(clique_graph_vertex)
+                .expect("Vertices in clique graph should have bags as weights")
+            {
+                clique_graph_map
+                    .entry(*vertex_in_clique)
+                    .or_default()
+                    .insert(clique_graph_vertex);
+            }
+        }
+        for (first_vertex, second_vertex) in tree_edges {
+            tree_adjacency
+                .get_mut(&first_vertex)
+                .expect("Tree edges should be between vertices of the clique graph")
+                .insert(second_vertex);
+            tree_adjacency
+                .get_mut(&second_vertex)
+                .expect("Tree edges should be between vertices of the clique graph")
+                .insert(first_vertex);
+        }
+
+        let mut filled_spanning_tree = FilledSpanningTree {
+            tree_adjacency,
+            clique_graph_map,
+            occurrences: Default::default(),
+            bag_sizes,
+        };
+        let vertices_in_original_graph: Vec<NodeIndex> = filled_spanning_tree
+            .clique_graph_map
+            .keys()
+            .cloned()
+            .collect();
+        for vertex_in_original_graph in vertices_in_original_graph {
+            filled_spanning_tree.refill_vertex(vertex_in_original_graph);
+        }
+
+        filled_spanning_tree
+    }
+
+    /// Returns the size of the biggest bag minus one and the sum of the sizes of the bags.
+    pub(crate) fn width_and_sum_of_bag_sizes(&self) -> (usize, usize) {
+        (
+            self.bag_sizes
+                .values()
+                .max()
+                .map_or(0, |bag_size| bag_size.saturating_sub(1)),
+            self.bag_sizes.values().sum(),
+        )
+    }
+
+    /// Returns true if the edge between the two vertices is an edge of the spanning tree.
+    pub(crate) fn contains_edge(&self, first_vertex: NodeIndex, second_vertex: NodeIndex) -> bool {
+        self.tree_adjacency
+            .get(&first_vertex)
+            .is_some_and(|neighbours| neighbours.contains(&second_vertex))
+    }
+
+    /// Returns the (unique) path between the two vertices in the spanning tree including both of
+    /// them.
+    pub(crate) fn tree_path(
+        &self,
+        start_vertex: NodeIndex,
+        end_vertex: NodeIndex,
+    ) -> Vec<NodeIndex> {
+        let predecessors = self.predecessors_from_root(start_vertex);
+        let mut path = vec![end_vertex];
+        let mut current_vertex = end_vertex;
+        while let Some(predecessor) = predecessors.get(&current_vertex) {
+            path.push(*predecessor);
+            current_vertex = *predecessor;
+        }
+        path.reverse();
+        path
+    }
+
+    /// Removes the first edge from the spanning tree and adds the second edge, updating the bags of
+    /// all vertices of the original graph whose subtree used the removed edge.
+    ///
+    /// The added edge has to close a cycle containing the removed edge, otherwise the result is not
+    /// a spanning tree. Returns the previous subtrees of the updated vertices, so the exchange can be
+    /// reverted using [FilledSpanningTree::revert_edge_exchange].
+    pub(crate) fn exchange_edges(
+        &mut self,
+        removed_edge: (NodeIndex, NodeIndex),
+        added_edge: (NodeIndex, NodeIndex),
+    ) -> Vec<(NodeIndex, HashSet<NodeIndex, S>)> {
+        let affected_vertices: Vec<NodeIndex> = self
+            .occurrences
+            .iter()
+            .filter(|(_, subtree)| {
+                subtree.contains(&removed_edge.0) && subtree.contains(&removed_edge.1)
+            })
+            .map(|(vertex_in_original_graph, _)| *vertex_in_original_graph)
+            .collect();
+
+        self.remove_tree_edge(removed_edge);
+        self.add_tree_edge(added_edge);
+
+        affected_vertices
+            .into_iter()
+            .map(|vertex_in_original_graph| {
+                (
+                    vertex_in_original_graph,
+                    self.refill_vertex(vertex_in_original_graph),
+                )
+            })
+            .collect()
+    }
+
+    /// Reverts an exchange done by [FilledSpanningTree::exchange_edges].
+    pub(crate) fn revert_edge_exchange(
+        &mut self,
+        removed_edge: (NodeIndex, NodeIndex),
+        added_edge: (NodeIndex, NodeIndex),
+        previous_subtrees: Vec<(NodeIndex, HashSet<NodeIndex, S>)>,
+    ) {
+        self.remove_tree_edge(added_edge);
+        self.add_tree_edge(removed_edge);
+        for (vertex_in_original_graph, previous_subtree) in previous_subtrees {
+            self.set_subtree(vertex_in_original_graph, previous_subtree);
+        }
+    }
+
+    /// Converts the spanning tree into a tree decomposition with the same vertex indices as the
+    /// clique graph. The edge weights are taken from the clique graph.
+    pub(crate) fn to_tree_decomposition<O: Clone>(
+        &self,
+        clique_graph: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    ) -> Graph<HashSet<NodeIndex, S>, O, Undirected> {
+        let mut tree_decomposition: Graph<HashSet<NodeIndex, S>, O, Undirected> =
+            Graph::new_undirected();
+        for _ in clique_graph.node_indices() {
+            tree_decomposition.add_node(Default::default());
+        }
+        for (vertex_in_original_graph, subtree) in self.occurrences.iter() {
+            for tree_vertex in subtree {
+                tree_decomposition
+                    .node_weight_mut(*tree_vertex)
+                    .expect("Tree vertices should be vertices of the clique graph")
+                    .insert(*vertex_in_original_graph);
+            }
+        }
+        for edge in clique_graph.edge_references() {
+            if self.contains_edge(edge.source(), edge.target()) {
+                tree_decomposition.add_edge(edge.source(), edge.target(), edge.weight().clone());
+            }
+        }
+
+        tree_decomposition
+    }
+
+    /// Recomputes the subtree of the given vertex of the original graph and returns the previous
+    /// one.
+    fn refill_vertex(&mut self, vertex_in_original_graph: NodeIndex) -> HashSet<NodeIndex, S> {
+        let cliques_containing_vertex = self
+            .clique_graph_map
+            .get(&vertex_in_original_graph)
+            .expect("Vertex should be contained in at least one clique");
+        let root = *cliques_containing_vertex
+            .iter()
+            .next()
+            .expect("Vertex should be contained in at least one clique");
+        let predecessors = self.predecessors_from_root(root);
+
+        // The subtree is the union of the paths from the cliques containing the vertex to the root
+        let mut subtree: HashSet<NodeIndex, S> = Default::default();
+        subtree.insert(root);
+        for clique_graph_vertex in cliques_containing_vertex {
+            let mut current_vertex = *clique_graph_vertex;
+            while subtree.insert(current_vertex) {
+                current_vertex = *predecessors
+                    .get(&current_vertex)
+                    .expect("Spanning tree should be connected");
+            }
+        }
+
+        self.set_subtree(vertex_in_original_graph, subtree)
+    }
+
+    /// Sets the subtree of the vertex of the original graph, updating the bag sizes accordingly,
+    /// and returns the previous subtree.
+    fn set_subtree(
+        &mut self,
+        vertex_in_original_graph: NodeIndex,
+        subtree: HashSet<NodeIndex, S>,
+    ) -> HashSet<NodeIndex, S> {
+        for tree_vertex in subtree.iter() {
+            *self
+                .bag_sizes
+                .get_mut(tree_vertex)
+                .expect("Tree vertices should have bag sizes") += 1;
+        }
+        let previous_subtree = self
+            .occurrences
+            .insert(vertex_in_original_graph, subtree)
+            .unwrap_or_default();
+        for tree_vertex in previous_subtree.iter() {
+            *self
+                .bag_sizes
+                .get_mut(tree_vertex)
+                .expect("Tree vertices should have bag sizes") -= 1;
+        }
+
+        previous_subtree
+    }
+
+    /// Returns a map from each vertex of the spanning tree (except the root) to its predecessor
+    /// when rooting the tree at the given root.
+    fn predecessors_from_root(&self, root: NodeIndex) -> HashMap<NodeIndex, NodeIndex, S> {
+        let mut predecessors: HashMap<NodeIndex, NodeIndex, S> = Default::default();
+        let mut stack = vec![root];
+        while let Some(current_vertex) = stack.pop() {
+            for neighbour in self
+                .tree_adjacency
+                .get(&current_vertex)
+                .expect("Tree vertices should have adjacencies")
+            {
+                if *neighbour != root && !predecessors.contains_key(neighbour) {
+                    predecessors.insert(*neighbour, current_vertex);
+                    stack.push(*neighbour);
+                }
+            }
+        }
+        predecessors
+    }
+
+    fn remove_tree_edge(&mut self, (first_vertex, second_vertex): (NodeIndex, NodeIndex)) {
+        for (vertex, other_vertex) in [(first_vertex, second_vertex), (second_vertex, first_vertex)]
+        {
+            self.tree_adjacency
+                .get_mut(&vertex)
+                .expect("Tree vertices should have adjacencies")
+                .remove(&other_vertex);
+        }
+    }
+
+    fn add_tree_edge(&mut self, (first_vertex, second_vertex): (NodeIndex, NodeIndex)) {
+        for (vertex, other_vertex) in [(first_vertex, second_vertex), (second_vertex, first_vertex)]
+        {
+            self.tree_adjacency
+                .get_mut(&vertex)
+                .expect("Tree vertices should have adjacencies")
+                .insert(other_vertex);
+        }
+    }
+}
+
+/// Improves a spanning tree of the given clique graph (e.g. the one returned by
+/// [compute_treewidth_upper_bound][crate::compute_treewidth_upper_bound]) using local search with
+/// edge exchanges.
+///
+/// Starts with the given spanning tree, which is given by a tree decomposition with the same vertex
+/// indices as the clique graph. Every tree decomposition returned by
+/// [compute_treewidth_upper_bound][crate::compute_treewidth_upper_bound] for the clique graph can be
+/// used. Edges of the spanning tree that are not edges of the clique graph are added to it. If no
+/// spanning tree is given, starts with a minimum spanning tree of the clique graph (the same tree that is used by
+/// [SpanningTreeConstructionMethod::MSTAndFill][crate::SpanningTreeConstructionMethod]). In each
+/// move, an edge of the clique graph that is not in the spanning tree is added and an edge on the
+/// cycle that is closed by it is removed. Afterwards the bags are re-filled incrementally, only
+/// updating the vertices of the original graph whose subtree used the removed edge. Spanning trees
+/// are compared by the width of the resulting tree decomposition first and the sum of the sizes of
+/// the bags second. Moves are accepted according to the given acceptance criterion until the
+/// budget is used up.
+///
+/// Returns a tuple with the tree decomposition of the best spanning tree found (with the same vertex
+/// indices as the clique graph) and its width.
+///
+/// Panics: Panics if the clique graph is empty or not connected or if the given spanning tree isn't
+/// a spanning tree of the clique graph
+pub fn improve_spanning_tree_using_local_search<
+    O: Clone + PartialOrd,
+    S: Default + BuildHasher + Clone,
+>(
+    clique_graph: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    initial_spanning_tree: Option<&Graph<HashSet<NodeIndex, S>, O, Undirected>>,
+    local_search_acceptance: LocalSearchAcceptance,
+    search_budget: SearchBudget,
+    rng: &mut impl Rng,
+) -> (Graph<HashSet<NodeIndex, S>, O, Undirected>, usize) {
+    // Edges of the spanning tree that connect cliques which only intersect after filling up the
+    // bags (see FillWhilstMSTEdgeUpdate) are added to the clique graph
+    let mut extended_clique_graph = clique_graph.clone();
+    let initial_tree_edges: Vec<(NodeIndex, NodeIndex)> = match initial_spanning_tree {
+        Some(initial_spanning_tree) => {
+            assert_eq!(
+                initial_spanning_tree.node_count(),
+                clique_graph.node_count(),
+                "Spanning tree should have the same vertices as the clique graph"
+            );
+            initial_spanning_tree
+                .edge_references()
+                .map(|edge| {
+                    if !extended_clique_graph.contains_edge(edge.source(), edge.target()) {
+                        extended_clique_graph.add_edge(
+                            edge.source(),
+                            edge.target(),
+                            edge.weight().clone(),
+                        );
+                    }
+                    (edge.source(), edge.target())
+                })
+                .collect()
+        }
+        None => {
+            let minimum_spanning_tree: Graph<HashSet<NodeIndex, S>, O, Undirected> =
+                petgraph::data::FromElements::from_elements(petgraph::algo::min_spanning_tree(
+                    clique_graph,
+                ));
+            minimum_spanning_tree
+                .edge_references()
+                .map(|edge| (edge.source(), edge.target()))
+                .collect()
+        }
+    };
+    assert_eq!(
+        initial_tree_edges.len() + 1,
+        clique_graph.node_count(),
+        "Clique graph should be connected and not empty"
+    );
+    let clique_graph = &extended_clique_graph;
+    let mut filled_spanning_tree = FilledSpanningTree::new(clique_graph, initial_tree_edges);
+
+    let clique_graph_edges: Vec<(NodeIndex, NodeIndex)> = clique_graph
+        .edge_references()
+        .map(|edge| (edge.source(), edge.target()))
+        .collect();
+    if clique_graph_edges.len() + 1 == clique_graph.node_count() {
+        // Clique graph is a tree, so there is nothing to exchange
+        let (width, _) = filled_spanning_tree.width_and_sum_of_bag_sizes();
+        return (
+            filled_spanning_tree.to_tree_decomposition(clique_graph),
+            width,
+        );
+    }
+
+    let number_of_vertices_in_original_graph = filled_spanning_tree.clique_graph_map.len();
+    let cost = |filled_spanning_tree: &FilledSpanningTree<S>| {
+        let (width, sum_of_bag_sizes) = filled_spanning_tree.width_and_sum_of_bag_sizes();
+        lexicographic_cost(
+            width,
+            sum_of_bag_sizes,
+            clique_graph.node_count() * number_of_vertices_in_original_graph,
+        )
+    };
+
+    let mut current_cost = cost(&filled_spanning_tree);
+    let mut best_cost = current_cost;
+    let mut best_tree_decomposition = filled_spanning_tree.to_tree_decomposition(clique_graph);
+
+    let mut annealing_schedule = AnnealingSchedule::new(local_search_acceptance);
+    // Edges that were exchanged recently, with their endpoints in increasing order
+    let mut tabu_list: TabuList<(NodeIndex, NodeIndex)> = TabuList::new();
+    let mut budget_tracker = BudgetTracker::new(search_budget);
+    let mut iteration = 0;
+
+    while budget_tracker.next_iteration() {
+        iteration += 1;
+        match local_search_acceptance {
+            LocalSearchAcceptance::HillClimbing
+            | LocalSearchAcceptance::SimulatedAnnealing { .. } => {
+                let (removed_edge, added_edge) =
+                    random_edge_exchange(&filled_spanning_tree, &clique_graph_edges, rng);
+                let previous_subtrees =
+                    filled_spanning_tree.exchange_edges(removed_edge, added_edge);
+                let candidate_cost = cost(&filled_spanning_tree);
+
+                if annealing_schedule.accept(candidate_cost - current_cost, rng) {
+                    current_cost = candidate_cost;
+                } else {
+                    filled_spanning_tree.revert_edge_exchange(
+                        removed_edge,
+                        added_edge,
+                        previous_subtrees,
+                    );
+                }
+                annealing_schedule.cool();
+            }
+            LocalSearchAcceptance::Tabu {
+                tabu_tenure,
+                sampled_moves,
+            } => {
+                tabu_list.expire(iteration);
+                let is_tabu = |(first_vertex, second_vertex): (NodeIndex, NodeIndex)| {
+                    tabu_list.contains(&(
+                        first_vertex.min(second_vertex),
+                        first_vertex.max(second_vertex),
+                    ))
+                };
+
+                // Cost, removed edge and added edge of the best move sampled so far
+                let mut best_candidate = None;
+                for _ in 0..sampled_moves.max(1) {
+                    let (removed_edge, added_edge) =
+                        random_edge_exchange(&filled_spanning_tree, &clique_graph_edges, rng);
+                    let previous_subtrees =
+                        filled_spanning_tree.exchange_edges(removed_edge, added_edge);
+                    let candidate_cost = cost(&filled_spanning_tree);
+                    filled_spanning_tree.revert_edge_exchange(
+                        removed_edge,
+                        added_edge,
+                        previous_subtrees,
+                    );
+
+                    // Aspiration criterion: tabu moves are allowed if they improve the best solution
+                    if (!(is_tabu(removed_edge) || is_tabu(added_edge))
+                        || candidate_cost < best_cost)
+                        && best_candidate.as_ref().map_or(
+                            true,
+                            |(best_candidate_cost, _, _): &(f64, _, _)| {
+                                candidate_cost < *best_candidate_cost
+                            },
+                        )
+                    {
+                        best_candidate = Some((candidate_cost, removed_edge, added_edge));
+                    }
+                }
+
+                if let Some((candidate_cost, removed_edge, added_edge)) = best_candidate {
+                    filled_spanning_tree.exchange_edges(removed_edge, added_edge);
+                    current_cost = candidate_cost;
+                    for (first_vertex, second_vertex) in [removed_edge, added_edge] {
+                        tabu_list.insert(
+                            (
+                                first_vertex.min(second_vertex),
+                                first_vertex.max(second_vertex),
+                            ),
+                            iteration + tabu_tenure,
+                        );
+                    }
+                }
+            }
+        }
+
+        if current_cost < best_cost {
+            best_cost = current_cost;
+            best_tree_decomposition = filled_spanning_tree.to_tree_decomposition(clique_graph);
+        }
+    }
+
+    let treewidth = crate::find_width_of_tree_decomposition(&best_tree_decomposition);
+    (best_tree_decomposition, treewidth)
+}
+
+/// Returns a random edge of the spanning tree (first entry) and a random edge of the clique graph
+/// that is not in the spanning tree (second entry), such that exchanging them results in a
+/// spanning tree again.
+///
+/// Panics: Panics if all edges of the clique graph are in the spanning tree
+pub(crate) fn random_edge_exchange<S: Default + BuildHasher + Clone>(
+    filled_spanning_tree: &FilledSpanningTree<S>,
+    clique_graph_edges: &[(NodeIndex, NodeIndex)],
+    rng: &mut impl Rng,
+) -> ((NodeIndex, NodeIndex), (NodeIndex, NodeIndex)) {
+    let added_edge = loop {
+        let edge = *clique_graph_edges
+            .choose(rng)
+            .expect("Clique graph should have edges");
+        if !filled_spanning_tree.contains_edge(edge.0, edge.1) {
+            break edge;
+        }
+    };
+
+    let path = filled_spanning_tree.tree_path(added_edge.0, added_edge.1);
+    let removed_edge_position = rng.gen_range(0..path.len() - 1);
+
+    (
+        (path[removed_edge_position], path[removed_edge_position + 1]),
+        added_edge,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::RandomState;
+
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::check_tree_decomposition;
+
+    #[test]
+    fn test_spanning_tree_local_search_on_test_graphs() {
+        for i in 1..3 {
+            let test_graph = crate::tests::setup_test_graph(i);
+            let (clique_graph, _, _, _, _, treewidth) =
+                crate::compute_treewidth_upper_bound::<_, _, _, RandomState>(
+                    &test_graph.graph,
+                    crate::constant,
+                    crate::SpanningTreeConstructionMethod::MSTAndFill,
+                    false,
+                    None,
+                );
+
+            for local_search_acceptance in crate::tests::LOCAL_SEARCH_ACCEPTANCES {
+                let mut rng = StdRng::seed_from_u64(i as u64);
+                let (tree_decomposition, improved_treewidth) =
+                    improve_spanning_tree_using_local_search(
+                        &clique_graph,
+                        None,
+                        local_search_acceptance,
+                        SearchBudget::Iterations(100),
+                        &mut rng,
+                    );
+
+                assert!(check_tree_decomposition(
+                    &test_graph.graph,
+                    &tree_decomposition,
+                    &None,
+                    &None
+                ));
+                assert!(improved_treewidth <= treewidth);
+                assert_eq!(
+                    improved_treewidth, test_graph.treewidth,
+                    "Test graph {} failed with acceptance {:?}",
+                    i, local_search_acceptance
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_spanning_tree_local_search_starts_at_given_spanning_tree() {
+        let mut rng = StdRng::seed_from_u64(29);
+        let (graph, _) =
+            crate::tests::setup_partial_k_tree_component_with_clique_graph(4, 30, 40, &mut rng);
+        assert!(!crate::is_chordal::<_, _, RandomState>(&graph));
+
+        for computation_method in [
+            crate::SpanningTreeConstructionMethod::FillWhilstMST,
+            crate::SpanningTreeConstructionMethod::FillWhilstMSTEdgeUpdate,
+            crate::SpanningTreeConstructionMethod::FillWhilstMSTTree,
+            crate::SpanningTreeConstructionMethod::FillWhilstMSTBagSize,
+            crate::SpanningTreeConstructionMethod::FillWhilstMSTBeam(3),
+        ] {
+            let (clique_graph, tree_decomposition, _, _, _, treewidth) =
+                crate::compute_treewidth_upper_bound::<_, _, _, RandomState>(
+                    &graph,
+                    crate::negative_intersection,
+                    computation_method,
+                    false,
+                    None,
+                );
+
+            // Without any moves the spanning tree of the method is returned unchanged
+            let (unchanged_tree_decomposition, unchanged_treewidth) =
+                improve_spanning_tree_using_local_search(
+                    &clique_graph,
+                    Some(&tree_decomposition),
+                    LocalSearchAcceptance::HillClimbing,
+                    SearchBudget::Iterations(0),
+                    &mut rng,
+                );
+            assert_eq!(unchanged_treewidth, treewidth);
+            assert!(unchanged_tree_decomposition
+                .node_weights()
+                .eq(tree_decomposition.node_weights()));
+
+            let (improved_tree_decomposition, improved_treewidth) =
+                improve_spanning_tree_using_local_search(
+                    &clique_graph,
+                    Some(&tree_decomposition),
+                    LocalSearchAcceptance::HillClimbing,
+                    SearchBudget::Iterations(100),
+                    &mut rng,
+                );
+            assert!(
+                improved_treewidth <= treewidth,
+                "Local search made the tree of {:?} worse",
+                computation_method
+            );
+            assert!(check_tree_decomposition(
+                &graph,
+                &improved_tree_decomposition,
+                &None,
+                &None
+            ));
+        }
+    }
+
+    #[test]
+    fn test_edge_exchange_matches_filling_from_scratch() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let (graph, clique_graph) =
+            crate::tests::setup_partial_k_tree_component_with_clique_graph(4, 40, 40, &mut rng);
+        let minimum_spanning_tree: Graph<HashSet<NodeIndex, RandomState>, i32, Undirected> =
+            petgraph::data::FromElements::from_elements(petgraph::algo::min_spanning_tree(
+                &clique_graph,
+            ));
+        let mut filled_spanning_tree = FilledSpanningTree::new(
+            &clique_graph,
+            minimum_spanning_tree
+                .edge_references()
+                .map(|edge| (edge.source(), edge.target())),
+        );
+        let clique_graph_edges: Vec<(NodeIndex, NodeIndex)> = clique_graph
+            .edge_references()
+            .map(|edge| (edge.source(), edge.target()))
+            .collect();
+        assert!(
+            clique_graph_edges.len() + 1 > clique_graph.node_count(),
+            "Clique graph should contain an edge that is not in the spanning tree"
+        );
+
+        // The spanning tree is first compared before any edge is exchanged, so this also checks that
+        // filling up a spanning tree from scratch results in the same bags
+        for iteration in 0..=20 {
+            if iteration > 0 {
+                let (removed_edge, added_edge) =
+                    random_edge_exchange(&filled_spanning_tree, &clique_graph_edges, &mut rng);
+                filled_spanning_tree.exchange_edges(removed_edge, added_edge);
+            }
+
+            let incrementally_filled_tree_decomposition =
+                filled_spanning_tree.to_tree_decomposition(&clique_graph);
+            let mut tree_decomposition_filled_from_scratch =
+                incrementally_filled_tree_decomposition.map(
+                    |vertex, _| {
+                        clique_graph
+                            .node_weight(vertex)
+                            .expect("Vertices should have bags as weights")
+                            .clone()
+                    },
+                    |_, weight| *weight,
+                );
+            crate::fill_bags_along_paths(&mut tree_decomposition_filled_from_scratch, None)
+                .expect("There is no width bound that could be exceeded");
+
+            assert!(incrementally_filled_tree_decomposition
+                .node_weights()
+                .eq(tree_decomposition_filled_from_scratch.node_weights()));
+            assert!(check_tree_decomposition(
+                &graph,
+                &incrementally_filled_tree_decomposition,
+                &None,
+                &None
+            ));
+        }
+    }
+}
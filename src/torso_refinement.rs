@@ -0,0 +1,361 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Debug,
+    hash::BuildHasher,
+};
+
+use petgraph::{graph::NodeIndex, Graph, Undirected};
+
+use crate::{
+    compute_treewidth_upper_bound_using_clique_separators, find_induced_subgraph,
+    find_width_of_tree_decomposition, tree_decomposition_from_elimination_ordering,
+    SpanningTreeConstructionMethod,
+};
+
+/// Maximum number of vertices of a graph whose treewidth can be computed by
+/// [find_optimal_elimination_ordering].
+const MAXIMUM_NUMBER_OF_VERTICES_FOR_EXACT_SOLVER: usize = 24;
+
+/// Refines a tree decomposition of the graph by repeatedly replacing its biggest bag with a tree
+/// decomposition of the torso of the bag.
+///
+/// The torso of a bag consists of the vertices of the bag, the edges of the graph between them and
+/// cliques on the intersections with the neighbouring bags. Any tree decomposition of the torso
+/// contains each of these intersections in one of its bags, so the neighbouring bags can be
+/// attached there, which results in a tree decomposition of the graph again.
+///
+/// Torsos with at most exact_torso_size vertices (but at most 24) are decomposed optimally (see
+/// [find_optimal_elimination_ordering]), bigger ones using the clique graph heuristic with the
+/// given spanning tree construction method. The weights of the new edges are given by the edge
+/// weight function.
+///
+/// This is repeated as long as the biggest bag can be replaced by smaller bags. Returns the width of
+/// the refined tree decomposition.
+pub fn refine_tree_decomposition_using_torsos<
+    N,
+    E: Clone,
+    O: Clone + Ord + Default + Debug,
+    S: Default + BuildHasher + Clone,
+>(
+    graph: &Graph<N, E, Undirected>,
+    tree_decomposition: &mut Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    treewidth_computation_method: SpanningTreeConstructionMethod,
+    exact_torso_size: usize,
+) -> usize {
+    while let Some(biggest_bag) = tree_decomposition.node_indices().max_by_key(|bag| {
+        tree_decomposition
+            .node_weight(*bag)
+            .expect("Bags should exist in the tree decomposition")
+            .len()
+    }) {
+        let bag = tree_decomposition
+            .node_weight(biggest_bag)
+            .expect("Bags should exist in the tree decomposition")
+            .clone();
+        let separators: Vec<(NodeIndex, HashSet<NodeIndex, S>)> = tree_decomposition
+            .neighbors(biggest_bag)
+            .map(|neighbour| {
+                (
+                    neighbour,
+                    bag.intersection(
+                        tree_decomposition
+                            .node_weight(neighbour)
+                            .expect("Bags should exist in the tree decomposition"),
+                    )
+                    .cloned()
+                    .collect(),
+                )
+            })
+            .collect();
+        let torso = construct_torso::<N, E, S>(
+            graph,
+            &bag,
+            separators.iter().map(|(_, separator)| separator),
+        );
+
+        let torso_tree_decomposition: Graph<HashSet<NodeIndex, S>, O, Undirected> = if torso
+            .node_count()
+            <= exact_torso_size.min(MAXIMUM_NUMBER_OF_VERTICES_FOR_EXACT_SOLVER)
+        {
+            tree_decomposition_from_elimination_ordering(
+                &torso,
+                &find_optimal_elimination_ordering(&torso),
+            )
+        } else {
+            compute_treewidth_upper_bound_using_clique_separators(
+                &torso,
+                edge_weight_function,
+                treewidth_computation_method,
+                false,
+                None,
+            )
+            .0
+        };
+        if find_width_of_tree_decomposition(&torso_tree_decomposition) + 1 >= bag.len() {
+            break;
+        }
+
+        // Splice the tree decomposition of the torso into the tree decomposition
+        let mut node_index_map: HashMap<NodeIndex, NodeIndex, S> = Default::default();
+        for torso_bag in torso_tree_decomposition.node_indices() {
+            let new_bag: HashSet<NodeIndex, S> = torso_tree_decomposition
+                .node_weight(torso_bag)
+                .expect("Bags should exist in the tree decomposition")
+                .iter()
+                .map(|vertex| {
+                    *torso
+                        .node_weight(*vertex)
+                        .expect("Vertices of the torso should map to the graph")
+                })
+                .collect();
+            node_index_map.insert(torso_bag, tree_decomposition.add_node(new_bag));
+        }
+        for edge in torso_tree_decomposition.edge_indices() {
+            let (first_bag, second_bag) = torso_tree_decomposition
+                .edge_endpoints(edge)
+                .expect("Edge should exist in the tree decomposition");
+            add_edge_with_weight(
+                tree_decomposition,
+                node_index_map[&first_bag],
+                node_index_map[&second_bag],
+                edge_weight_function,
+            );
+        }
+        for (neighbour, separator) in separators {
+            let new_bag = node_index_map
+                .values()
+                .find(|new_bag| {
+                    separator.is_subset(
+                        tree_decomposition
+                            .node_weight(**new_bag)
+                            .expect("Bags should exist in the tree decomposition"),
+                    )
+                })
+                .expect("Separators are cliques in the torso and should be contained in a bag");
+            add_edge_with_weight(
+                tree_decomposition,
+                neighbour,
+                *new_bag,
+                edge_weight_function,
+            );
+        }
+        tree_decomposition.remove_node(biggest_bag);
+    }
+
+    find_width_of_tree_decomposition(tree_decomposition)
+}
+
+/// Computes an elimination ordering of the graph whose induced width is the treewidth of the graph.
+///
+/// Uses the dynamic program over subsets of vertices by Bodlaender et al.: The treewidth of the
+/// graph restricted to eliminating the vertices of a set S first is the minimum over the vertices
+/// v of S of the maximum of the value for S without v and the number of vertices outside of S that
+/// can be reached from v using only vertices of S. This takes exponential time.
+///
+/// Panics: Panics if the graph has more than 24 vertices.
+pub fn find_optimal_elimination_ordering<N, E>(graph: &Graph<N, E, Undirected>) -> Vec<NodeIndex> {
+    let number_of_vertices = graph.node_count();
+    assert!(
+        number_of_vertices <= MAXIMUM_NUMBER_OF_VERTICES_FOR_EXACT_SOLVER,
+        "Graph should have at most {} vertices",
+        MAXIMUM_NUMBER_OF_VERTICES_FOR_EXACT_SOLVER
+    );
+    let vertices: Vec<NodeIndex> = graph.node_indices().collect();
+    let neighbourhoods: Vec<u32> = vertices
+        .iter()
+        .map(|vertex| {
+            graph
+                .neighbors(*vertex)
+                .filter(|neighbour| neighbour != vertex)
+                .fold(0, |neighbourhood, neighbour| {
+                    neighbourhood | 1 << neighbour.index()
+                })
+        })
+        .collect();
+
+    // For each set of vertices eliminated first the smallest width and the last eliminated vertex
+    let mut widths: Vec<u8> = vec![0; 1 << number_of_vertices];
+    let mut last_eliminated_vertices: Vec<u8> = vec![0; 1 << number_of_vertices];
+    for set in 1..(1_u32 << number_of_vertices) {
+        let mut best_width = u8::MAX;
+        for vertex in (0..number_of_vertices).filter(|vertex| set & 1 << vertex != 0) {
+            let previous_set = set & !(1 << vertex);
+            let width = widths[previous_set as usize].max(number_of_vertices_reachable_through(
+                &neighbourhoods,
+                vertex,
+                previous_set,
+            ) as u8);
+            if width < best_width {
+                best_width = width;
+                last_eliminated_vertices[set as usize] = vertex as u8;
+            }
+        }
+        widths[set as usize] = best_width;
+    }
+
+    let mut elimination_ordering = Vec::with_capacity(number_of_vertices);
+    let mut set: u32 = (1_u32 << number_of_vertices) - 1;
+    while set != 0 {
+        let vertex = last_eliminated_vertices[set as usize];
+        elimination_ordering.push(vertices[vertex as usize]);
+        set &= !(1 << vertex);
+    }
+    elimination_ordering.reverse();
+    elimination_ordering
+}
+
+/// Returns the number of vertices outside of the set (and other than the vertex) that can be reached
+/// from the vertex using only vertices of the set.
+fn number_of_vertices_reachable_through(neighbourhoods: &[u32], vertex: usize, set: u32) -> u32 {
+    let mut reached_in_set: u32 = 0;
+    let mut reached_outside_set: u32 = neighbourhoods[vertex] & !set;
+    let mut frontier: u32 = neighbourhoods[vertex] & set;
+    while frontier != 0 {
+        reached_in_set |= frontier;
+        let next_vertex = frontier.trailing_zeros() as usize;
+        frontier &= !(1 << next_vertex);
+        reached_outside_set |= neighbourhoods[next_vertex] & !set;
+        frontier |= neighbourhoods[next_vertex] & set & !reached_in_set;
+    }
+    (reached_outside_set & !(1 << vertex)).count_ones()
+}
+
+/// Constructs the torso of the bag, i.e. the subgraph induced by the bag with cliques on the
+/// separators. The vertices of the torso are weighted by the vertices of the graph.
+fn construct_torso<'a, N, E: Clone, S: Default + BuildHasher + 'a>(
+    graph: &Graph<N, E, Undirected>,
+    bag: &HashSet<NodeIndex, S>,
+    separators: impl Iterator<Item = &'a HashSet<NodeIndex, S>>,
+) -> Graph<NodeIndex, (), Undirected> {
+    let mut torso: Graph<NodeIndex, (), Undirected> =
+        find_induced_subgraph(graph, bag).map(|_, vertex| *vertex, |_, _| ());
+    let node_index_map: HashMap<NodeIndex, NodeIndex, S> = torso
+        .node_indices()
+        .map(|torso_vertex| {
+            (
+                *torso
+                    .node_weight(torso_vertex)
+                    .expect("Vertices of the torso should map to the graph"),
+                torso_vertex,
+            )
+        })
+        .collect();
+
+    for separator in separators {
+        for first_vertex in separator.iter() {
+            for second_vertex in separator.iter().filter(|vertex| first_vertex < *vertex) {
+                torso.update_edge(
+                    node_index_map[first_vertex],
+                    node_index_map[second_vertex],
+                    (),
+                );
+            }
+        }
+    }
+
+    torso
+}
+
+/// Adds an edge between the bags weighted by the edge weight function.
+fn add_edge_with_weight<O, S>(
+    tree_decomposition: &mut Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    first_bag: NodeIndex,
+    second_bag: NodeIndex,
+    edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+) {
+    let weight = edge_weight_function(
+        tree_decomposition
+            .node_weight(first_bag)
+            .expect("Bags should exist in the tree decomposition"),
+        tree_decomposition
+            .node_weight(second_bag)
+            .expect("Bags should exist in the tree decomposition"),
+    );
+    tree_decomposition.add_edge(first_bag, second_bag, weight);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::RandomState;
+
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::{check_tree_decomposition, negative_intersection};
+
+    #[test]
+    fn test_find_optimal_elimination_ordering() {
+        for i in 0..3 {
+            let test_graph = crate::tests::setup_test_graph(i);
+            let elimination_ordering = find_optimal_elimination_ordering(&test_graph.graph);
+            assert_eq!(
+                crate::find_induced_width_of_elimination_ordering::<_, _, RandomState>(
+                    &test_graph.graph,
+                    &elimination_ordering
+                ),
+                test_graph.treewidth
+            );
+        }
+    }
+
+    #[test]
+    fn test_refining_a_single_bag_gives_optimal_tree_decomposition() {
+        let mut rng = StdRng::seed_from_u64(41);
+        for _ in 0..5 {
+            let graph =
+                crate::generate_partial_k_tree_with_guaranteed_treewidth(3, 12, 30, &mut rng)
+                    .expect("k should be smaller than n");
+            let mut tree_decomposition: Graph<HashSet<NodeIndex, RandomState>, i32, Undirected> =
+                Graph::new_undirected();
+            tree_decomposition.add_node(graph.node_indices().collect());
+
+            let width = refine_tree_decomposition_using_torsos(
+                &graph,
+                &mut tree_decomposition,
+                negative_intersection,
+                SpanningTreeConstructionMethod::FillWhilstMST,
+                12,
+            );
+            assert_eq!(width, 3);
+            assert!(check_tree_decomposition(
+                &graph,
+                &tree_decomposition,
+                &None,
+                &None
+            ));
+        }
+    }
+
+    #[test]
+    fn test_refinement_keeps_tree_decomposition_valid() {
+        for (graph, treewidth) in
+            crate::tests::setup_test_graphs_and_partial_k_trees(41, 5, 4, 30, 50)
+        {
+            let (_, mut tree_decomposition, _, _, _, computed_treewidth) =
+                crate::compute_treewidth_upper_bound::<_, _, _, RandomState>(
+                    &graph,
+                    negative_intersection,
+                    SpanningTreeConstructionMethod::MSTAndFill,
+                    false,
+                    None,
+                );
+
+            let width = refine_tree_decomposition_using_torsos(
+                &graph,
+                &mut tree_decomposition,
+                negative_intersection,
+                SpanningTreeConstructionMethod::FillWhilstMST,
+                10,
+            );
+            assert!(width <= computed_treewidth);
+            assert!(width >= treewidth);
+            assert!(check_tree_decomposition(
+                &graph,
+                &tree_decomposition,
+                &None,
+                &None
+            ));
+        }
+    }
+}
@@ -0,0 +1,244 @@
+use std::{collections::HashSet, hash::BuildHasher};
+
+use petgraph::{graph::NodeIndex, Graph, Undirected};
+
+use crate::triangulation::find_fill_edges;
+
+/// Objectives that are used to break ties between tree decompositions of the same width.
+///
+/// SumOfBagSizes The sum of the sizes of the bags
+///
+/// NumberOfBags The number of bags
+///
+/// SumOfExponentialBagSizes The sum of 2^|bag| over all bags, i.e. the total size of the tables of
+/// a dynamic program with two states per vertex
+///
+/// FillEdges The number of edges that need to be added to the graph so that every bag is a clique
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SecondaryObjective {
+    SumOfBagSizes,
+    NumberOfBags,
+    SumOfExponentialBagSizes,
+    FillEdges,
+}
+
+/// Metrics of a tree decomposition of a graph as returned by
+/// [find_metrics_of_tree_decomposition].
+///
+/// width The size of the biggest bag minus one
+///
+/// sum_of_bag_sizes The sum of the sizes of the bags
+///
+/// number_of_bags The number of bags
+///
+/// sum_of_exponential_bag_sizes The sum of 2^|bag| over all bags, saturating at u128::MAX
+///
+/// fill_edges The number of edges that need to be added to the graph so that every bag is a clique
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct TreeDecompositionMetrics {
+    pub width: usize,
+    pub sum_of_bag_sizes: usize,
+    pub number_of_bags: usize,
+    pub sum_of_exponential_bag_sizes: u128,
+    pub fill_edges: usize,
+}
+
+impl TreeDecompositionMetrics {
+    /// Returns the value of the secondary objective.
+    pub fn value_of(&self, secondary_objective: SecondaryObjective) -> u128 {
+        match secondary_objective {
+            SecondaryObjective::SumOfBagSizes => self.sum_of_bag_sizes as u128,
+            SecondaryObjective::NumberOfBags => self.number_of_bags as u128,
+            SecondaryObjective::SumOfExponentialBagSizes => self.sum_of_exponential_bag_sizes,
+            SecondaryObjective::FillEdges => self.fill_edges as u128,
+        }
+    }
+}
+
+/// Lexicographic objective for tree decompositions: Tree decompositions are compared by their
+/// width first and ties are broken by the secondary objectives in the given order. The default
+/// objective only compares the width.
+///
+/// tie_breakers The secondary objectives in the order they are compared in
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct TreeDecompositionObjective {
+    pub tie_breakers: Vec<SecondaryObjective>,
+}
+
+impl TreeDecompositionObjective {
+    /// Returns the objective that compares the width first and then the tie breakers in the given
+    /// order.
+    pub fn width_then(tie_breakers: impl IntoIterator<Item = SecondaryObjective>) -> Self {
+        TreeDecompositionObjective {
+            tie_breakers: tie_breakers.into_iter().collect(),
+        }
+    }
+
+    /// Returns the key of the metrics according to the objective, i.e. the width followed by the
+    /// values of the tie breakers. Smaller keys are better.
+    pub fn key_of(&self, metrics: &TreeDecompositionMetrics) -> Vec<u128> {
+        std::iter::once(metrics.width as u128)
+            .chain(
+                self.tie_breakers
+                    .iter()
+                    .map(|tie_breaker| metrics.value_of(*tie_breaker)),
+            )
+            .collect()
+    }
+
+    /// Returns the key of the tree decomposition of the graph according to the objective (see
+    /// [TreeDecompositionObjective::key_of]). The fill edges are only counted if they are one of the
+    /// tie breakers.
+    pub fn evaluate<N, E, O, S: Default + BuildHasher>(
+        &self,
+        graph: &Graph<N, E, Undirected>,
+        tree_decomposition: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    ) -> Vec<u128> {
+        let mut metrics = find_metrics_without_fill_edges(tree_decomposition);
+        if self.tie_breakers.contains(&SecondaryObjective::FillEdges) {
+            metrics.fill_edges = find_fill_edges::<N, E, O, S>(graph, tree_decomposition).len();
+        }
+        self.key_of(&metrics)
+    }
+}
+
+/// Computes all metrics of the tree decomposition of the graph (see [TreeDecompositionMetrics]).
+pub fn find_metrics_of_tree_decomposition<N, E, O, S: Default + BuildHasher>(
+    graph: &Graph<N, E, Undirected>,
+    tree_decomposition: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+) -> TreeDecompositionMetrics {
+    TreeDecompositionMetrics {
+        fill_edges: find_fill_edges::<N, E, O, S>(graph, tree_decomposition).len(),
+        ..find_metrics_without_fill_edges(tree_decomposition)
+    }
+}
+
+/// Computes the metrics of the tree decomposition that don't depend on the graph and sets the
+/// number of fill edges to 0.
+fn find_metrics_without_fill_edges<O, S>(
+    tree_decomposition: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+) -> TreeDecompositionMetrics {
+    let mut metrics = TreeDecompositionMetrics {
+        number_of_bags: tree_decomposition.node_count(),
+        ..Default::default()
+    };
+    for bag in tree_decomposition.node_weights() {
+        metrics.width = metrics.width.max(bag.len().saturating_sub(1));
+        metrics.sum_of_bag_sizes += bag.len();
+        let exponential_bag_size = if bag.len() < 128 {
+            1u128 << bag.len()
+        } else {
+            u128::MAX
+        };
+        metrics.sum_of_exponential_bag_sizes = metrics
+            .sum_of_exponential_bag_sizes
+            .saturating_add(exponential_bag_size);
+    }
+    metrics
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::RandomState;
+
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn test_metrics_of_cycle_decomposition() {
+        // Cycle a - b - c - d - a with the tree decomposition {a, b, c} - {a, c, d}
+        let mut graph: Graph<i32, i32, Undirected> = Graph::new_undirected();
+        let a = graph.add_node(0);
+        let b = graph.add_node(1);
+        let c = graph.add_node(2);
+        let d = graph.add_node(3);
+        graph.extend_with_edges([(a, b), (b, c), (c, d), (d, a)]);
+
+        let mut tree_decomposition: Graph<HashSet<NodeIndex, RandomState>, i32, Undirected> =
+            Graph::new_undirected();
+        let first_bag = tree_decomposition.add_node([a, b, c].into_iter().collect());
+        let second_bag = tree_decomposition.add_node([a, c, d].into_iter().collect());
+        tree_decomposition.add_edge(first_bag, second_bag, 0);
+
+        let metrics = find_metrics_of_tree_decomposition(&graph, &tree_decomposition);
+        assert_eq!(
+            metrics,
+            TreeDecompositionMetrics {
+                width: 2,
+                sum_of_bag_sizes: 6,
+                number_of_bags: 2,
+                sum_of_exponential_bag_sizes: 16,
+                fill_edges: 1,
+            }
+        );
+
+        let objective = TreeDecompositionObjective::width_then([
+            SecondaryObjective::FillEdges,
+            SecondaryObjective::NumberOfBags,
+        ]);
+        assert_eq!(
+            objective.evaluate(&graph, &tree_decomposition),
+            vec![2, 1, 2]
+        );
+        assert_eq!(
+            TreeDecompositionObjective::default().evaluate(&graph, &tree_decomposition),
+            vec![2]
+        );
+    }
+
+    #[test]
+    fn test_heuristics_with_objective() {
+        let objective = TreeDecompositionObjective::width_then([
+            SecondaryObjective::SumOfExponentialBagSizes,
+            SecondaryObjective::FillEdges,
+        ]);
+        let mut rng = StdRng::seed_from_u64(48);
+        for (graph, treewidth) in
+            crate::tests::setup_test_graphs_and_partial_k_trees(48, 2, 3, 20, 40)
+        {
+            for computation_method in crate::tests::COMPUTATION_METHODS {
+                let (result, metrics) =
+                    crate::compute_treewidth_upper_bound_with_objective::<_, _, _, RandomState>(
+                        &graph,
+                        crate::negative_intersection,
+                        computation_method,
+                        &objective,
+                        true,
+                        None,
+                    );
+                assert_eq!(metrics.width, result.5);
+                assert!(metrics.width >= treewidth);
+                assert_eq!(
+                    metrics,
+                    find_metrics_of_tree_decomposition(&graph, &result.1)
+                );
+            }
+
+            let (best_tree_decomposition, metrics) =
+                crate::compute_treewidth_upper_bound_multi_start_with_objective::<
+                    _,
+                    _,
+                    _,
+                    RandomState,
+                >(
+                    &graph,
+                    crate::negative_intersection,
+                    crate::SpanningTreeConstructionMethod::FillWhilstMSTBagSize,
+                    crate::MultiStartConfiguration {
+                        restricted_candidate_list: crate::RestrictedCandidateList::TopK(3),
+                        root: None,
+                        number_of_restarts: 10,
+                    },
+                    &objective,
+                    true,
+                    None,
+                    &mut rng,
+                );
+            let best_key = objective.evaluate(&graph, &best_tree_decomposition);
+            assert!(metrics
+                .iter()
+                .all(|metrics| best_key <= objective.key_of(metrics)));
+        }
+    }
+}
@@ -0,0 +1,314 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Debug,
+    hash::BuildHasher,
+};
+
+use petgraph::{graph::NodeIndex, Directed, Direction, Graph, Undirected};
+
+use crate::{
+    compute_tree_decomposition_of_components, find_connected_components_of_induced_subgraph,
+    SpanningTreeConstructionMethod,
+};
+
+/// Computes an elimination forest of the graph using the tree decomposition, i.e. a rooted forest
+/// on the vertices of the graph such that for every edge one endpoint is an ancestor of the other.
+/// The vertices of the forest are weighted by the vertices of the graph and its edges are directed
+/// from the parent to the child.
+///
+/// Uses recursive balanced separators taken from the bags: For each connected component of the
+/// remaining graph, the bags containing its vertices form a subtree of the tree decomposition. The
+/// vertices of the component are assigned to one of their bags each and the weighted centroid bag of
+/// that subtree is chosen, so removing its vertices leaves components with at most half of the
+/// vertices. These vertices form a path in the forest and the elimination forests of the remaining
+/// components are attached to its end. The depth of the forest is therefore at most
+/// (w + 1)(log_2(n) + 1) for a tree decomposition of width w.
+///
+/// Panics: Panics if the tree decomposition isn't a tree decomposition of the graph.
+pub fn find_elimination_forest<N, E, O, S: Default + BuildHasher + Clone>(
+    graph: &Graph<N, E, Undirected>,
+    tree_decomposition: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+) -> Graph<NodeIndex, (), Directed> {
+    let mut bags_of_vertices: HashMap<NodeIndex, Vec<NodeIndex>, S> = Default::default();
+    for bag in tree_decomposition.node_indices() {
+        for vertex in tree_decomposition
+            .node_weight(bag)
+            .expect("Bags should exist in the tree decomposition")
+        {
+            bags_of_vertices.entry(*vertex).or_default().push(bag);
+        }
+    }
+
+    let mut elimination_forest: Graph<NodeIndex, (), Directed> = Graph::new();
+    let all_vertices: HashSet<NodeIndex, S> = graph.node_indices().collect();
+    let mut stack: Vec<(Vec<NodeIndex>, Option<NodeIndex>)> =
+        find_connected_components_of_induced_subgraph::<N, E, S>(graph, &all_vertices)
+            .into_iter()
+            .map(|component| (component, None))
+            .collect();
+
+    while let Some((component, parent)) = stack.pop() {
+        let separator =
+            find_centroid_bag::<O, S>(tree_decomposition, &bags_of_vertices, &component);
+        let mut separator_vertices: Vec<NodeIndex> = component
+            .iter()
+            .filter(|vertex| separator.contains(vertex))
+            .cloned()
+            .collect();
+        separator_vertices.sort();
+
+        let mut parent = parent;
+        for vertex in separator_vertices {
+            let forest_vertex = elimination_forest.add_node(vertex);
+            if let Some(parent) = parent {
+                elimination_forest.add_edge(parent, forest_vertex, ());
+            }
+            parent = Some(forest_vertex);
+        }
+
+        let remaining_vertices: HashSet<NodeIndex, S> = component
+            .into_iter()
+            .filter(|vertex| !separator.contains(vertex))
+            .collect();
+        stack.extend(
+            find_connected_components_of_induced_subgraph::<N, E, S>(graph, &remaining_vertices)
+                .into_iter()
+                .map(|remaining_component| (remaining_component, parent)),
+        );
+    }
+
+    elimination_forest
+}
+
+/// Returns the depth of the elimination forest, i.e. the maximum number of vertices on a path from
+/// a root to a leaf. This is an upper bound for the treedepth of the graph.
+pub fn find_depth_of_elimination_forest(
+    elimination_forest: &Graph<NodeIndex, (), Directed>,
+) -> usize {
+    let mut depth = 0;
+    let mut stack: Vec<(NodeIndex, usize)> = elimination_forest
+        .externals(Direction::Incoming)
+        .map(|root| (root, 1))
+        .collect();
+    while let Some((vertex, vertex_depth)) = stack.pop() {
+        depth = depth.max(vertex_depth);
+        stack.extend(
+            elimination_forest
+                .neighbors(vertex)
+                .map(|child| (child, vertex_depth + 1)),
+        );
+    }
+    depth
+}
+
+/// Computes an upper bound for the treedepth by computing a tree decomposition of each component
+/// using [compute_treewidth_upper_bound][crate::compute_treewidth_upper_bound] and deriving an
+/// elimination forest from them (see [find_elimination_forest]). The graph doesn't need to be
+/// connected.
+///
+/// Returns the elimination forest and its depth.
+///
+/// Panics: If check_tree_decomposition_bool is true, panics if a tree decomposition or the
+/// elimination forest is incorrect.
+pub fn compute_treedepth_upper_bound<
+    N: Clone,
+    E: Clone,
+    O: Clone + Ord + Default + Debug,
+    S: Default + BuildHasher + Clone,
+>(
+    graph: &Graph<N, E, Undirected>,
+    edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    treewidth_computation_method: SpanningTreeConstructionMethod,
+    check_tree_decomposition_bool: bool,
+    clique_bound: Option<usize>,
+) -> (Graph<NodeIndex, (), Directed>, usize) {
+    let tree_decomposition = compute_tree_decomposition_of_components(
+        graph,
+        edge_weight_function,
+        treewidth_computation_method,
+        check_tree_decomposition_bool,
+        clique_bound,
+    );
+    let elimination_forest = find_elimination_forest(graph, &tree_decomposition);
+
+    if check_tree_decomposition_bool {
+        assert!(
+            is_elimination_forest(graph, &elimination_forest),
+            "Elimination forest is invalid"
+        );
+    }
+    let treedepth = find_depth_of_elimination_forest(&elimination_forest);
+
+    (elimination_forest, treedepth)
+}
+
+/// Returns true if the forest contains every vertex of the graph exactly once and for every edge of
+/// the graph one endpoint is an ancestor of the other.
+fn is_elimination_forest<N, E>(
+    graph: &Graph<N, E, Undirected>,
+    elimination_forest: &Graph<NodeIndex, (), Directed>,
+) -> bool {
+    let mut forest_vertices: HashMap<NodeIndex, NodeIndex> = HashMap::new();
+    for forest_vertex in elimination_forest.node_indices() {
+        if forest_vertices
+            .insert(elimination_forest[forest_vertex], forest_vertex)
+            .is_some()
+        {
+            return false;
+        }
+    }
+    if forest_vertices.len() != graph.node_count() {
+        return false;
+    }
+
+    let is_ancestor = |ancestor: NodeIndex, vertex: NodeIndex| {
+        let mut current_vertex = Some(forest_vertices[&vertex]);
+        while let Some(forest_vertex) = current_vertex {
+            if elimination_forest[forest_vertex] == ancestor {
+                return true;
+            }
+            current_vertex = elimination_forest
+                .neighbors_directed(forest_vertex, Direction::Incoming)
+                .next();
+        }
+        false
+    };
+    graph.edge_indices().all(|edge| {
+        let (first_vertex, second_vertex) = graph
+            .edge_endpoints(edge)
+            .expect("Edge should exist in the graph");
+        is_ancestor(first_vertex, second_vertex) || is_ancestor(second_vertex, first_vertex)
+    })
+}
+
+/// Returns the bag whose removal splits the subtree of bags containing vertices of the component
+/// into parts with at most half of the vertices of the component each. Each vertex is counted for
+/// the first of its bags.
+fn find_centroid_bag<'a, O, S: Default + BuildHasher>(
+    tree_decomposition: &'a Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    bags_of_vertices: &HashMap<NodeIndex, Vec<NodeIndex>, S>,
+    component: &[NodeIndex],
+) -> &'a HashSet<NodeIndex, S> {
+    let mut weights: HashMap<NodeIndex, usize, S> = Default::default();
+    for vertex in component {
+        let bags = bags_of_vertices
+            .get(vertex)
+            .expect("Every vertex should be contained in a bag");
+        for bag in bags {
+            weights.entry(*bag).or_insert(0);
+        }
+        *weights.entry(bags[0]).or_insert(0) += 1;
+    }
+
+    // Subtree weights of the bags when rooted at some bag
+    let root = *bags_of_vertices[&component[0]]
+        .first()
+        .expect("Every vertex should be contained in a bag");
+    let mut pre_order: Vec<(NodeIndex, Option<NodeIndex>)> = Vec::new();
+    let mut stack: Vec<(NodeIndex, Option<NodeIndex>)> = vec![(root, None)];
+    while let Some((bag, parent)) = stack.pop() {
+        pre_order.push((bag, parent));
+        stack.extend(
+            tree_decomposition
+                .neighbors(bag)
+                .filter(|neighbour| Some(*neighbour) != parent && weights.contains_key(neighbour))
+                .map(|neighbour| (neighbour, Some(bag))),
+        );
+    }
+    let mut subtree_weights: HashMap<NodeIndex, usize, S> = Default::default();
+    for (bag, parent) in pre_order.iter().rev() {
+        let subtree_weight = subtree_weights.get(bag).cloned().unwrap_or(0) + weights[bag];
+        subtree_weights.insert(*bag, subtree_weight);
+        if let Some(parent) = parent {
+            *subtree_weights.entry(*parent).or_insert(0) += subtree_weight;
+        }
+    }
+
+    // Walk into the heaviest child as long as it has more than half of the weight
+    let total_weight = component.len();
+    let mut centroid = root;
+    let mut parent: Option<NodeIndex> = None;
+    while let Some(heavy_child) = tree_decomposition
+        .neighbors(centroid)
+        .filter(|neighbour| Some(*neighbour) != parent && weights.contains_key(neighbour))
+        .find(|child| 2 * subtree_weights[child] > total_weight)
+    {
+        parent = Some(centroid);
+        centroid = heavy_child;
+    }
+
+    tree_decomposition
+        .node_weight(centroid)
+        .expect("Bags should exist in the tree decomposition")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::RandomState;
+
+    use super::*;
+    use crate::{find_width_of_tree_decomposition, negative_intersection};
+
+    #[test]
+    fn test_elimination_forest_of_path() {
+        // A path with 2^k - 1 vertices has treedepth k
+        let mut graph: Graph<i32, i32, Undirected> = Graph::new_undirected();
+        let vertices: Vec<NodeIndex> = (0..15).map(|i| graph.add_node(i)).collect();
+        for i in 1..15 {
+            graph.add_edge(vertices[i - 1], vertices[i], 0);
+        }
+
+        let (elimination_forest, treedepth) = compute_treedepth_upper_bound::<_, _, _, RandomState>(
+            &graph,
+            negative_intersection,
+            SpanningTreeConstructionMethod::MSTAndFill,
+            true,
+            None,
+        );
+        assert_eq!(elimination_forest.node_count(), 15);
+        assert!(treedepth >= 4);
+        assert!(treedepth <= 2 * 4);
+    }
+
+    #[test]
+    fn test_elimination_forests_are_valid() {
+        let mut graphs = crate::tests::setup_test_graphs_and_partial_k_trees(45, 5, 3, 40, 40);
+        // Test graph 0 is not connected
+        let test_graph = crate::tests::setup_test_graph(0);
+        graphs.push((test_graph.graph, test_graph.treewidth));
+
+        for (graph, treewidth) in graphs {
+            for computation_method in crate::tests::COMPUTATION_METHODS {
+                let (elimination_forest, depth) =
+                    compute_treedepth_upper_bound::<_, _, _, RandomState>(
+                        &graph,
+                        negative_intersection,
+                        computation_method,
+                        true,
+                        None,
+                    );
+                assert!(is_elimination_forest(&graph, &elimination_forest));
+                // The treedepth is bigger than the treewidth
+                assert!(depth > treewidth);
+
+                // The depth of the elimination forest derived from a tree decomposition of width w
+                // is at most (w + 1)(log_2(n) + 1)
+                let tree_decomposition =
+                    compute_tree_decomposition_of_components::<_, _, _, RandomState>(
+                        &graph,
+                        negative_intersection,
+                        computation_method,
+                        true,
+                        None,
+                    );
+                let treewidth = find_width_of_tree_decomposition(&tree_decomposition);
+                let elimination_forest = find_elimination_forest(&graph, &tree_decomposition);
+                assert!(is_elimination_forest(&graph, &elimination_forest));
+                assert!(
+                    find_depth_of_elimination_forest(&elimination_forest)
+                        <= (treewidth + 1) * (graph.node_count().ilog2() as usize + 1)
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use std::{collections::HashSet, hash::BuildHasher};
+
+use itertools::Itertools;
+use petgraph::{graph::NodeIndex, Graph, Undirected};
+
+use crate::{find_maximal_cliques_of_chordal_graph, find_perfect_elimination_ordering};
+
+/// Triangulation of a graph induced by a tree decomposition as returned by
+/// [find_triangulation_of_tree_decomposition].
+///
+/// triangulation The graph with an additional edge between every pair of non-adjacent vertices that
+/// are contained in a common bag. The vertices have the same indices as in the graph
+///
+/// fill_edges The edges that were added to the graph, each given by its endpoints in increasing
+/// order. The edges are sorted
+#[derive(Clone, Debug)]
+pub struct Triangulation<N, E> {
+    pub triangulation: Graph<N, E, Undirected>,
+    pub fill_edges: Vec<(NodeIndex, NodeIndex)>,
+}
+
+impl<N, E> Triangulation<N, E> {
+    /// Returns the number of fill edges.
+    pub fn fill_count(&self) -> usize {
+        self.fill_edges.len()
+    }
+}
+
+/// Returns the triangulation of the graph induced by the tree decomposition, i.e. the graph
+/// together with all edges between vertices that are contained in a common bag. This is a chordal
+/// supergraph of the graph whose maximal cliques are contained in the bags. The fill edges get the
+/// default edge weight.
+pub fn find_triangulation_of_tree_decomposition<
+    N: Clone,
+    E: Clone + Default,
+    O,
+    S: Default + BuildHasher,
+>(
+    graph: &Graph<N, E, Undirected>,
+    tree_decomposition: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+) -> Triangulation<N, E> {
+    let fill_edges = find_fill_edges::<N, E, O, S>(graph, tree_decomposition);
+    let mut triangulation = graph.clone();
+    for (first_vertex, second_vertex) in fill_edges.iter() {
+        triangulation.add_edge(*first_vertex, *second_vertex, E::default());
+    }
+
+    Triangulation {
+        triangulation,
+        fill_edges,
+    }
+}
+
+/// Returns true if the triangulation is chordal and its clique number (the size of its biggest
+/// clique) is width + 1. This holds for the triangulation induced by a tree decomposition of the
+/// given width since every clique of the triangulation is contained in a bag.
+pub fn check_triangulation<N, E, S: Default + BuildHasher>(
+    triangulation: &Triangulation<N, E>,
+    width: usize,
+) -> bool {
+    let Some(perfect_elimination_ordering) =
+        find_perfect_elimination_ordering::<N, E, S>(&triangulation.triangulation)
+    else {
+        println!("Triangulation is not chordal");
+        return false;
+    };
+    let clique_number = find_maximal_cliques_of_chordal_graph::<N, E, S>(
+        &triangulation.triangulation,
+        &perfect_elimination_ordering,
+    )
+    .iter()
+    .map(|clique| clique.len())
+    .max()
+    .unwrap_or(0);
+
+    if clique_number != width + 1 {
+        println!(
+            "Clique number {} of the triangulation doesn't match the width {}",
+            clique_number, width
+        );
+        return false;
+    }
+    true
+}
+
+/// Returns the pairs of non-adjacent vertices of the graph that are contained in a common bag of the
+/// tree decomposition, each given by its endpoints in increasing order. The pairs are sorted.
+pub(crate) fn find_fill_edges<N, E, O, S: Default + BuildHasher>(
+    graph: &Graph<N, E, Undirected>,
+    tree_decomposition: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+) -> Vec<(NodeIndex, NodeIndex)> {
+    let mut fill_edges: HashSet<(NodeIndex, NodeIndex), S> = Default::default();
+    for bag in tree_decomposition.node_weights() {
+        for (first_vertex, second_vertex) in bag.iter().tuple_combinations() {
+            if !graph.contains_edge(*first_vertex, *second_vertex) {
+                fill_edges.insert((
+                    *first_vertex.min(second_vertex),
+                    *first_vertex.max(second_vertex),
+                ));
+            }
+        }
+    }
+    let mut fill_edges: Vec<(NodeIndex, NodeIndex)> = fill_edges.into_iter().collect();
+    fill_edges.sort();
+    fill_edges
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::RandomState;
+
+    use super::*;
+    use crate::{
+        compute_tree_decomposition_of_components, find_width_of_tree_decomposition, is_chordal,
+        negative_intersection,
+    };
+
+    #[test]
+    fn test_triangulations_of_tree_decompositions() {
+        let mut graphs = crate::tests::setup_test_graphs_and_partial_k_trees(49, 3, 4, 30, 60);
+        // Test graph 0 is not connected, so its tree decomposition is a forest
+        let test_graph = crate::tests::setup_test_graph(0);
+        graphs.push((test_graph.graph, test_graph.treewidth));
+
+        for (graph, treewidth) in graphs {
+            let graph_is_chordal = is_chordal::<_, _, RandomState>(&graph);
+            for computation_method in crate::tests::COMPUTATION_METHODS {
+                let tree_decomposition =
+                    compute_tree_decomposition_of_components::<_, _, _, RandomState>(
+                        &graph,
+                        negative_intersection,
+                        computation_method,
+                        false,
+                        None,
+                    );
+                let width = find_width_of_tree_decomposition(&tree_decomposition);
+                let triangulation =
+                    find_triangulation_of_tree_decomposition(&graph, &tree_decomposition);
+
+                assert!(width >= treewidth);
+                assert!(check_triangulation::<_, _, RandomState>(
+                    &triangulation,
+                    width
+                ));
+                assert_eq!(
+                    triangulation.triangulation.edge_count(),
+                    graph.edge_count() + triangulation.fill_count()
+                );
+                for (first_vertex, second_vertex) in triangulation.fill_edges.iter() {
+                    assert!(!graph.contains_edge(*first_vertex, *second_vertex));
+                }
+                // Chordal graphs get a clique tree, every other graph needs fill edges to become
+                // chordal
+                assert_eq!(triangulation.fill_count() == 0, graph_is_chordal);
+            }
+        }
+    }
+
+    #[test]
+    fn test_triangulation_of_cycle() {
+        // Cycle a - b - c - d - a with the tree decomposition {a, b, c} - {a, c, d} needs the fill
+        // edge a - c
+        let mut graph: Graph<i32, i32, Undirected> = Graph::new_undirected();
+        let a = graph.add_node(0);
+        let b = graph.add_node(1);
+        let c = graph.add_node(2);
+        let d = graph.add_node(3);
+        graph.extend_with_edges([(a, b), (b, c), (c, d), (d, a)]);
+
+        let mut tree_decomposition: Graph<HashSet<NodeIndex, RandomState>, i32, Undirected> =
+            Graph::new_undirected();
+        let first_bag = tree_decomposition.add_node([a, b, c].into_iter().collect());
+        let second_bag = tree_decomposition.add_node([a, c, d].into_iter().collect());
+        tree_decomposition.add_edge(first_bag, second_bag, 0);
+
+        let triangulation = find_triangulation_of_tree_decomposition(&graph, &tree_decomposition);
+        assert_eq!(triangulation.fill_edges, vec![(a, c)]);
+        assert!(check_triangulation::<_, _, RandomState>(&triangulation, 2));
+        assert!(!check_triangulation::<_, _, RandomState>(&triangulation, 3));
+        assert!(!check_triangulation::<_, _, RandomState>(
+            &Triangulation {
+                triangulation: graph,
+                fill_edges: Vec::new(),
+            },
+            2
+        ));
+    }
+}
@@ -0,0 +1,299 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Debug,
+    hash::BuildHasher,
+};
+
+use itertools::Itertools;
+use petgraph::{graph::NodeIndex, Graph, Undirected};
+
+use crate::{
+    check_tree_decomposition,
+    compute_treewidth_upper_bound::{
+        compute_treewidth_upper_bound_with_optional_width_bound, BagSizeObjective,
+    },
+    find_width_of_tree_decomposition, SpanningTreeConstructionMethod, VertexWeightedObjective,
+    VertexWeights,
+};
+
+/// Returns the classes of true twins of the graph, i.e. the classes of vertices with identical
+/// closed neighbourhoods. Each class is sorted and the classes are sorted by their first vertex.
+pub fn find_true_twin_classes<N, E, S: Default + BuildHasher>(
+    graph: &Graph<N, E, Undirected>,
+) -> Vec<Vec<NodeIndex>> {
+    let mut classes: HashMap<Vec<NodeIndex>, Vec<NodeIndex>, S> = Default::default();
+    for vertex in graph.node_indices() {
+        let closed_neighbourhood: Vec<NodeIndex> = graph
+            .neighbors(vertex)
+            .chain(std::iter::once(vertex))
+            .sorted()
+            .dedup()
+            .collect();
+        classes
+            .entry(closed_neighbourhood)
+            .or_default()
+            .push(vertex);
+    }
+
+    classes
+        .into_values()
+        .map(|class| class.into_iter().sorted().collect_vec())
+        .sorted()
+        .collect()
+}
+
+/// Computes an upper bound for the treewidth by collapsing each class of true twins (see
+/// [find_true_twin_classes]) to a single vertex and running the heuristic on the resulting quotient
+/// graph. The vertices of the quotient graph are weighted by their classes.
+///
+/// The heuristic takes the sizes of the classes into account: The edge weight function is
+/// evaluated on the expanded bags (i.e. the bags in which each vertex is replaced by its class) and
+/// FillWhilstMSTBagSize minimizes the size of the biggest expanded bag, using the vertex weighted
+/// path (see [compute_vertex_weighted_treewidth_upper_bound][crate::compute_vertex_weighted_treewidth_upper_bound])
+/// with a domain size of 2^|class| for each vertex of the quotient graph.
+///
+/// Afterwards each vertex in a bag is replaced by its class, which keeps the properties of a tree
+/// decomposition since true twins are adjacent to each other and have the same neighbours. The
+/// width is computed from the expanded bags and the edge weights are recomputed from them using
+/// the edge weight function.
+///
+/// Returns the tree decomposition of the graph and its width.
+///
+/// Panics: If check_tree_decomposition_bool is true, panics if the resulting tree decomposition is
+/// incorrect.
+pub fn compute_treewidth_upper_bound_using_twin_compression<
+    N: Clone,
+    E: Clone,
+    O: Clone + Ord + Default + Debug,
+    S: Default + BuildHasher + Clone,
+>(
+    graph: &Graph<N, E, Undirected>,
+    edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    treewidth_computation_method: SpanningTreeConstructionMethod,
+    check_tree_decomposition_bool: bool,
+    clique_bound: Option<usize>,
+) -> (Graph<HashSet<NodeIndex, S>, O, Undirected>, usize) {
+    let quotient_graph = construct_quotient_graph::<N, E, S>(graph);
+    let expand_bag = |bag: &HashSet<NodeIndex, S>| -> HashSet<NodeIndex, S> {
+        bag.iter()
+            .flat_map(|representative| {
+                quotient_graph
+                    .node_weight(*representative)
+                    .expect("Vertices of the quotient graph should have classes as weights")
+                    .iter()
+                    .cloned()
+            })
+            .collect()
+    };
+    // The weighted size of a bag of the quotient graph is the size of the expanded bag
+    let class_sizes =
+        VertexWeights::from_function(&quotient_graph, |_, class| 2f64.powi(class.len() as i32));
+
+    let ((_, quotient_tree_decomposition, _, _, _, _), _) =
+        compute_treewidth_upper_bound_with_optional_width_bound(
+            &quotient_graph,
+            |first_bag, second_bag| {
+                edge_weight_function(&expand_bag(first_bag), &expand_bag(second_bag))
+            },
+            treewidth_computation_method,
+            false,
+            clique_bound,
+            None,
+            Some(BagSizeObjective::VertexWeighted(
+                &class_sizes,
+                VertexWeightedObjective::WeightedWidth,
+            )),
+        )
+        .expect("There is no width bound that could be exceeded");
+
+    let expanded_bags: Graph<HashSet<NodeIndex, S>, (), Undirected> =
+        quotient_tree_decomposition.map(|_, bag| expand_bag(bag), |_, _| ());
+    let tree_decomposition = expanded_bags.map(
+        |_, bag| bag.clone(),
+        |edge, _| {
+            let (first_bag, second_bag) = expanded_bags
+                .edge_endpoints(edge)
+                .expect("Edge should exist in the tree decomposition");
+            edge_weight_function(
+                expanded_bags
+                    .node_weight(first_bag)
+                    .expect("Bags should exist in the tree decomposition"),
+                expanded_bags
+                    .node_weight(second_bag)
+                    .expect("Bags should exist in the tree decomposition"),
+            )
+        },
+    );
+
+    if check_tree_decomposition_bool {
+        assert!(
+            check_tree_decomposition(graph, &tree_decomposition, &None, &None),
+            "Tree decomposition is invalid. See previous print statements for reason."
+        );
+    }
+    let treewidth = find_width_of_tree_decomposition(&tree_decomposition);
+
+    (tree_decomposition, treewidth)
+}
+
+/// Constructs the quotient graph of the graph with respect to the classes of true twins. The
+/// vertices are weighted by their classes and two of them are adjacent iff the vertices of their
+/// classes are adjacent.
+fn construct_quotient_graph<N, E, S: Default + BuildHasher>(
+    graph: &Graph<N, E, Undirected>,
+) -> Graph<Vec<NodeIndex>, (), Undirected> {
+    let mut quotient_graph: Graph<Vec<NodeIndex>, (), Undirected> = Graph::new_undirected();
+    let mut representatives: HashMap<NodeIndex, NodeIndex, S> = Default::default();
+    for class in find_true_twin_classes::<N, E, S>(graph) {
+        let representative = quotient_graph.add_node(class.clone());
+        for vertex in class {
+            representatives.insert(vertex, representative);
+        }
+    }
+
+    for edge in graph.edge_indices() {
+        let (first_vertex, second_vertex) = graph
+            .edge_endpoints(edge)
+            .expect("Edge should exist in the graph");
+        let first_representative = representatives[&first_vertex];
+        let second_representative = representatives[&second_vertex];
+        if first_representative != second_representative {
+            quotient_graph.update_edge(first_representative, second_representative, ());
+        }
+    }
+
+    quotient_graph
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::RandomState;
+
+    use super::*;
+    use crate::negative_intersection;
+
+    /// Returns a path of length 3 whose vertices are replaced by cliques of size 3 (each vertex is
+    /// adjacent to all vertices of its own and the neighbouring cliques).
+    fn setup_graph_with_twins() -> Graph<i32, i32, Undirected> {
+        let mut graph: Graph<i32, i32, Undirected> = Graph::new_undirected();
+        let classes: Vec<Vec<NodeIndex>> = (0..3)
+            .map(|_| (0..3).map(|i| graph.add_node(i)).collect())
+            .collect();
+        for (first_class, second_class) in [(0, 0), (1, 1), (2, 2), (0, 1), (1, 2)] {
+            for first_vertex in classes[first_class].iter() {
+                for second_vertex in classes[second_class].iter() {
+                    if first_vertex < second_vertex || first_class != second_class {
+                        graph.add_edge(*first_vertex, *second_vertex, 0);
+                    }
+                }
+            }
+        }
+        graph
+    }
+
+    /// Returns the cycle a - b - c - d - a in which a and c are replaced by cliques of size 5, so
+    /// only the chord b - d leads to a tree decomposition of width 6.
+    fn setup_cycle_with_big_twin_classes() -> Graph<i32, i32, Undirected> {
+        let mut graph: Graph<i32, i32, Undirected> = Graph::new_undirected();
+        let classes: Vec<Vec<NodeIndex>> = [5, 1, 5, 1]
+            .into_iter()
+            .map(|class_size| (0..class_size).map(|i| graph.add_node(i)).collect())
+            .collect();
+        for (first_class, second_class) in [(0, 0), (2, 2), (0, 1), (1, 2), (2, 3), (3, 0)] {
+            for first_vertex in classes[first_class].iter() {
+                for second_vertex in classes[second_class].iter() {
+                    if first_vertex < second_vertex || first_class != second_class {
+                        graph.add_edge(*first_vertex, *second_vertex, 0);
+                    }
+                }
+            }
+        }
+        graph
+    }
+
+    #[test]
+    fn test_twin_compression_takes_class_sizes_into_account() {
+        let graph = setup_cycle_with_big_twin_classes();
+        let first_big_class: HashSet<NodeIndex, RandomState> = (0..5).map(NodeIndex::new).collect();
+        let second_big_class: HashSet<NodeIndex, RandomState> =
+            (6..11).map(NodeIndex::new).collect();
+
+        // On the quotient graph (a cycle of length 4) both chords look the same, but the chord
+        // a - c leads to bags containing both big classes
+        for computation_method in [
+            SpanningTreeConstructionMethod::FillWhilstMST,
+            SpanningTreeConstructionMethod::FillWhilstMSTEdgeUpdate,
+            SpanningTreeConstructionMethod::FillWhilstMSTTree,
+            SpanningTreeConstructionMethod::FillWhilstMSTBagSize,
+            SpanningTreeConstructionMethod::MSTAndFill,
+            SpanningTreeConstructionMethod::MSTAndUseTreeStructure,
+        ] {
+            let (tree_decomposition, computed_treewidth) =
+                compute_treewidth_upper_bound_using_twin_compression::<_, _, _, RandomState>(
+                    &graph,
+                    negative_intersection,
+                    computation_method,
+                    true,
+                    None,
+                );
+            assert_eq!(
+                computed_treewidth, 6,
+                "Computation method {:?} failed",
+                computation_method
+            );
+            assert!(
+                tree_decomposition
+                    .node_weights()
+                    .all(|bag| bag.is_disjoint(&first_big_class)
+                        || bag.is_disjoint(&second_big_class))
+            );
+        }
+    }
+
+    #[test]
+    fn test_find_true_twin_classes() {
+        let graph = setup_graph_with_twins();
+        let classes: Vec<Vec<usize>> = find_true_twin_classes::<_, _, RandomState>(&graph)
+            .into_iter()
+            .map(|class| class.into_iter().map(|vertex| vertex.index()).collect())
+            .collect();
+        assert_eq!(classes, vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 7, 8]]);
+
+        let test_graph = crate::tests::setup_test_graph(0);
+        assert_eq!(
+            find_true_twin_classes::<_, _, RandomState>(&test_graph.graph).len(),
+            6
+        );
+    }
+
+    #[test]
+    fn test_treewidth_heuristic_using_twin_compression() {
+        let graph = setup_graph_with_twins();
+        for computation_method in crate::tests::COMPUTATION_METHODS {
+            let (_, computed_treewidth) =
+                compute_treewidth_upper_bound_using_twin_compression::<_, _, _, RandomState>(
+                    &graph,
+                    negative_intersection,
+                    computation_method,
+                    true,
+                    None,
+                );
+            assert_eq!(computed_treewidth, 5);
+        }
+
+        for i in 1..3 {
+            let test_graph = crate::tests::setup_test_graph(i);
+            for computation_method in crate::tests::COMPUTATION_METHODS {
+                let (_, computed_treewidth) =
+                    compute_treewidth_upper_bound_using_twin_compression::<_, _, _, RandomState>(
+                        &test_graph.graph,
+                        negative_intersection,
+                        computation_method,
+                        true,
+                        None,
+                    );
+                assert!(computed_treewidth >= test_graph.treewidth);
+            }
+        }
+    }
+}
@@ -0,0 +1,550 @@
+use std::{collections::HashSet, fmt::Debug, hash::BuildHasher, path::Path, str::FromStr};
+
+use itertools::Itertools;
+use petgraph::{graph::NodeIndex, Graph, Undirected};
+
+use crate::{
+    check_tree_decomposition, compute_tree_decomposition_of_components,
+    SpanningTreeConstructionMethod,
+};
+
+/// The type of a probabilistic graphical model in the UAI format.
+///
+/// Markov A Markov network whose factors are arbitrary functions of their scope
+///
+/// Bayes A Bayesian network whose factors are conditional probability tables. The last variable of
+/// a scope is the child, the others are its parents
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UaiNetworkType {
+    Markov,
+    Bayes,
+}
+
+/// A factor of a probabilistic graphical model.
+///
+/// scope The variables of the factor in the order of the UAI file
+///
+/// table The values of the factor, the last variable of the scope changing fastest
+#[derive(Clone, Debug, PartialEq)]
+pub struct Factor {
+    pub scope: Vec<usize>,
+    pub table: Vec<f64>,
+}
+
+/// A probabilistic graphical model as read by [parse_uai] or [read_uai_file].
+///
+/// network_type Whether the model is a Markov or a Bayesian network
+///
+/// domain_sizes The number of values of each variable
+///
+/// factors The factors of the model in the order of the UAI file
+#[derive(Clone, Debug, PartialEq)]
+pub struct UaiModel {
+    pub network_type: UaiNetworkType,
+    pub domain_sizes: Vec<usize>,
+    pub factors: Vec<Factor>,
+}
+
+/// Error returned if a UAI file can't be read.
+///
+/// Io The file couldn't be read
+///
+/// UnexpectedEndOfFile The file ended before the model was complete
+///
+/// InvalidToken The token couldn't be parsed as the expected value
+///
+/// InvalidModel The file could be parsed but doesn't describe a valid model
+#[derive(Debug)]
+pub enum UaiError {
+    Io(std::io::Error),
+    UnexpectedEndOfFile,
+    InvalidToken(String),
+    InvalidModel(String),
+}
+
+impl std::fmt::Display for UaiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UaiError::Io(error) => write!(f, "couldn't read UAI file: {}", error),
+            UaiError::UnexpectedEndOfFile => write!(f, "unexpected end of UAI file"),
+            UaiError::InvalidToken(token) => write!(f, "invalid token {:?} in UAI file", token),
+            UaiError::InvalidModel(message) => write!(f, "invalid UAI model: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for UaiError {}
+
+impl From<std::io::Error> for UaiError {
+    fn from(error: std::io::Error) -> Self {
+        UaiError::Io(error)
+    }
+}
+
+impl UaiModel {
+    /// Returns the primal graph of the model whose vertices are the variables (vertex i being
+    /// variable i, weighted by its domain size) and that contains an edge between two variables if
+    /// they occur in the scope of a common factor. For Bayesian networks this is the moral graph,
+    /// since the scope of each conditional probability table contains the child and all its
+    /// parents.
+    pub fn primal_graph(&self) -> Graph<usize, (), Undirected> {
+        let mut graph: Graph<usize, (), Undirected> = Graph::new_undirected();
+        for domain_size in self.domain_sizes.iter() {
+            graph.add_node(*domain_size);
+        }
+        for factor in self.factors.iter() {
+            for (first_variable, second_variable) in
+                factor.scope.iter().sorted().dedup().tuple_combinations()
+            {
+                let first_vertex = NodeIndex::new(*first_variable);
+                let second_vertex = NodeIndex::new(*second_variable);
+                if !graph.contains_edge(first_vertex, second_vertex) {
+                    graph.add_edge(first_vertex, second_vertex, ());
+                }
+            }
+        }
+        graph
+    }
+}
+
+/// Parses a model in the
+/// [UAI format](https://uaicompetition.github.io/uci-2022/file-formats/model-format/): The network
+/// type (MARKOV or BAYES), the number of variables, their domain sizes, the number of
+/// factors, the scope of each factor (its size followed by its variables) and the table of each
+/// factor (its number of entries followed by the entries).
+pub fn parse_uai(input: &str) -> Result<UaiModel, UaiError> {
+    let mut tokens = input.split_whitespace();
+
+    let network_type = match next_token(&mut tokens)? {
+        "MARKOV" => UaiNetworkType::Markov,
+        "BAYES" => UaiNetworkType::Bayes,
+        token => return Err(UaiError::InvalidToken(token.to_string())),
+    };
+
+    let number_of_variables: usize = parse_next_token(&mut tokens)?;
+    let domain_sizes: Vec<usize> = (0..number_of_variables)
+        .map(|_| parse_next_token(&mut tokens))
+        .collect::<Result<_, _>>()?;
+    if domain_sizes.contains(&0) {
+        return Err(UaiError::InvalidModel(
+            "domain sizes should be positive".to_string(),
+        ));
+    }
+
+    let number_of_factors: usize = parse_next_token(&mut tokens)?;
+    let mut scopes: Vec<Vec<usize>> = Vec::with_capacity(number_of_factors);
+    for factor in 0..number_of_factors {
+        let scope_size: usize = parse_next_token(&mut tokens)?;
+        let scope: Vec<usize> = (0..scope_size)
+            .map(|_| parse_next_token(&mut tokens))
+            .collect::<Result<_, _>>()?;
+        if let Some(variable) = scope
+            .iter()
+            .find(|variable| **variable >= number_of_variables)
+        {
+            return Err(UaiError::InvalidModel(format!(
+                "variable {} of factor {} doesn't exist",
+                variable, factor
+            )));
+        }
+        if network_type == UaiNetworkType::Bayes && scope.is_empty() {
+            return Err(UaiError::InvalidModel(format!(
+                "factor {} of a Bayesian network should contain its child",
+                factor
+            )));
+        }
+        scopes.push(scope);
+    }
+
+    let mut factors = Vec::with_capacity(number_of_factors);
+    for (factor, scope) in scopes.into_iter().enumerate() {
+        let number_of_entries: usize = parse_next_token(&mut tokens)?;
+        let expected_number_of_entries: usize = scope
+            .iter()
+            .map(|variable| domain_sizes[*variable])
+            .product();
+        if number_of_entries != expected_number_of_entries {
+            return Err(UaiError::InvalidModel(format!(
+                "factor {} should have {} entries but has {}",
+                factor, expected_number_of_entries, number_of_entries
+            )));
+        }
+        let table: Vec<f64> = (0..number_of_entries)
+            .map(|_| parse_next_token(&mut tokens))
+            .collect::<Result<_, _>>()?;
+        factors.push(Factor { scope, table });
+    }
+
+    Ok(UaiModel {
+        network_type,
+        domain_sizes,
+        factors,
+    })
+}
+
+/// Reads a model in the UAI format from the file (see [parse_uai]).
+pub fn read_uai_file(path: impl AsRef<Path>) -> Result<UaiModel, UaiError> {
+    parse_uai(&std::fs::read_to_string(path)?)
+}
+
+/// A junction tree of a probabilistic graphical model as returned by [construct_junction_tree].
+///
+/// tree The tree whose vertices are the bags (sets of variables, variable i being NodeIndex i) and
+/// whose edges are weighted by the separators, i.e. the intersections of the bags at their
+/// endpoints. It satisfies the running intersection property: For every variable the bags
+/// containing it form a subtree
+///
+/// factor_assignment For each factor (in the order of the model) the bag it is assigned to, which
+/// contains the scope of the factor
+#[derive(Clone, Debug)]
+pub struct JunctionTree<S> {
+    pub tree: Graph<HashSet<NodeIndex, S>, HashSet<NodeIndex, S>, Undirected>,
+    pub factor_assignment: Vec<NodeIndex>,
+}
+
+impl<S: BuildHasher> JunctionTree<S> {
+    /// Writes the junction tree in the td format of the
+    /// [PACE challenge](https://pacechallenge.org/2017/treewidth/) with the primal graph of the
+    /// model having number_of_variables vertices. As in that format, bags and variables are
+    /// numbered starting at 1. The factor assignment is written as comment lines
+    /// "c factor <factor> <bag>" directly after the solution line, the factors being numbered in
+    /// the order of the model starting at 0.
+    pub fn write_td(
+        &self,
+        number_of_variables: usize,
+        writer: &mut impl std::io::Write,
+    ) -> std::io::Result<()> {
+        let maximum_bag_size = self
+            .tree
+            .node_weights()
+            .map(|bag| bag.len())
+            .max()
+            .unwrap_or(0);
+        writeln!(
+            writer,
+            "s td {} {} {}",
+            self.tree.node_count(),
+            maximum_bag_size,
+            number_of_variables
+        )?;
+        for (factor, bag) in self.factor_assignment.iter().enumerate() {
+            writeln!(writer, "c factor {} {}", factor, bag.index() + 1)?;
+        }
+        for bag in self.tree.node_indices() {
+            write!(writer, "b {}", bag.index() + 1)?;
+            for variable in self.tree[bag].iter().sorted() {
+                write!(writer, " {}", variable.index() + 1)?;
+            }
+            writeln!(writer)?;
+        }
+        for edge in self.tree.edge_indices() {
+            let (first_bag, second_bag) = self
+                .tree
+                .edge_endpoints(edge)
+                .expect("Edge should exist in the junction tree");
+            writeln!(
+                writer,
+                "{} {}",
+                first_bag.index() + 1,
+                second_bag.index() + 1
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Constructs a junction tree of the model by computing a tree decomposition of each component of
+/// its primal graph using [compute_treewidth_upper_bound][crate::compute_treewidth_upper_bound],
+/// connecting the tree decompositions by edges with empty separators and assigning each factor to
+/// the smallest bag containing its scope.
+///
+/// Panics: If check_tree_decomposition_bool is true, panics if the junction tree doesn't satisfy
+/// the running intersection property or doesn't cover the primal graph.
+pub fn construct_junction_tree<
+    O: Clone + Ord + Default + Debug,
+    S: Default + BuildHasher + Clone,
+>(
+    model: &UaiModel,
+    edge_weight_function: fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>) -> O,
+    treewidth_computation_method: SpanningTreeConstructionMethod,
+    check_tree_decomposition_bool: bool,
+    clique_bound: Option<usize>,
+) -> JunctionTree<S> {
+    let primal_graph = model.primal_graph();
+    let tree_decomposition = compute_tree_decomposition_of_components(
+        &primal_graph,
+        edge_weight_function,
+        treewidth_computation_method,
+        check_tree_decomposition_bool,
+        clique_bound,
+    );
+
+    let mut tree = tree_decomposition.map(
+        |_, bag| bag.clone(),
+        |edge, _| {
+            let (first_bag, second_bag) = tree_decomposition
+                .edge_endpoints(edge)
+                .expect("Edge should exist in the tree decomposition");
+            tree_decomposition[first_bag]
+                .intersection(&tree_decomposition[second_bag])
+                .cloned()
+                .collect()
+        },
+    );
+    // Factors without variables need a bag
+    if tree.node_count() == 0 && !model.factors.is_empty() {
+        tree.add_node(Default::default());
+    }
+    // Connect the tree decompositions of the components
+    let mut visited: HashSet<NodeIndex, S> = Default::default();
+    let mut previous_root: Option<NodeIndex> = None;
+    for root in tree.node_indices() {
+        if visited.contains(&root) {
+            continue;
+        }
+        let mut stack = vec![root];
+        visited.insert(root);
+        while let Some(bag) = stack.pop() {
+            for neighbour in tree.neighbors(bag) {
+                if visited.insert(neighbour) {
+                    stack.push(neighbour);
+                }
+            }
+        }
+        if let Some(previous_root) = previous_root {
+            tree.add_edge(previous_root, root, Default::default());
+        }
+        previous_root = Some(root);
+    }
+
+    if check_tree_decomposition_bool {
+        assert!(
+            check_tree_decomposition(&primal_graph, &tree, &None, &None),
+            "Junction tree is invalid. See previous print statements for reason."
+        );
+    }
+
+    let factor_assignment = model
+        .factors
+        .iter()
+        .map(|factor| {
+            tree.node_indices()
+                .filter(|bag| {
+                    factor
+                        .scope
+                        .iter()
+                        .all(|variable| tree[*bag].contains(&NodeIndex::new(*variable)))
+                })
+                .min_by_key(|bag| tree[*bag].len())
+                .expect("The scope of a factor is a clique, so a bag should contain it")
+        })
+        .collect();
+
+    JunctionTree {
+        tree,
+        factor_assignment,
+    }
+}
+
+/// Returns the next token or an error if there is none.
+fn next_token<'a>(tokens: &mut impl Iterator<Item = &'a str>) -> Result<&'a str, UaiError> {
+    tokens.next().ok_or(UaiError::UnexpectedEndOfFile)
+}
+
+/// Parses the next token as a value of type T.
+fn parse_next_token<'a, T: FromStr>(
+    tokens: &mut impl Iterator<Item = &'a str>,
+) -> Result<T, UaiError> {
+    let token = next_token(tokens)?;
+    token
+        .parse()
+        .map_err(|_| UaiError::InvalidToken(token.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::RandomState;
+
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+    use crate::negative_intersection;
+
+    /// Bayesian network with the variables a, b, c and d where c depends on a and b and d on c
+    const BAYESIAN_NETWORK: &str = "BAYES
+4
+2 2 3 2
+4
+1 0
+1 1
+3 0 1 2
+2 2 3
+
+2
+ 0.4 0.6
+2
+ 0.5 0.5
+12
+ 0.1 0.2 0.7
+ 0.3 0.3 0.4
+ 0.2 0.2 0.6
+ 0.9 0.05 0.05
+6
+ 0.5 0.5
+ 0.1 0.9
+ 0.3 0.7
+";
+
+    /// Asserts that the junction tree is valid for the model
+    fn assert_junction_tree_is_valid(model: &UaiModel, junction_tree: &JunctionTree<RandomState>) {
+        assert!(check_tree_decomposition(
+            &model.primal_graph(),
+            &junction_tree.tree,
+            &None,
+            &None
+        ));
+        assert_eq!(
+            junction_tree.tree.edge_count() + 1,
+            junction_tree.tree.node_count().max(1)
+        );
+        for edge in junction_tree.tree.edge_indices() {
+            let (first_bag, second_bag) = junction_tree
+                .tree
+                .edge_endpoints(edge)
+                .expect("Edge should exist in the junction tree");
+            let separator: HashSet<NodeIndex, RandomState> = junction_tree.tree[first_bag]
+                .intersection(&junction_tree.tree[second_bag])
+                .cloned()
+                .collect();
+            assert_eq!(junction_tree.tree[edge], separator);
+        }
+        for (factor, bag) in model
+            .factors
+            .iter()
+            .zip(junction_tree.factor_assignment.iter())
+        {
+            assert!(factor
+                .scope
+                .iter()
+                .all(|variable| junction_tree.tree[*bag].contains(&NodeIndex::new(*variable))));
+        }
+    }
+
+    #[test]
+    fn test_junction_tree_of_bayesian_network() {
+        let model = parse_uai(BAYESIAN_NETWORK).expect("Model should be valid");
+        assert_eq!(model.network_type, UaiNetworkType::Bayes);
+        assert_eq!(model.domain_sizes, vec![2, 2, 3, 2]);
+        assert_eq!(model.factors[2].scope, vec![0, 1, 2]);
+        assert_eq!(model.factors[2].table.len(), 12);
+
+        // The parents a and b of c are married
+        let primal_graph = model.primal_graph();
+        assert!(primal_graph.contains_edge(NodeIndex::new(0), NodeIndex::new(1)));
+        assert_eq!(primal_graph.edge_count(), 4);
+
+        for computation_method in crate::tests::COMPUTATION_METHODS {
+            let junction_tree = construct_junction_tree::<_, RandomState>(
+                &model,
+                negative_intersection,
+                computation_method,
+                true,
+                None,
+            );
+            assert_junction_tree_is_valid(&model, &junction_tree);
+
+            let mut output = Vec::new();
+            junction_tree
+                .write_td(model.domain_sizes.len(), &mut output)
+                .expect("Writing to a vector shouldn't fail");
+            let output = String::from_utf8(output).expect("Output should be valid UTF-8");
+            let lines: Vec<&str> = output.lines().collect();
+            assert_eq!(
+                lines[0],
+                format!("s td {} 3 4", junction_tree.tree.node_count())
+            );
+            assert_eq!(
+                lines
+                    .iter()
+                    .filter(|line| line.starts_with("c factor"))
+                    .count(),
+                4
+            );
+            assert!(lines
+                .iter()
+                .any(|line| line.starts_with("b ") && line.split(' ').skip(2).eq(["1", "2", "3"])));
+        }
+    }
+
+    #[test]
+    fn test_junction_trees_of_random_markov_networks() {
+        let mut rng = StdRng::seed_from_u64(50);
+        for (graph, treewidth) in
+            crate::tests::setup_test_graphs_and_partial_k_trees(50, 5, 3, 30, 50)
+        {
+            let domain_sizes: Vec<usize> = (0..graph.node_count())
+                .map(|_| rng.gen_range(2..4))
+                .collect();
+            let factors: Vec<Factor> = graph
+                .edge_indices()
+                .map(|edge| {
+                    let (first_vertex, second_vertex) = graph
+                        .edge_endpoints(edge)
+                        .expect("Edge should exist in the graph");
+                    let scope = vec![first_vertex.index(), second_vertex.index()];
+                    let table = vec![1.0; scope.iter().map(|v| domain_sizes[*v]).product()];
+                    Factor { scope, table }
+                })
+                .collect();
+
+            let mut input = format!(
+                "MARKOV\n{}\n{}\n{}\n",
+                domain_sizes.len(),
+                domain_sizes.iter().join(" "),
+                factors.len()
+            );
+            for factor in factors.iter() {
+                input += &format!("{} {}\n", factor.scope.len(), factor.scope.iter().join(" "));
+            }
+            for factor in factors.iter() {
+                input += &format!(
+                    "{}\n{}\n",
+                    factor.table.len(),
+                    factor.table.iter().join(" ")
+                );
+            }
+            let model = parse_uai(&input).expect("Model should be valid");
+            assert_eq!(model.factors, factors);
+
+            let junction_tree = construct_junction_tree::<_, RandomState>(
+                &model,
+                negative_intersection,
+                SpanningTreeConstructionMethod::FillWhilstMST,
+                true,
+                None,
+            );
+            assert_junction_tree_is_valid(&model, &junction_tree);
+            assert!(crate::find_width_of_tree_decomposition(&junction_tree.tree) >= treewidth);
+        }
+    }
+
+    #[test]
+    fn test_invalid_uai_files() {
+        assert!(matches!(
+            parse_uai("MARKOV\n2\n2 2\n1\n2 0 1\n4\n1 1 1"),
+            Err(UaiError::UnexpectedEndOfFile)
+        ));
+        assert!(matches!(
+            parse_uai("FACTOR\n1\n2\n0\n"),
+            Err(UaiError::InvalidToken(_))
+        ));
+        assert!(matches!(
+            parse_uai("MARKOV\n2\n2 2\n1\n2 0 2\n4\n1 1 1 1"),
+            Err(UaiError::InvalidModel(_))
+        ));
+        assert!(matches!(
+            parse_uai("MARKOV\n2\n2 2\n1\n2 0 1\n3\n1 1 1"),
+            Err(UaiError::InvalidModel(_))
+        ));
+    }
+}
@@ -0,0 +1,357 @@
+use std::{cmp::Ordering, collections::HashSet, fmt::Debug, hash::BuildHasher};
+
+use petgraph::{graph::NodeIndex, Graph, Undirected};
+
+use crate::{
+    compute_treewidth_upper_bound::{
+        compute_treewidth_upper_bound_with_optional_width_bound, BagSizeObjective,
+    },
+    EdgeWeightValue, SpanningTreeConstructionMethod,
+};
+
+/// Objectives for tree decompositions of graphs whose vertices are weighted by domain sizes, e.g.
+/// the variables of a probabilistic model.
+///
+/// WeightedWidth The maximum over all bags of the sum of the logarithms (base 2) of the domain
+/// sizes of the vertices in the bag, i.e. the logarithm of the biggest table
+///
+/// TotalTableSize The sum over all bags of the product of the domain sizes of the vertices in the
+/// bag, i.e. the total size of all tables
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VertexWeightedObjective {
+    WeightedWidth,
+    TotalTableSize,
+}
+
+/// Floating point value of an objective or a vertex weighted edge weight that is totally ordered
+/// (using [f64::total_cmp]) so that it can be used as an edge weight of the clique graph.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ObjectiveValue(pub f64);
+
+impl PartialEq for ObjectiveValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for ObjectiveValue {}
+
+impl PartialOrd for ObjectiveValue {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ObjectiveValue {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl EdgeWeightValue for ObjectiveValue {
+    fn edge_weight_value(&self) -> f64 {
+        self.0
+    }
+}
+
+/// Domain sizes of the vertices of a graph, indexed by the node indices of the graph.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VertexWeights {
+    domain_sizes: Vec<f64>,
+}
+
+impl VertexWeights {
+    /// Uses the node weights of the graph as domain sizes.
+    ///
+    /// Panics: Panics if a domain size isn't positive.
+    pub fn from_node_weights<N: Clone + Into<f64>, E>(graph: &Graph<N, E, Undirected>) -> Self {
+        Self::from_function(graph, |_, weight| weight.clone().into())
+    }
+
+    /// Computes the domain size of each vertex using the weight function which gets the vertex and
+    /// its node weight.
+    ///
+    /// Panics: Panics if a domain size isn't positive.
+    pub fn from_function<N, E>(
+        graph: &Graph<N, E, Undirected>,
+        weight_function: impl Fn(NodeIndex, &N) -> f64,
+    ) -> Self {
+        let domain_sizes: Vec<f64> = graph
+            .node_indices()
+            .map(|vertex| weight_function(vertex, &graph[vertex]))
+            .collect();
+        assert!(
+            domain_sizes.iter().all(|domain_size| *domain_size > 0.0),
+            "Domain sizes should be positive"
+        );
+        VertexWeights { domain_sizes }
+    }
+
+    /// Returns the domain size of the vertex.
+    ///
+    /// Panics: Panics if the vertex isn't a vertex of the graph the weights were computed for.
+    pub fn domain_size(&self, vertex: NodeIndex) -> f64 {
+        *self
+            .domain_sizes
+            .get(vertex.index())
+            .expect("Vertex should be a vertex of the weighted graph")
+    }
+
+    /// Returns the sum of the logarithms (base 2) of the domain sizes of the vertices.
+    pub fn weighted_size<'a>(&self, vertices: impl IntoIterator<Item = &'a NodeIndex>) -> f64 {
+        vertices
+            .into_iter()
+            .map(|vertex| self.domain_size(*vertex).log2())
+            .sum()
+    }
+
+    /// Returns the product of the domain sizes of the vertices.
+    pub fn table_size<'a>(&self, vertices: impl IntoIterator<Item = &'a NodeIndex>) -> f64 {
+        vertices
+            .into_iter()
+            .map(|vertex| self.domain_size(*vertex))
+            .product()
+    }
+
+    /// Returns the value of the objective for the tree decomposition (see
+    /// [VertexWeightedObjective]).
+    pub fn evaluate<O, S>(
+        &self,
+        objective: VertexWeightedObjective,
+        tree_decomposition: &Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    ) -> f64 {
+        match objective {
+            VertexWeightedObjective::WeightedWidth => tree_decomposition
+                .node_weights()
+                .map(|bag| self.weighted_size(bag))
+                .fold(0.0, f64::max),
+            VertexWeightedObjective::TotalTableSize => tree_decomposition
+                .node_weights()
+                .map(|bag| self.table_size(bag))
+                .sum(),
+        }
+    }
+}
+
+/// Edge weight function for the clique graph of a vertex weighted graph, see e.g.
+/// [negative_weighted_intersection][crate::negative_weighted_intersection].
+pub type VertexWeightedEdgeWeightFunction<O, S> =
+    fn(&HashSet<NodeIndex, S>, &HashSet<NodeIndex, S>, &VertexWeights) -> O;
+
+/// Tree decomposition of a vertex weighted graph as returned by
+/// [compute_vertex_weighted_treewidth_upper_bound].
+///
+/// tree_decomposition The tree decomposition
+///
+/// width The (unweighted) width of the tree decomposition
+///
+/// weighted_width The weighted width of the tree decomposition (see
+/// [VertexWeightedObjective::WeightedWidth])
+///
+/// total_table_size The total table size of the tree decomposition (see
+/// [VertexWeightedObjective::TotalTableSize])
+///
+/// is_optimal Whether the graph is chordal and the tree decomposition is a clique tree, which is
+/// optimal for both objectives since every maximal clique needs to be contained in a bag
+#[derive(Clone, Debug)]
+pub struct VertexWeightedTreeDecomposition<O, S> {
+    pub tree_decomposition: Graph<HashSet<NodeIndex, S>, O, Undirected>,
+    pub width: usize,
+    pub weighted_width: f64,
+    pub total_table_size: f64,
+    pub is_optimal: bool,
+}
+
+/// Computes a tree decomposition of a vertex weighted graph like
+/// [compute_treewidth_upper_bound][crate::compute_treewidth_upper_bound] except that the edge
+/// weight function gets the vertex weights (see e.g.
+/// [negative_weighted_intersection][crate::negative_weighted_intersection]) and that
+/// FillWhilstMSTBagSize minimizes the given objective instead of the width, breaking ties by the
+/// width. The other spanning tree construction methods only use the objective through the edge
+/// weight function.
+///
+/// Panics: If check_tree_decomposition_bool is true, panics if the tree decomposition is
+/// incorrect.
+pub fn compute_vertex_weighted_treewidth_upper_bound<
+    N: Clone,
+    E: Clone,
+    O: Clone + Ord + Default + Debug,
+    S: Default + BuildHasher + Clone,
+>(
+    graph: &Graph<N, E, Undirected>,
+    vertex_weights: &VertexWeights,
+    objective: VertexWeightedObjective,
+    edge_weight_function: VertexWeightedEdgeWeightFunction<O, S>,
+    treewidth_computation_method: SpanningTreeConstructionMethod,
+    check_tree_decomposition_bool: bool,
+    clique_bound: Option<usize>,
+) -> VertexWeightedTreeDecomposition<O, S> {
+    let ((_, tree_decomposition, _, _, _, width), is_optimal) =
+        compute_treewidth_upper_bound_with_optional_width_bound(
+            graph,
+            |first_bag, second_bag| edge_weight_function(first_bag, second_bag, vertex_weights),
+            treewidth_computation_method,
+            check_tree_decomposition_bool,
+            clique_bound,
+            None,
+            Some(BagSizeObjective::VertexWeighted(vertex_weights, objective)),
+        )
+        .expect("There is no width bound that could be exceeded");
+
+    VertexWeightedTreeDecomposition {
+        weighted_width: vertex_weights
+            .evaluate(VertexWeightedObjective::WeightedWidth, &tree_decomposition),
+        total_table_size: vertex_weights
+            .evaluate(VertexWeightedObjective::TotalTableSize, &tree_decomposition),
+        tree_decomposition,
+        width,
+        is_optimal,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::RandomState;
+
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+    use crate::{negative_weighted_intersection, weighted_least_difference};
+
+    #[test]
+    fn test_objectives_of_path() {
+        // Path a - b - c with domain sizes 2, 3 and 4
+        let mut graph: Graph<i32, i32, Undirected> = Graph::new_undirected();
+        let a = graph.add_node(2);
+        let b = graph.add_node(3);
+        let c = graph.add_node(4);
+        graph.add_edge(a, b, 0);
+        graph.add_edge(b, c, 0);
+        let vertex_weights = VertexWeights::from_node_weights(&graph);
+
+        let result = compute_vertex_weighted_treewidth_upper_bound::<_, _, _, RandomState>(
+            &graph,
+            &vertex_weights,
+            VertexWeightedObjective::TotalTableSize,
+            negative_weighted_intersection,
+            SpanningTreeConstructionMethod::FillWhilstMSTBagSize,
+            true,
+            None,
+        );
+        assert!(result.is_optimal);
+        assert_eq!(result.width, 1);
+        assert_eq!(result.total_table_size, 6.0 + 12.0);
+        assert!((result.weighted_width - 12f64.log2()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_objectives_of_cycle_with_big_domains() {
+        // Cycle a - b - c - d - a where two opposite vertices have big domains, so the chord between
+        // the other two vertices leads to much smaller tables although both chords give the same
+        // width. Which chord is better only depends on the domain sizes.
+        for (first_domain_size, second_domain_size) in [(100, 2), (2, 100)] {
+            let mut graph: Graph<i32, i32, Undirected> = Graph::new_undirected();
+            let a = graph.add_node(first_domain_size);
+            let b = graph.add_node(second_domain_size);
+            let c = graph.add_node(first_domain_size);
+            let d = graph.add_node(second_domain_size);
+            graph.extend_with_edges([(a, b), (b, c), (c, d), (d, a)]);
+            let vertex_weights = VertexWeights::from_node_weights(&graph);
+            let (first_big_vertex, second_big_vertex) = if first_domain_size > second_domain_size {
+                (a, c)
+            } else {
+                (b, d)
+            };
+
+            for objective in [
+                VertexWeightedObjective::WeightedWidth,
+                VertexWeightedObjective::TotalTableSize,
+            ] {
+                let result = compute_vertex_weighted_treewidth_upper_bound::<_, _, _, RandomState>(
+                    &graph,
+                    &vertex_weights,
+                    objective,
+                    negative_weighted_intersection,
+                    SpanningTreeConstructionMethod::FillWhilstMSTBagSize,
+                    true,
+                    None,
+                );
+                assert!(!result.is_optimal);
+                assert_eq!(result.width, 2);
+                assert!(result
+                    .tree_decomposition
+                    .node_weights()
+                    .all(|bag| !(bag.contains(&first_big_vertex)
+                        && bag.contains(&second_big_vertex))));
+                // Every bag containing both vertices with big domains has a table of size at least
+                // 20000
+                assert!(result.total_table_size < 20000.0);
+                assert!((result.weighted_width - 400f64.log2()).abs() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn test_vertex_weighted_heuristic() {
+        let mut rng = StdRng::seed_from_u64(47);
+        for (graph, _) in crate::tests::setup_test_graphs_and_partial_k_trees(47, 5, 3, 25, 40) {
+            let domain_sizes: Vec<f64> = (0..graph.node_count())
+                .map(|_| rng.gen_range(1..10) as f64)
+                .collect();
+            let vertex_weights =
+                VertexWeights::from_function(&graph, |vertex, _| domain_sizes[vertex.index()]);
+
+            for component in crate::find_connected_components::<
+                HashSet<NodeIndex, RandomState>,
+                _,
+                _,
+                RandomState,
+            >(&graph)
+            {
+                let subgraph = crate::find_induced_subgraph(&graph, &component);
+                let subgraph_weights = VertexWeights::from_function(&subgraph, |_, vertex| {
+                    vertex_weights.domain_size(*vertex)
+                });
+
+                for objective in [
+                    VertexWeightedObjective::WeightedWidth,
+                    VertexWeightedObjective::TotalTableSize,
+                ] {
+                    for computation_method in crate::tests::COMPUTATION_METHODS
+                        .into_iter()
+                        .chain([SpanningTreeConstructionMethod::FillWhilstMSTBagSize])
+                    {
+                        let result =
+                            compute_vertex_weighted_treewidth_upper_bound::<_, _, _, RandomState>(
+                                &subgraph,
+                                &subgraph_weights,
+                                objective,
+                                weighted_least_difference,
+                                computation_method,
+                                true,
+                                None,
+                            );
+                        assert_eq!(
+                            result.width,
+                            crate::find_width_of_tree_decomposition(&result.tree_decomposition)
+                        );
+                        // Both endpoints of every edge are contained in a common bag
+                        for edge in subgraph.edge_indices() {
+                            let (first_vertex, second_vertex) = subgraph
+                                .edge_endpoints(edge)
+                                .expect("Edge should exist in the graph");
+                            let endpoints = [first_vertex, second_vertex];
+                            assert!(
+                                result.weighted_width
+                                    >= subgraph_weights.weighted_size(&endpoints) - 1e-9
+                            );
+                            assert!(
+                                result.total_table_size >= subgraph_weights.table_size(&endpoints)
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+}